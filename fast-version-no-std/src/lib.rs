@@ -0,0 +1,67 @@
+//! Exercises fast-version in a `no_std` + `alloc` crate.
+//!
+//! Building this crate for a target without std, e.g. `thumbv7em-none-eabi`, fails as soon as
+//! fast-version pulls in std somewhere with `--no-default-features --features serde`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use fast_version::{ExtendedVersion, Version, VersionReq, VersionReqSet};
+
+/// Picks the highest of `supported` that satisfies the peer's requirement.
+pub fn negotiate(supported: &[Version<u32>], peer_req: &str) -> Option<Version<u32>> {
+    let req = VersionReq::<u32>::parse(peer_req).ok()?;
+    req.max_satisfying(supported)
+}
+
+/// Whether `version` is accepted by the `||` separated requirement `set`.
+pub fn accepts(set: &str, version: &str) -> bool {
+    match (VersionReqSet::<u32>::parse(set), version.parse::<Version<u32>>()) {
+        (Ok(set), Ok(version)) => set.fits(version),
+        _ => false,
+    }
+}
+
+/// Normalizes a version with pre-release and build metadata.
+pub fn canonical(version: &str) -> Option<String> {
+    version.parse::<ExtendedVersion<u32>>().ok().map(|v| v.to_string())
+}
+
+/// All of `versions` that satisfy `req`.
+pub fn matching(req: &VersionReq<u16>, versions: &[Version<u16>]) -> Vec<Version<u16>> {
+    req.filter(versions)
+}
+
+/// Only compiles if the serde impls are available without std.
+pub fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>() {}
+
+pub fn assert_serde_impls() {
+    assert_serde::<Version<u8>>();
+    assert_serde::<VersionReq<u64>>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_test() {
+        let supported = ["1.0.0", "1.4.2", "2.0.0"].map(|v| v.parse().unwrap());
+        assert_eq!(negotiate(&supported, "^1.2"), Some("1.4.2".parse().unwrap()));
+        assert_eq!(negotiate(&supported, "^3"), None);
+
+        assert!(accepts("1.4.* || >=2.1, <3", "2.2.0"));
+        assert!(!accepts("1.4.* || >=2.1, <3", "2.0.0"));
+
+        assert_eq!(canonical("1.2.3-rc.1+build").as_deref(), Some("1.2.3-rc.1+build"));
+
+        let versions = ["1.0.0", "1.2.0"].map(|v| v.parse().unwrap());
+        assert_eq!(matching(&"^1.1".parse().unwrap(), &versions).len(), 1);
+
+        assert_serde_impls();
+    }
+}
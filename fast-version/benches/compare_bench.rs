@@ -1,5 +1,5 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use fast_version::{version::{VersionNumber, serial_compare}, version_req::serial_version_req, simd::simd_version_req};
+use fast_version::{version::{VersionNumber, serial_compare}, version_req::{serial_version_req, serial_version_req_batch}};
 use rand::{distributions::uniform::SampleUniform, thread_rng, Rng};
 
 
@@ -15,15 +15,16 @@ fn generate_version_part<N: VersionNumber + SampleUniform>() -> N {
     ret_num
 }
 
+// Leaves room for the `- one` and `+ one` in `generate_version_reg`.
 fn generate_valid_version_part<N: VersionNumber + SampleUniform>() -> N {
-    let min = N::min();
+    let zero = N::zero();
     let max = N::max();
     let one = N::one();
 
     let mut rng = thread_rng();
 
-    let lower = min + one;
-    let upper = max - one;
+    let lower = zero + one;
+    let upper = max - one - one;
 
     let ret_num: N = rng.gen_range(lower..=upper);
 
@@ -39,7 +40,7 @@ fn generate_version_reg<N: VersionNumber + SampleUniform>() -> ([N; 3], [N; 3],
 
     let version = [ major, minor, patch ];
     let lower = [ major - one, minor - one, patch - one ]; 
-    let upper = [ major + one, major + one, major + one ];
+    let upper = [ major + one, minor + one, patch + one ];
 
     (version, lower, upper)
 }
@@ -68,11 +69,48 @@ fn perform_compare_simd<N: VersionNumber + SampleUniform>() {
     fast_compare_simd(major, minor, patch);
 }
 
+#[cfg(nightly)]
 fn perform_req_simd<N: VersionNumber + SampleUniform>() {
+    use fast_version::simd::simd_version_req;
+
     let (version, lower, upper) = generate_version_reg::<N>();
     simd_version_req(version, lower, upper);
 }
 
+const BATCH_SIZE: usize = 1024;
+
+fn generate_batch<N: VersionNumber + SampleUniform>() -> (Vec<[[N; 3]; 4]>, [N; 3], [N; 3]) {
+    let (_, lower, upper) = generate_version_reg::<N>();
+    let batch = (0..BATCH_SIZE / 4)
+        .map(|_| core::array::from_fn(|_| generate_version_reg::<N>().0))
+        .collect();
+
+    (batch, lower, upper)
+}
+
+fn perform_batch_serial<N: VersionNumber>(batch: &[[[N; 3]; 4]], lower: [N; 3], upper: [N; 3]) -> u32 {
+    batch.iter().map(|vers| serial_version_req_batch(*vers, lower, upper).count_ones()).sum()
+}
+
+#[cfg(nightly)]
+fn perform_batch_simd<N: VersionNumber>(batch: &[[[N; 3]; 4]], lower: [N; 3], upper: [N; 3]) -> u32 {
+    use fast_version::simd::simd_version_req_batch;
+
+    batch.iter().map(|vers| simd_version_req_batch(*vers, lower, upper).count_ones()).sum()
+}
+
+fn bench_batch<N: VersionNumber + SampleUniform>(c: &mut Criterion, name: &str) {
+    let (batch, lower, upper) = generate_batch::<N>();
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("non-simd", |b| b.iter(|| perform_batch_serial(&batch, lower, upper)));
+
+    #[cfg(nightly)]
+    group.bench_function("simd", |b| b.iter(|| perform_batch_simd(&batch, lower, upper)));
+
+    group.finish();
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let mut u8_group = c.benchmark_group("Compare u8");
     u8_group.bench_function("non-simd", |b| b.iter(perform_compare_serial::<u8>));
@@ -249,5 +287,18 @@ fn version_req_benchmark(c: &mut Criterion) {
     isize_group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark, version_req_benchmark);
+fn batch_benchmark(c: &mut Criterion) {
+    bench_batch::<u8>(c, "VersionReq batch u8");
+    bench_batch::<u16>(c, "VersionReq batch u16");
+    bench_batch::<u32>(c, "VersionReq batch u32");
+    bench_batch::<u64>(c, "VersionReq batch u64");
+    bench_batch::<usize>(c, "VersionReq batch usize");
+    bench_batch::<i8>(c, "VersionReq batch i8");
+    bench_batch::<i16>(c, "VersionReq batch i16");
+    bench_batch::<i32>(c, "VersionReq batch i32");
+    bench_batch::<i64>(c, "VersionReq batch i64");
+    bench_batch::<isize>(c, "VersionReq batch isize");
+}
+
+criterion_group!(benches, criterion_benchmark, version_req_benchmark, batch_benchmark);
 criterion_main!(benches);
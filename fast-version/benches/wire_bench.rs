@@ -0,0 +1,84 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fast_version::{Version, VersionReq};
+
+const VERSIONS: [&str; 4] = ["0.1.0", "1.2.3", "12.40.127", "2024.1.300"];
+
+const VERSION_REQS: [&str; 6] = ["*", "^1.2", "~1.2.3", "=1.2.3", ">=1.0.0, <1.5.0", "1.*"];
+
+fn versions() -> Vec<Version<u64>> {
+    VERSIONS.iter().map(|v| v.parse().unwrap()).collect()
+}
+
+fn version_reqs() -> Vec<VersionReq<u64>> {
+    VERSION_REQS.iter().map(|r| r.parse().unwrap()).collect()
+}
+
+/// Prints the encoded size of every sample in both encodings, criterion only measures time.
+fn print_sizes() {
+    println!("{:<20} {:>6} {:>8}", "value", "wire", "bincode");
+    for v in versions() {
+        println!("{:<20} {:>6} {:>8}", v.to_string(), v.encoded_len(), bincode::serialized_size(&v).unwrap());
+    }
+    for r in version_reqs() {
+        println!("{:<20} {:>6} {:>8}", r.to_string(), r.encoded_len(), bincode::serialized_size(&r).unwrap());
+    }
+}
+
+fn wire_benchmark(c: &mut Criterion) {
+    print_sizes();
+
+    let versions = versions();
+    let version_reqs = version_reqs();
+
+    c.bench_function("wire encode version", |b| b.iter(|| {
+        let mut buf = [0; 64];
+        for v in &versions {
+            black_box(v.encode_into(&mut buf).unwrap());
+        }
+    }));
+
+    c.bench_function("bincode encode version", |b| b.iter(|| {
+        let mut buf = [0; 64];
+        for v in &versions {
+            bincode::serialize_into(&mut buf[..], v).unwrap();
+            black_box(&buf);
+        }
+    }));
+
+    c.bench_function("wire encode version req", |b| b.iter(|| {
+        let mut buf = [0; 64];
+        for r in &version_reqs {
+            black_box(r.encode_into(&mut buf).unwrap());
+        }
+    }));
+
+    c.bench_function("bincode encode version req", |b| b.iter(|| {
+        let mut buf = [0; 64];
+        for r in &version_reqs {
+            bincode::serialize_into(&mut buf[..], r).unwrap();
+            black_box(&buf);
+        }
+    }));
+
+    let wire_reqs: Vec<Vec<u8>> = version_reqs.iter().map(|r| {
+        let mut buf = vec![0; r.encoded_len()];
+        r.encode_into(&mut buf).unwrap();
+        buf
+    }).collect();
+    let bincode_reqs: Vec<Vec<u8>> = version_reqs.iter().map(|r| bincode::serialize(r).unwrap()).collect();
+
+    c.bench_function("wire decode version req", |b| b.iter(|| {
+        for bytes in &wire_reqs {
+            black_box(VersionReq::<u64>::decode(bytes).unwrap());
+        }
+    }));
+
+    c.bench_function("bincode decode version req", |b| b.iter(|| {
+        for bytes in &bincode_reqs {
+            black_box(bincode::deserialize::<VersionReq<u64>>(bytes).unwrap());
+        }
+    }));
+}
+
+criterion_group!(benches, wire_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+//! [`arbitrary::Arbitrary`] implementations for fuzzing.
+//!
+//! Like the `proptest` strategies they only build valid values: components in `[0, N::max())`,
+//! requirements that [`VersionReq::try_from`] accepts.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::generate::{self, REG_TYPE_KINDS};
+use crate::version::VersionNumber;
+use crate::version_req::{VersionRegCompType, VersionRegType};
+use crate::{Version, VersionReq};
+
+fn component<N: VersionNumber>(u: &mut Unstructured<'_>) -> Result<N> {
+    let value = u.int_in_range(0..=generate::top::<N>())?;
+    Ok(generate::component(value))
+}
+
+fn components<N: VersionNumber>(u: &mut Unstructured<'_>) -> Result<[N; 3]> {
+    Ok([component(u)?, component(u)?, component(u)?])
+}
+
+impl<'a, N: VersionNumber> Arbitrary<'a> for Version<N> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let [major, minor, patch] = components(u)?;
+        Ok(Version { major, minor, patch })
+    }
+}
+
+impl<'a, N: VersionNumber> Arbitrary<'a> for VersionRegType<N> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let kind = u.int_in_range(0..=REG_TYPE_KINDS - 1)?;
+        Ok(generate::reg_type(kind, components(u)?))
+    }
+}
+
+impl<'a, N: VersionNumber> Arbitrary<'a> for VersionRegCompType<N> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let first = VersionRegType::arbitrary(u)?;
+        match bool::arbitrary(u)? {
+            true => Ok(generate::comp_type(first, VersionRegType::arbitrary(u)?)),
+            false => Ok(VersionRegCompType::Pure(first)),
+        }
+    }
+}
+
+impl<'a, N: VersionNumber> Arbitrary<'a> for VersionReq<N> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let comp = VersionRegCompType::arbitrary(u)?;
+        Ok(VersionReq::try_from(comp).expect("generated requirements are valid"))
+    }
+}
+
+#[cfg(test)]
+mod arbitrary_tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::{thread_rng, Rng};
+
+    use crate::offset::{OffsetI32, OffsetU8};
+    use crate::version::VersionNumber;
+    use crate::version_req::VersionRegCompType;
+    use crate::{Version, VersionReq};
+
+    fn validity_test<N: VersionNumber + core::fmt::Debug>() {
+        let mut rng = thread_rng();
+        for _ in 0..1000 {
+            let mut data = [0_u8; 128];
+            rng.fill(&mut data[..]);
+            let mut u = Unstructured::new(&data);
+
+            let version = Version::<N>::arbitrary(&mut u).unwrap();
+            assert!(Version::new(version.major, version.minor, version.patch).is_ok(), "{:?}", version);
+
+            let comp = VersionRegCompType::<N>::arbitrary(&mut u).unwrap();
+            assert!(VersionReq::try_from(comp).is_ok(), "{:?}", comp);
+
+            VersionReq::<N>::arbitrary(&mut u).unwrap();
+        }
+    }
+
+    #[test]
+    fn validity_u8_test() {
+        validity_test::<u8>();
+    }
+
+    #[test]
+    fn validity_i32_test() {
+        validity_test::<i32>();
+    }
+
+    #[test]
+    fn validity_u128_test() {
+        validity_test::<u128>();
+    }
+
+    #[test]
+    fn validity_offset_u8_test() {
+        validity_test::<OffsetU8>();
+    }
+
+    #[test]
+    fn validity_offset_i32_test() {
+        validity_test::<OffsetI32>();
+    }
+
+    #[test]
+    fn exhausted_test() {
+        // Running out of data yields zeros rather than an error.
+        let mut u = Unstructured::new(&[]);
+        let req = VersionReq::<u64>::arbitrary(&mut u).unwrap();
+        assert!(req.fits(Version::new(0, 0, 0).unwrap()));
+    }
+}
@@ -0,0 +1,382 @@
+//! Stable SIMD backend on top of `core::arch`, used when `portable_simd` isn't available.
+//!
+//! Every function returns a bitmask over four lanes, like the nightly `PortableSimdElement`
+//! methods. SSE2 is part of the x86_64 baseline, AVX2 (needed for 64 bit lanes) is detected at
+//! runtime if `std` is available and at compile time otherwise. On aarch64 NEON is used. Types
+//! without a vector implementation on the current target fall back to a serial loop.
+
+/// Lane masks for four elements, bit `i` is set if the comparison holds for lane `i`.
+pub(crate) trait ArchLanes: Copy + PartialOrd {
+    /// Whether the masks run on vector instructions on this machine.
+    #[inline]
+    fn available() -> bool {
+        false
+    }
+
+    #[inline]
+    fn eq_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+        serial_mask(a, b, |a, b| a == b)
+    }
+
+    #[inline]
+    fn gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+        serial_mask(a, b, |a, b| a > b)
+    }
+}
+
+#[inline]
+fn serial_mask<T: Copy>(a: [T; 4], b: [T; 4], f: impl Fn(T, T) -> bool) -> u64 {
+    (0..4).fold(0, |mask, i| mask | ((f(a[i], b[i]) as u64) << i))
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))] {
+        use core::arch::x86_64::*;
+
+        #[inline]
+        fn has_avx2() -> bool {
+            cfg_if::cfg_if! {
+                if #[cfg(feature = "std")] {
+                    std::is_x86_feature_detected!("avx2")
+                } else {
+                    cfg!(target_feature = "avx2")
+                }
+            }
+        }
+
+        #[inline]
+        fn load_i8(a: [i8; 4]) -> __m128i {
+            // SAFETY: SSE2 is part of the x86_64 baseline.
+            unsafe { _mm_cvtsi32_si128(i32::from_ne_bytes(a.map(|x| x as u8))) }
+        }
+
+        #[inline]
+        fn load_i16(a: [i16; 4]) -> __m128i {
+            // SAFETY: SSE2 is part of the x86_64 baseline.
+            unsafe { _mm_set_epi16(0, 0, 0, 0, a[3], a[2], a[1], a[0]) }
+        }
+
+        #[inline]
+        fn load_i32(a: [i32; 4]) -> __m128i {
+            // SAFETY: SSE2 is part of the x86_64 baseline.
+            unsafe { _mm_set_epi32(a[3], a[2], a[1], a[0]) }
+        }
+
+        #[inline]
+        fn bits_i8(mask: __m128i) -> u64 {
+            // SAFETY: SSE2 is part of the x86_64 baseline.
+            unsafe { (_mm_movemask_epi8(mask) & 0b1111) as u64 }
+        }
+
+        #[inline]
+        fn bits_i16(mask: __m128i) -> u64 {
+            // SAFETY: SSE2 is part of the x86_64 baseline.
+            unsafe { bits_i8(_mm_packs_epi16(mask, _mm_setzero_si128())) }
+        }
+
+        #[inline]
+        fn bits_i32(mask: __m128i) -> u64 {
+            // SAFETY: SSE2 is part of the x86_64 baseline.
+            unsafe { _mm_movemask_ps(_mm_castsi128_ps(mask)) as u64 }
+        }
+
+        #[target_feature(enable = "avx2")]
+        fn eq_i64_avx2(a: [i64; 4], b: [i64; 4]) -> u64 {
+            let a = _mm256_set_epi64x(a[3], a[2], a[1], a[0]);
+            let b = _mm256_set_epi64x(b[3], b[2], b[1], b[0]);
+            _mm256_movemask_pd(_mm256_castsi256_pd(_mm256_cmpeq_epi64(a, b))) as u64
+        }
+
+        #[target_feature(enable = "avx2")]
+        fn gt_i64_avx2(a: [i64; 4], b: [i64; 4]) -> u64 {
+            let a = _mm256_set_epi64x(a[3], a[2], a[1], a[0]);
+            let b = _mm256_set_epi64x(b[3], b[2], b[1], b[0]);
+            _mm256_movemask_pd(_mm256_castsi256_pd(_mm256_cmpgt_epi64(a, b))) as u64
+        }
+
+        // SSE2 and AVX2 only compare signed lanes, unsigned lanes are compared after flipping the
+        // sign bit.
+
+        macro_rules! impl_sse2_lanes {
+            ($($t:ty, $flip:expr, $load:ident, $bits:ident, $eq:ident, $gt:ident);*) => {
+                $(
+                    impl ArchLanes for $t {
+                        #[inline]
+                        fn available() -> bool {
+                            true
+                        }
+
+                        #[inline]
+                        fn eq_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            // SAFETY: SSE2 is part of the x86_64 baseline.
+                            $bits(unsafe { $eq($load(a.map($flip)), $load(b.map($flip))) })
+                        }
+
+                        #[inline]
+                        fn gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            // SAFETY: SSE2 is part of the x86_64 baseline.
+                            $bits(unsafe { $gt($load(a.map($flip)), $load(b.map($flip))) })
+                        }
+                    }
+                )*
+            };
+        }
+
+        impl_sse2_lanes!(
+            i8, |x: i8| x, load_i8, bits_i8, _mm_cmpeq_epi8, _mm_cmpgt_epi8;
+            u8, |x: u8| (x ^ 0x80) as i8, load_i8, bits_i8, _mm_cmpeq_epi8, _mm_cmpgt_epi8;
+            i16, |x: i16| x, load_i16, bits_i16, _mm_cmpeq_epi16, _mm_cmpgt_epi16;
+            u16, |x: u16| (x ^ 0x8000) as i16, load_i16, bits_i16, _mm_cmpeq_epi16, _mm_cmpgt_epi16;
+            i32, |x: i32| x, load_i32, bits_i32, _mm_cmpeq_epi32, _mm_cmpgt_epi32;
+            u32, |x: u32| (x ^ 0x8000_0000) as i32, load_i32, bits_i32, _mm_cmpeq_epi32, _mm_cmpgt_epi32
+        );
+
+        macro_rules! impl_avx2_lanes {
+            ($($t:ty, $flip:expr);*) => {
+                $(
+                    impl ArchLanes for $t {
+                        #[inline]
+                        fn available() -> bool {
+                            has_avx2()
+                        }
+
+                        #[inline]
+                        fn eq_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            if !has_avx2() {
+                                return serial_mask(a, b, |a, b| a == b);
+                            }
+                            // SAFETY: AVX2 support was checked above.
+                            unsafe { eq_i64_avx2(a.map($flip), b.map($flip)) }
+                        }
+
+                        #[inline]
+                        fn gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            if !has_avx2() {
+                                return serial_mask(a, b, |a, b| a > b);
+                            }
+                            // SAFETY: AVX2 support was checked above.
+                            unsafe { gt_i64_avx2(a.map($flip), b.map($flip)) }
+                        }
+                    }
+                )*
+            };
+        }
+
+        impl_avx2_lanes!(i64, |x: i64| x; u64, |x: u64| (x ^ (1 << 63)) as i64);
+    } else if #[cfg(all(target_arch = "aarch64", target_feature = "neon"))] {
+        use core::arch::aarch64::*;
+
+        #[inline]
+        fn bits_8(mask: uint8x8_t) -> u64 {
+            let weights: [u8; 8] = [1, 2, 4, 8, 0, 0, 0, 0];
+            // SAFETY: NEON is enabled for this target and `weights` holds eight lanes.
+            unsafe { vaddv_u8(vand_u8(mask, vld1_u8(weights.as_ptr()))) as u64 }
+        }
+
+        #[inline]
+        fn bits_16(mask: uint16x4_t) -> u64 {
+            let weights: [u16; 4] = [1, 2, 4, 8];
+            // SAFETY: NEON is enabled for this target and `weights` holds four lanes.
+            unsafe { vaddv_u16(vand_u16(mask, vld1_u16(weights.as_ptr()))) as u64 }
+        }
+
+        #[inline]
+        fn bits_32(mask: uint32x4_t) -> u64 {
+            let weights: [u32; 4] = [1, 2, 4, 8];
+            // SAFETY: NEON is enabled for this target and `weights` holds four lanes.
+            unsafe { vaddvq_u32(vandq_u32(mask, vld1q_u32(weights.as_ptr()))) as u64 }
+        }
+
+        #[inline]
+        fn bits_64(low: uint64x2_t, high: uint64x2_t) -> u64 {
+            let weights: [u64; 2] = [1, 2];
+            // SAFETY: NEON is enabled for this target and `weights` holds two lanes.
+            unsafe {
+                let weights = vld1q_u64(weights.as_ptr());
+                vaddvq_u64(vandq_u64(low, weights)) | (vaddvq_u64(vandq_u64(high, weights)) << 2)
+            }
+        }
+
+        // Eight bit lanes are padded to the 64 bit vector, the padding lanes are masked out.
+
+        macro_rules! impl_neon_lanes {
+            ($($t:ty, $pad:expr, $load:ident, $bits:ident, $eq:ident, $gt:ident);*) => {
+                $(
+                    impl ArchLanes for $t {
+                        #[inline]
+                        fn available() -> bool {
+                            true
+                        }
+
+                        #[inline]
+                        fn eq_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            let (a, b) = ($pad(a), $pad(b));
+                            // SAFETY: NEON is enabled for this target and the arrays fill a whole vector.
+                            $bits(unsafe { $eq($load(a.as_ptr()), $load(b.as_ptr())) })
+                        }
+
+                        #[inline]
+                        fn gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            let (a, b) = ($pad(a), $pad(b));
+                            // SAFETY: NEON is enabled for this target and the arrays fill a whole vector.
+                            $bits(unsafe { $gt($load(a.as_ptr()), $load(b.as_ptr())) })
+                        }
+                    }
+                )*
+            };
+        }
+
+        fn pad_8<T: Copy + Default>(a: [T; 4]) -> [T; 8] {
+            core::array::from_fn(|i| if i < 4 { a[i] } else { T::default() })
+        }
+
+        fn keep<T>(a: [T; 4]) -> [T; 4] {
+            a
+        }
+
+        impl_neon_lanes!(
+            u8, pad_8, vld1_u8, bits_8, vceq_u8, vcgt_u8;
+            i8, pad_8, vld1_s8, bits_8, vceq_s8, vcgt_s8;
+            u16, keep, vld1_u16, bits_16, vceq_u16, vcgt_u16;
+            i16, keep, vld1_s16, bits_16, vceq_s16, vcgt_s16;
+            u32, keep, vld1q_u32, bits_32, vceqq_u32, vcgtq_u32;
+            i32, keep, vld1q_s32, bits_32, vceqq_s32, vcgtq_s32
+        );
+
+        macro_rules! impl_neon_lanes_64 {
+            ($($t:ty, $load:ident, $eq:ident, $gt:ident);*) => {
+                $(
+                    impl ArchLanes for $t {
+                        #[inline]
+                        fn available() -> bool {
+                            true
+                        }
+
+                        #[inline]
+                        fn eq_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            // SAFETY: NEON is enabled for this target and each half holds two lanes.
+                            unsafe {
+                                let low = $eq($load(a.as_ptr()), $load(b.as_ptr()));
+                                let high = $eq($load(a[2..].as_ptr()), $load(b[2..].as_ptr()));
+                                bits_64(low, high)
+                            }
+                        }
+
+                        #[inline]
+                        fn gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            // SAFETY: NEON is enabled for this target and each half holds two lanes.
+                            unsafe {
+                                let low = $gt($load(a.as_ptr()), $load(b.as_ptr()));
+                                let high = $gt($load(a[2..].as_ptr()), $load(b[2..].as_ptr()));
+                                bits_64(low, high)
+                            }
+                        }
+                    }
+                )*
+            };
+        }
+
+        impl_neon_lanes_64!(
+            u64, vld1q_u64, vceqq_u64, vcgtq_u64;
+            i64, vld1q_s64, vceqq_s64, vcgtq_s64
+        );
+    } else {
+        impl ArchLanes for u8 {}
+
+        impl ArchLanes for u16 {}
+
+        impl ArchLanes for u32 {}
+
+        impl ArchLanes for u64 {}
+
+        impl ArchLanes for i8 {}
+
+        impl ArchLanes for i16 {}
+
+        impl ArchLanes for i32 {}
+
+        impl ArchLanes for i64 {}
+    }
+}
+
+// No target has 128 bit lanes.
+
+impl ArchLanes for u128 {}
+
+impl ArchLanes for i128 {}
+
+// `usize` and `isize` share the lanes of the fixed width type of the same size.
+
+macro_rules! impl_pointer_sized_lanes {
+    ($($t:ty => $fixed:ty),*) => {
+        $(
+            impl ArchLanes for $t {
+                #[inline]
+                fn available() -> bool {
+                    <$fixed as ArchLanes>::available()
+                }
+
+                #[inline]
+                fn eq_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                    <$fixed as ArchLanes>::eq_mask(a.map(|x| x as $fixed), b.map(|x| x as $fixed))
+                }
+
+                #[inline]
+                fn gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                    <$fixed as ArchLanes>::gt_mask(a.map(|x| x as $fixed), b.map(|x| x as $fixed))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(target_pointer_width = "64")]
+impl_pointer_sized_lanes!(usize => u64, isize => i64);
+
+#[cfg(target_pointer_width = "32")]
+impl_pointer_sized_lanes!(usize => u32, isize => i32);
+
+#[cfg(target_pointer_width = "16")]
+impl_pointer_sized_lanes!(usize => u16, isize => i16);
+
+#[cfg(test)]
+mod arch_tests {
+    use rand::{distributions::uniform::SampleUniform, thread_rng, Rng};
+
+    use super::{serial_mask, ArchLanes};
+
+    const SAMPLE_SIZE: usize = 1_000;
+
+    /// Compares the vector masks with the serial ones, on the full range and on a few values
+    /// around zero and the extremes, where equal lanes and sign flips happen.
+    fn differential_test<N: ArchLanes + SampleUniform + core::fmt::Debug>(min: N, max: N, small: [N; 4]) {
+        let mut rng = thread_rng();
+        let mut lanes = |full: bool| -> [N; 4] {
+            core::array::from_fn(|_| if full { rng.gen_range(min..=max) } else { small[rng.gen_range(0..4)] })
+        };
+
+        for i in 0..SAMPLE_SIZE {
+            let (a, b) = (lanes(i % 2 == 0), lanes(i % 2 == 0));
+            assert_eq!(N::eq_mask(a, b), serial_mask(a, b, |a, b| a == b), "eq {:?} {:?}", a, b);
+            assert_eq!(N::gt_mask(a, b), serial_mask(a, b, |a, b| a > b), "gt {:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn unsigned_differential_test() {
+        differential_test::<u8>(u8::MIN, u8::MAX, [0, 1, 0x80, u8::MAX]);
+        differential_test::<u16>(u16::MIN, u16::MAX, [0, 1, 0x8000, u16::MAX]);
+        differential_test::<u32>(u32::MIN, u32::MAX, [0, 1, 0x8000_0000, u32::MAX]);
+        differential_test::<u64>(u64::MIN, u64::MAX, [0, 1, 1 << 63, u64::MAX]);
+        differential_test::<usize>(usize::MIN, usize::MAX, [0, 1, usize::MAX / 2 + 1, usize::MAX]);
+    }
+
+    #[test]
+    fn signed_differential_test() {
+        differential_test::<i8>(i8::MIN, i8::MAX, [i8::MIN, -1, 0, i8::MAX]);
+        differential_test::<i16>(i16::MIN, i16::MAX, [i16::MIN, -1, 0, i16::MAX]);
+        differential_test::<i32>(i32::MIN, i32::MAX, [i32::MIN, -1, 0, i32::MAX]);
+        differential_test::<i64>(i64::MIN, i64::MAX, [i64::MIN, -1, 0, i64::MAX]);
+        differential_test::<isize>(isize::MIN, isize::MAX, [isize::MIN, -1, 0, isize::MAX]);
+    }
+}
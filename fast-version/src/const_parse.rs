@@ -0,0 +1,551 @@
+//! `const fn` parsers behind [`crate::version!`] and [`crate::version_req!`].
+//!
+//! Trait methods can't be called in a `const fn`, so this duplicates the generic parser in
+//! `parse.rs` for `u64`, just like `Version::<u64>::const_try_new` duplicates `Version::new`.
+//! Both parsers accept the same inputs and report the same errors.
+
+use crate::parse::{ParseVersionError, ParseVersionErrorKind};
+use crate::version::NewVersionError;
+use crate::version_req::{VersionComperatorLower, VersionComperatorUpper, VersionRegError};
+use crate::{Version, VersionReq};
+
+const MAX: u64 = u64::MAX;
+
+/// `?` isn't available in `const fn`.
+macro_rules! tri {
+    ($e:expr) => {
+        match $e {
+            Ok(value) => value,
+            Err(e) => return Err(e),
+        }
+    };
+}
+
+struct ConstCursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ConstCursor<'a> {
+    const fn new(input: &'a str) -> Self {
+        Self { bytes: input.as_bytes(), position: 0 }
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    /// Decodes the character at the current position, the input is valid UTF-8.
+    const fn peek(&self) -> Option<char> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let first = self.bytes[self.position];
+        let (len, mut code) = if first < 0x80 {
+            (1, first as u32)
+        } else if first >> 5 == 0b110 {
+            (2, (first & 0x1f) as u32)
+        } else if first >> 4 == 0b1110 {
+            (3, (first & 0x0f) as u32)
+        } else {
+            (4, (first & 0x07) as u32)
+        };
+
+        let mut i = 1;
+        while i < len {
+            code = (code << 6) | (self.bytes[self.position + i] & 0x3f) as u32;
+            i += 1;
+        }
+        char::from_u32(code)
+    }
+
+    const fn eat(&mut self, expected: u8) -> bool {
+        if !self.is_empty() && self.bytes[self.position] == expected {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    const fn skip_whitespace(&mut self) {
+        while !self.is_empty() && self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    const fn eat_wildcard(&mut self) -> bool {
+        self.eat(b'*') || self.eat(b'x') || self.eat(b'X')
+    }
+
+    const fn error(&self, kind: ParseVersionErrorKind) -> ParseVersionError {
+        ParseVersionError { kind, position: self.position }
+    }
+
+    const fn unexpected(&self) -> ParseVersionError {
+        match self.peek() {
+            Some(c) => self.error(ParseVersionErrorKind::UnexpectedCharacter(c)),
+            None => self.error(ParseVersionErrorKind::UnexpectedEnd),
+        }
+    }
+
+    const fn expect(&mut self, expected: u8) -> Result<(), ParseVersionError> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+        Err(self.unexpected())
+    }
+
+    const fn number(&mut self) -> Result<u64, ParseVersionError> {
+        let start = self.position;
+        let mut end = start;
+        while end < self.bytes.len() && self.bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+
+        if end == start {
+            return Err(self.unexpected());
+        }
+
+        if self.bytes[start] == b'0' && end - start > 1 {
+            return Err(self.error(ParseVersionErrorKind::LeadingZero));
+        }
+
+        let mut ret: u64 = 0;
+        let mut i = start;
+        while i < end {
+            let digit = (self.bytes[i] - b'0') as u64;
+            ret = match ret.checked_mul(10) {
+                Some(shifted) => match shifted.checked_add(digit) {
+                    Some(value) => value,
+                    None => return Err(self.error(ParseVersionErrorKind::NumberToBig)),
+                },
+                None => return Err(self.error(ParseVersionErrorKind::NumberToBig)),
+            };
+            i += 1;
+        }
+
+        self.position = end;
+        Ok(ret)
+    }
+}
+
+const fn version_error(error: NewVersionError, positions: [usize; 3]) -> ParseVersionError {
+    let position = match error {
+        NewVersionError::MajorIsMax | NewVersionError::MajorIsMin => positions[0],
+        NewVersionError::MinorIsMax | NewVersionError::MinorIsMin => positions[1],
+        NewVersionError::PatchIsMax | NewVersionError::PatchIsMin => positions[2],
+    };
+    ParseVersionError { kind: ParseVersionErrorKind::InvalidVersion(error), position }
+}
+
+pub(crate) const fn const_parse_version(input: &str) -> Result<Version<u64>, ParseVersionError> {
+    let mut cursor = ConstCursor::new(input);
+    if cursor.is_empty() {
+        return Err(cursor.error(ParseVersionErrorKind::Empty));
+    }
+
+    let major_position = cursor.position;
+    let major = tri!(cursor.number());
+    tri!(cursor.expect(b'.'));
+    let minor_position = cursor.position;
+    let minor = tri!(cursor.number());
+    tri!(cursor.expect(b'.'));
+    let patch_position = cursor.position;
+    let patch = tri!(cursor.number());
+
+    if !cursor.is_empty() {
+        return Err(cursor.unexpected());
+    }
+
+    match Version::<u64>::const_try_new(major, minor, patch) {
+        Ok(version) => Ok(version),
+        Err(e) => Err(version_error(e, [major_position, minor_position, patch_position])),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Exact,
+    Greater,
+    GreaterEq,
+    Lesser,
+    LesserEq,
+    Caret,
+    Tilde,
+}
+
+struct Partial {
+    parts: [u64; 3],
+    len: usize,
+    wildcard: bool,
+}
+
+const fn parse_operator(cursor: &mut ConstCursor<'_>) -> Option<Operator> {
+    let op = if cursor.eat(b'=') {
+        Operator::Exact
+    } else if cursor.eat(b'>') {
+        if cursor.eat(b'=') { Operator::GreaterEq } else { Operator::Greater }
+    } else if cursor.eat(b'<') {
+        if cursor.eat(b'=') { Operator::LesserEq } else { Operator::Lesser }
+    } else if cursor.eat(b'^') {
+        Operator::Caret
+    } else if cursor.eat(b'~') {
+        Operator::Tilde
+    } else {
+        return None;
+    };
+    Some(op)
+}
+
+const fn parse_partial(cursor: &mut ConstCursor<'_>) -> Result<Partial, ParseVersionError> {
+    let mut ret = Partial { parts: [1; 3], len: 0, wildcard: false };
+
+    if cursor.eat_wildcard() {
+        ret.wildcard = true;
+        return Ok(ret);
+    }
+
+    ret.parts[0] = tri!(cursor.number());
+    ret.len = 1;
+    while ret.len < 3 && cursor.eat(b'.') {
+        if cursor.eat_wildcard() {
+            ret.wildcard = true;
+            break;
+        }
+        ret.parts[ret.len] = tri!(cursor.number());
+        ret.len += 1;
+    }
+
+    Ok(ret)
+}
+
+const fn validate(parts: [u64; 3], len: usize) -> Result<(), VersionRegError> {
+    let mut i = 0;
+    while i < len {
+        if parts[i] == MAX {
+            return Err(VersionRegError::MaxValueNotAllowed);
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+const fn set_lower(req: &mut VersionReq<u64>, [major, minor, patch]: [u64; 3], comperator: VersionComperatorLower) {
+    req.major_lower = major;
+    req.minor_lower = minor;
+    req.patch_lower = patch;
+    req.comperator_lower = comperator;
+}
+
+const fn set_upper(req: &mut VersionReq<u64>, [major, minor, patch]: [u64; 3], comperator: VersionComperatorUpper) {
+    req.major_upper = major;
+    req.minor_upper = minor;
+    req.patch_upper = patch;
+    req.comperator_higher = comperator;
+}
+
+/// `>=` for the first `len` components, mirrors `VersionReq::apply_greater_eq_*`.
+const fn apply_greater_eq(req: &mut VersionReq<u64>, [major, minor, patch]: [u64; 3], len: usize) -> Result<(), VersionRegError> {
+    tri!(validate([major, minor, patch], len));
+    match len {
+        1 => set_lower(req, [major, 0, 0], VersionComperatorLower::GreaterEqMajor),
+        2 => set_lower(req, [major, minor, 0], VersionComperatorLower::GreaterEqMinor),
+        _ => set_lower(req, [major, minor, patch], VersionComperatorLower::GreaterEqPatch),
+    }
+    Ok(())
+}
+
+/// `>` for the first `len` components, mirrors `VersionReq::apply_greater_*`.
+const fn apply_greater(req: &mut VersionReq<u64>, [major, minor, patch]: [u64; 3], len: usize) -> Result<(), VersionRegError> {
+    tri!(validate([major, minor, patch], len));
+    let top = MAX - 1;
+    if major == top && (len < 2 || minor == top) && (len < 3 || patch == top) {
+        return Err(VersionRegError::Unsatisfiable);
+    }
+    match len {
+        1 => set_lower(req, [major + 1, 0, 0], VersionComperatorLower::GreaterMajor),
+        2 => set_lower(req, [major, minor + 1, 0], VersionComperatorLower::GreaterMinor),
+        _ => set_lower(req, [major, minor, patch + 1], VersionComperatorLower::GreaterPatch),
+    }
+    Ok(())
+}
+
+/// `<=` for the first `len` components, mirrors `VersionReq::apply_lesser_eq_*`.
+const fn apply_lesser_eq(req: &mut VersionReq<u64>, [major, minor, patch]: [u64; 3], len: usize) -> Result<(), VersionRegError> {
+    tri!(validate([major, minor, patch], len));
+    match len {
+        1 => set_upper(req, [major, MAX, MAX], VersionComperatorUpper::LesserEqMajor),
+        2 => set_upper(req, [major, minor, MAX], VersionComperatorUpper::LesserEqMinor),
+        _ => set_upper(req, [major, minor, patch], VersionComperatorUpper::LesserEqPatch),
+    }
+    Ok(())
+}
+
+/// `<` for the first `len` components, borrowing like `VersionReq::apply_lesser_*`.
+const fn apply_lesser(req: &mut VersionReq<u64>, [major, minor, patch]: [u64; 3], len: usize) -> Result<(), VersionRegError> {
+    tri!(validate([major, minor, patch], len));
+    if len >= 3 && patch != 0 {
+        set_upper(req, [major, minor, patch - 1], VersionComperatorUpper::LesserPatch);
+    } else if len >= 2 && minor != 0 {
+        set_upper(req, [major, minor - 1, MAX], VersionComperatorUpper::LesserMinor);
+    } else if major != 0 {
+        set_upper(req, [major - 1, MAX, MAX], VersionComperatorUpper::LesserMajor);
+    } else {
+        return Err(VersionRegError::Unsatisfiable);
+    }
+    Ok(())
+}
+
+/// Upper bound of a caret requirement, mirrors `caret_upper` in `parse.rs`.
+const fn apply_caret_upper(req: &mut VersionReq<u64>, parts: [u64; 3], len: usize) -> Result<(), VersionRegError> {
+    if parts[0] != 0 || len == 1 {
+        apply_lesser_eq(req, parts, 1)
+    } else if parts[1] != 0 || len == 2 {
+        apply_lesser_eq(req, parts, 2)
+    } else {
+        apply_lesser_eq(req, parts, 3)
+    }
+}
+
+/// Mirrors `comparator_types` and `apply_types` in `parse.rs`.
+const fn apply_comparator(req: &mut VersionReq<u64>, op: Operator, p: &Partial) -> Result<(), VersionRegError> {
+    let parts = p.parts;
+    let len = p.len;
+    if len == 0 {
+        return Ok(());
+    }
+
+    match op {
+        Operator::Exact if len == 3 => {
+            tri!(validate(parts, 3));
+            set_lower(req, parts, VersionComperatorLower::Strict);
+            set_upper(req, parts, VersionComperatorUpper::LesserEqPatch);
+            Ok(())
+        }
+        Operator::Exact => {
+            tri!(apply_greater_eq(req, parts, len));
+            apply_lesser_eq(req, parts, len)
+        }
+        Operator::Greater => apply_greater(req, parts, len),
+        Operator::GreaterEq => apply_greater_eq(req, parts, len),
+        Operator::Lesser => apply_lesser(req, parts, len),
+        Operator::LesserEq => apply_lesser_eq(req, parts, len),
+        Operator::Caret => {
+            tri!(apply_greater_eq(req, parts, len));
+            apply_caret_upper(req, parts, len)
+        }
+        Operator::Tilde => {
+            tri!(apply_greater_eq(req, parts, len));
+            apply_lesser_eq(req, parts, if len == 1 { 1 } else { 2 })
+        }
+    }
+}
+
+const fn any() -> VersionReq<u64> {
+    VersionReq {
+        comperator_lower: VersionComperatorLower::None,
+        comperator_higher: VersionComperatorUpper::None,
+        major_lower: 0,
+        minor_lower: 0,
+        patch_lower: 0,
+        major_upper: MAX,
+        minor_upper: MAX,
+        patch_upper: MAX,
+    }
+}
+
+/// Lexicographic `a < b`.
+const fn less(a: [u64; 3], b: [u64; 3]) -> bool {
+    let mut i = 0;
+    while i < 3 {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Mirrors `VersionReq::normalized_bounds(..).is_none()`.
+const fn is_empty(req: &VersionReq<u64>) -> bool {
+    // Carries `max` in the lower bound into the next component.
+    let mut lower = [req.major_lower, req.minor_lower, req.patch_lower];
+    let mut i = 2;
+    while i > 0 {
+        if lower[i] == MAX {
+            lower[i] = 0;
+            lower[i - 1] += 1;
+            let mut j = i + 1;
+            while j < 3 {
+                lower[j] = 0;
+                j += 1;
+            }
+        }
+        i -= 1;
+    }
+    if lower[0] == MAX {
+        return true;
+    }
+
+    // Open upper components accept everything up to `MAX - 1`.
+    let mut upper = [req.major_upper, req.minor_upper, req.patch_upper];
+    let mut open = false;
+    let mut i = 0;
+    while i < 3 {
+        open = open || upper[i] == MAX;
+        if open {
+            upper[i] = MAX - 1;
+        }
+        i += 1;
+    }
+
+    less(upper, lower)
+}
+
+/// Mirrors `VersionReq::intersect`.
+const fn intersect(req: &mut VersionReq<u64>, other: &VersionReq<u64>) -> Result<(), VersionRegError> {
+    let self_strict = matches!(req.comperator_lower, VersionComperatorLower::Strict);
+    let other_strict = matches!(other.comperator_lower, VersionComperatorLower::Strict);
+    if self_strict || other_strict {
+        let self_any = matches!(req.comperator_lower, VersionComperatorLower::None) && matches!(req.comperator_higher, VersionComperatorUpper::None);
+        if !self_any {
+            return Err(VersionRegError::StrictNotAllowedInComposite);
+        }
+        *req = *other;
+        return Ok(());
+    }
+
+    let self_lower = [req.major_lower, req.minor_lower, req.patch_lower];
+    let other_lower = [other.major_lower, other.minor_lower, other.patch_lower];
+    let lower_unset = matches!(req.comperator_lower, VersionComperatorLower::None);
+    let other_lower_set = !matches!(other.comperator_lower, VersionComperatorLower::None);
+    if other_lower_set && (lower_unset || less(self_lower, other_lower)) {
+        set_lower(req, other_lower, other.comperator_lower);
+    }
+
+    let self_upper = [req.major_upper, req.minor_upper, req.patch_upper];
+    let other_upper = [other.major_upper, other.minor_upper, other.patch_upper];
+    let upper_unset = matches!(req.comperator_higher, VersionComperatorUpper::None);
+    let other_upper_set = !matches!(other.comperator_higher, VersionComperatorUpper::None);
+    if other_upper_set && (upper_unset || less(other_upper, self_upper)) {
+        set_upper(req, other_upper, other.comperator_higher);
+    }
+
+    if is_empty(req) {
+        return Err(VersionRegError::EmptyRange);
+    }
+    Ok(())
+}
+
+const fn parse_comparator(cursor: &mut ConstCursor<'_>) -> Result<VersionReq<u64>, ParseVersionError> {
+    let op = parse_operator(cursor);
+    cursor.skip_whitespace();
+
+    let start = cursor.position;
+    let partial = tri!(parse_partial(cursor));
+
+    // A bare version is a caret requirement unless it contains a wildcard, like in cargo.
+    let op = match op {
+        Some(op) => op,
+        None if partial.wildcard => Operator::Exact,
+        None => Operator::Caret,
+    };
+
+    if partial.len == 0 && !matches!(op, Operator::Exact) {
+        return Err(ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('*'), position: start });
+    }
+
+    let mut ret = any();
+    match apply_comparator(&mut ret, op, &partial) {
+        Ok(()) => Ok(ret),
+        Err(e) => Err(ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(e), position: start }),
+    }
+}
+
+pub(crate) const fn const_parse_version_req(input: &str) -> Result<VersionReq<u64>, ParseVersionError> {
+    let mut cursor = ConstCursor::new(input);
+    cursor.skip_whitespace();
+    if cursor.is_empty() {
+        return Err(cursor.error(ParseVersionErrorKind::Empty));
+    }
+
+    let mut ret = any();
+    loop {
+        cursor.skip_whitespace();
+        let start = cursor.position;
+        let comparator = tri!(parse_comparator(&mut cursor));
+        if let Err(e) = intersect(&mut ret, &comparator) {
+            return Err(ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(e), position: start });
+        }
+
+        cursor.skip_whitespace();
+        if cursor.is_empty() {
+            break;
+        }
+        tri!(cursor.expect(b','));
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod const_parse_tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    use rand::{thread_rng, Rng};
+
+    use crate::{Version, VersionReq};
+
+    const VERSION: Version<u64> = crate::version!("1.2.3");
+    const VERSION_REQ: VersionReq<u64> = crate::version_req!(">=1.1, <2");
+
+    #[test]
+    fn macro_test() {
+        assert_eq!(VERSION, Version::new(1, 2, 3).unwrap());
+        assert_eq!(VERSION_REQ, VersionReq::parse("^1.1").unwrap());
+        assert_eq!(VERSION_REQ.to_string(), ">=1.1, <2");
+        assert_eq!(crate::version_req!("=1.1.1").to_string(), "=1.1.1");
+    }
+
+    fn assert_same_req(input: &str) {
+        let runtime = VersionReq::<u64>::parse(input);
+        let constant = VersionReq::<u64>::const_parse(input);
+        match (runtime, constant) {
+            (Ok(a), Ok(b)) => {
+                assert_eq!((a.lower(), a.upper()), (b.lower(), b.upper()), "{}", input);
+                assert_eq!(a.to_string(), b.to_string(), "{}", input);
+            }
+            (a, b) => assert_eq!(a.err(), b.err(), "{}", input),
+        }
+    }
+
+    fn assert_same_version(input: &str) {
+        assert_eq!(input.parse::<Version<u64>>(), Version::<u64>::const_parse(input), "{}", input);
+    }
+
+    #[test]
+    fn fixed_inputs_test() {
+        let versions = ["1.2.3", "0.0.0", "", "1.2", "1.2.3.4", "01.2.3", "1.2.18446744073709551615", "1.2.18446744073709551616", "1.ä.3", " 1.2.3"];
+        versions.iter().for_each(|v| assert_same_version(v));
+
+        let reqs = [
+            "*", "1.*", "1.2.x", "^1.2", "^0.0", "~1.2.3", "=1.2.3", "=1.2", ">1", "<1.0", "<0.0.0", "<=2",
+            ">=1.2.3, <2", "=1.2.3, >1", "1.2 || 2", ">", "^*", "^1.2.", " ^1 , <1.5 ", "^18446744073709551615", "1.é",
+        ];
+        reqs.iter().for_each(|r| assert_same_req(r));
+    }
+
+    /// Random requirements from a small alphabet agree with the runtime parser.
+    #[test]
+    fn differential_test() {
+        const TOKENS: [&str; 16] = ["=", ">", ">=", "<", "<=", "~", "^", "0", "1", "2", "10", ".", ".", "*", ",", " "];
+        let mut rng = thread_rng();
+        for _ in 0..10_000 {
+            let input: String = (0..rng.gen_range(0..10)).map(|_| TOKENS[rng.gen_range(0..TOKENS.len())]).collect::<Vec<_>>().concat();
+            assert_same_req(&input);
+            assert_same_version(&input);
+        }
+    }
+}
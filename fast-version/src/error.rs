@@ -0,0 +1,135 @@
+//! [`VersionError`] wraps the error of every fallible operation of this crate, so callers that
+//! mix them can use a single error type with `?`.
+
+use core::fmt;
+
+use crate::parse::ParseVersionError;
+#[cfg(feature = "semver")]
+use crate::semver::SemverConversionError;
+use crate::version::NewVersionError;
+use crate::version_req::VersionRegError;
+use crate::wire::WireError;
+
+/// Any error of this crate. The wrapped error is the [`source`](core::error::Error::source), the
+/// `Display` output only names the operation that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionError {
+    /// See [`Version::new`](crate::Version::new).
+    Version(NewVersionError),
+    /// See [`VersionReq::try_from`](crate::VersionReq::try_from).
+    Requirement(VersionRegError),
+    /// Parsing a version or requirement from text failed.
+    Parse(ParseVersionError),
+    /// Encoding or decoding the wire format failed.
+    Wire(WireError),
+    /// Converting from the `semver` crate failed.
+    #[cfg(feature = "semver")]
+    Semver(SemverConversionError),
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VersionError::Version(_) => "invalid version",
+            VersionError::Requirement(_) => "invalid version requirement",
+            VersionError::Parse(_) => "failed to parse version",
+            VersionError::Wire(_) => "failed to encode or decode version",
+            #[cfg(feature = "semver")]
+            VersionError::Semver(_) => "failed to convert semver version",
+        })
+    }
+}
+
+impl core::error::Error for VersionError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            VersionError::Version(e) => Some(e),
+            VersionError::Requirement(e) => Some(e),
+            VersionError::Parse(e) => Some(e),
+            VersionError::Wire(e) => Some(e),
+            #[cfg(feature = "semver")]
+            VersionError::Semver(e) => Some(e),
+        }
+    }
+}
+
+impl From<NewVersionError> for VersionError {
+    fn from(e: NewVersionError) -> Self {
+        VersionError::Version(e)
+    }
+}
+
+impl From<VersionRegError> for VersionError {
+    fn from(e: VersionRegError) -> Self {
+        VersionError::Requirement(e)
+    }
+}
+
+impl From<ParseVersionError> for VersionError {
+    fn from(e: ParseVersionError) -> Self {
+        VersionError::Parse(e)
+    }
+}
+
+impl From<WireError> for VersionError {
+    fn from(e: WireError) -> Self {
+        VersionError::Wire(e)
+    }
+}
+
+#[cfg(feature = "semver")]
+impl From<SemverConversionError> for VersionError {
+    fn from(e: SemverConversionError) -> Self {
+        VersionError::Semver(e)
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::error::Error;
+
+    use super::VersionError;
+    use crate::parse::{ParseVersionError, ParseVersionErrorKind};
+    use crate::version::NewVersionError;
+    use crate::version_req::{VersionRegCompType, VersionRegType, VersionRegError};
+    use crate::{Version, VersionReq};
+
+    /// The messages of `error` and all of its sources.
+    fn chain(error: &dyn Error) -> Vec<String> {
+        let mut ret = Vec::new();
+        let mut current = Some(error);
+        while let Some(e) = current {
+            ret.push(e.to_string());
+            current = e.source();
+        }
+        ret
+    }
+
+    fn parse(input: &str) -> Result<Version<u8>, VersionError> {
+        Ok(input.parse::<Version<u8>>()?)
+    }
+
+    #[test]
+    fn chain_test() {
+        let error = parse("1.255.0").unwrap_err();
+        assert_eq!(error, VersionError::Parse(ParseVersionError { kind: ParseVersionErrorKind::InvalidVersion(NewVersionError::MinorIsMax), position: 2 }));
+        assert_eq!(chain(&error), [
+            "failed to parse version",
+            "invalid version component at position 2",
+            "minor component is the reserved maximum value",
+        ]);
+
+        let error = VersionError::from(Version::<i8>::new(1, -1, 0).unwrap_err());
+        assert_eq!(chain(&error), ["invalid version", "minor component is negative"]);
+
+        let range = VersionRegCompType::Composite { lower: VersionRegType::GreaterEqMajor { major: 3 }, higher: VersionRegType::LesserMajor { major: 2 } };
+        let error = VersionError::from(VersionReq::<u64>::try_from(range).unwrap_err());
+        assert_eq!(error, VersionError::Requirement(VersionRegError::EmptyRange));
+        assert_eq!(chain(&error), ["invalid version requirement", "lower bound of the range lies above its upper bound"]);
+
+        let error = VersionError::from(Version::<u64>::decode(&[]).unwrap_err());
+        assert_eq!(chain(&error), ["failed to encode or decode version", "unexpected end of input at position 0"]);
+    }
+}
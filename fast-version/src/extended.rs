@@ -0,0 +1,533 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::parse::{parse_build, parse_extended_version, parse_extended_version_req, parse_prerelease, Cursor, ParseVersionError};
+use crate::version::VersionNumber;
+use crate::version_req::{VersionComperatorLower, VersionComperatorUpper, VersionRegError};
+use crate::{Version, VersionReq};
+
+/// A single dot separated pre-release identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+/// Numeric identifiers have lower precedence than alphanumeric ones, alphanumeric identifiers are
+/// compared in ASCII sort order.
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::AlphaNumeric(s) => f.write_str(s),
+        }
+    }
+}
+
+/// The pre-release part of a version, e.g. the `rc.1` in `1.2.0-rc.1`.
+///
+/// An empty pre-release marks a normal release and therefore orders *after* every non-empty one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Prerelease {
+    pub(crate) identifiers: Vec<Identifier>,
+}
+
+impl Prerelease {
+    pub fn is_empty(&self) -> bool {
+        self.identifiers.is_empty()
+    }
+
+    pub fn identifiers(&self) -> &[Identifier] {
+        &self.identifiers
+    }
+}
+
+impl Ord for Prerelease {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.identifiers.cmp(&other.identifiers),
+        }
+    }
+}
+
+impl PartialOrd for Prerelease {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Prerelease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, identifier) in self.identifiers.iter().enumerate() {
+            if i != 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", identifier)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Prerelease {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        if cursor.is_empty() {
+            return Ok(Self::default());
+        }
+        let ret = parse_prerelease(&mut cursor)?;
+        if !cursor.is_empty() {
+            return Err(cursor.unexpected());
+        }
+        Ok(ret)
+    }
+}
+
+/// The build metadata of a version, e.g. the `build.5` in `1.2.0+build.5`.
+///
+/// Build metadata doesn't take part in precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BuildMetadata {
+    pub(crate) identifier: String,
+}
+
+impl BuildMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.identifier.is_empty()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.identifier
+    }
+}
+
+impl fmt::Display for BuildMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.identifier)
+    }
+}
+
+impl FromStr for BuildMetadata {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        if cursor.is_empty() {
+            return Ok(Self::default());
+        }
+        let ret = parse_build(&mut cursor)?;
+        if !cursor.is_empty() {
+            return Err(cursor.unexpected());
+        }
+        Ok(ret)
+    }
+}
+
+/// A [`Version`] with pre-release identifiers and build metadata, e.g. `1.2.0-rc.1+build.5`.
+///
+/// The plain numeric [`Version`] stays `Copy` and allocation free, this type is only needed where
+/// pre-releases actually show up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedVersion<N: VersionNumber> {
+    pub version: Version<N>,
+    pub pre: Prerelease,
+    pub build: BuildMetadata,
+}
+
+impl<N: VersionNumber> ExtendedVersion<N> {
+    pub fn new(version: Version<N>, pre: Prerelease, build: BuildMetadata) -> Self {
+        Self { version, pre, build }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// Compares by semver precedence, ignoring build metadata.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        self.version.cmp(&other.version).then_with(|| self.pre.cmp(&other.pre))
+    }
+}
+
+impl<N: VersionNumber> From<Version<N>> for ExtendedVersion<N> {
+    fn from(version: Version<N>) -> Self {
+        Self { version, pre: Prerelease::default(), build: BuildMetadata::default() }
+    }
+}
+
+impl<N: VersionNumber> Eq for ExtendedVersion<N> {}
+
+/// Orders by semver precedence and breaks ties on the build metadata, so that the ordering agrees
+/// with `==`.
+impl<N: VersionNumber> Ord for ExtendedVersion<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other).then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl<N: VersionNumber> PartialOrd for ExtendedVersion<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N: VersionNumber> core::hash::Hash for ExtendedVersion<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.version.hash(state);
+        self.pre.hash(state);
+        self.build.hash(state);
+    }
+}
+
+impl<N: VersionNumber> fmt::Display for ExtendedVersion<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.version)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: VersionNumber> FromStr for ExtendedVersion<N> {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_extended_version(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PrereleaseBound {
+    pub(crate) pre: Prerelease,
+    pub(crate) inclusive: bool,
+}
+
+/// A [`VersionReq`] whose bounds may name a pre-release, e.g. `>=1.2.0-rc.1, <2`.
+///
+/// Like in cargo, a pre-release version only matches if one of the bounds names a pre-release of
+/// the same `major.minor.patch`, so `>=1.2.0-rc.1` matches `1.2.0-rc.2` but not `1.3.0-rc.1`.
+#[derive(Debug, Clone)]
+pub struct ExtendedVersionReq<N: VersionNumber> {
+    pub(crate) req: VersionReq<N>,
+    pub(crate) lower_pre: Option<PrereleaseBound>,
+    pub(crate) upper_pre: Option<PrereleaseBound>,
+}
+
+/// Compares `(version, pre)` against a bound given as `(core, pre)`.
+fn cmp_bound<N: VersionNumber>(version: [N; 3], pre: &Prerelease, core: [N; 3], bound: Option<&PrereleaseBound>) -> Ordering {
+    let ordering = version.partial_cmp(&core).unwrap_or(Ordering::Equal);
+    if ordering.is_ne() {
+        return ordering;
+    }
+    match bound {
+        Some(bound) => pre.cmp(&bound.pre),
+        None => pre.cmp(&Prerelease::default()),
+    }
+}
+
+impl<N: VersionNumber> ExtendedVersionReq<N> {
+    /// Parses a cargo style requirement whose comparators may name pre-releases.
+    pub fn parse(input: &str) -> Result<Self, ParseVersionError> {
+        parse_extended_version_req(input)
+    }
+
+    /// The requirement without its pre-release bounds.
+    pub fn version_req(&self) -> &VersionReq<N> {
+        &self.req
+    }
+
+    fn names_prerelease(&self) -> bool {
+        self.lower_pre.is_some() || self.upper_pre.is_some()
+    }
+
+    pub fn fits(&self, version: &ExtendedVersion<N>) -> bool {
+        if !self.names_prerelease() {
+            return self.req.fits_extended(version);
+        }
+
+        let v = &version.version;
+        let core = [v.major, v.minor, v.patch];
+        let lower = self.req.lower();
+        let upper = self.req.upper();
+
+        let lower_ok = match cmp_bound(core, &version.pre, lower, self.lower_pre.as_ref()) {
+            Ordering::Greater => true,
+            Ordering::Equal => self.lower_pre.as_ref().is_none_or(|b| b.inclusive),
+            Ordering::Less => false,
+        };
+        let upper_ok = match cmp_bound(core, &version.pre, upper, self.upper_pre.as_ref()) {
+            Ordering::Less => true,
+            Ordering::Equal => self.upper_pre.as_ref().is_none_or(|b| b.inclusive),
+            Ordering::Greater => false,
+        };
+
+        let pre_ok = version.pre.is_empty()
+            || (self.lower_pre.is_some() && core == lower)
+            || (self.upper_pre.is_some() && core == upper);
+
+        lower_ok && upper_ok && pre_ok
+    }
+
+    fn cmp_lower(&self, other: &Self) -> Ordering {
+        let empty = Prerelease::default();
+        let ordering = self.req.lower().partial_cmp(&other.req.lower()).unwrap_or(Ordering::Equal);
+        let self_pre = self.lower_pre.as_ref().map_or(&empty, |b| &b.pre);
+        let other_pre = other.lower_pre.as_ref().map_or(&empty, |b| &b.pre);
+        let self_inclusive = self.lower_pre.as_ref().is_none_or(|b| b.inclusive);
+        let other_inclusive = other.lower_pre.as_ref().is_none_or(|b| b.inclusive);
+
+        // An exclusive lower bound is tighter than an inclusive one on the same version.
+        ordering
+            .then_with(|| self_pre.cmp(other_pre))
+            .then_with(|| other_inclusive.cmp(&self_inclusive))
+    }
+
+    fn cmp_upper(&self, other: &Self) -> Ordering {
+        let empty = Prerelease::default();
+        let ordering = self.req.upper().partial_cmp(&other.req.upper()).unwrap_or(Ordering::Equal);
+        let self_pre = self.upper_pre.as_ref().map_or(&empty, |b| &b.pre);
+        let other_pre = other.upper_pre.as_ref().map_or(&empty, |b| &b.pre);
+        let self_inclusive = self.upper_pre.as_ref().is_none_or(|b| b.inclusive);
+        let other_inclusive = other.upper_pre.as_ref().is_none_or(|b| b.inclusive);
+
+        ordering
+            .then_with(|| self_pre.cmp(other_pre))
+            .then_with(|| self_inclusive.cmp(&other_inclusive))
+    }
+
+    /// Narrows `self` to the intersection with `other`, see [`VersionReq::intersect`].
+    pub(crate) fn intersect(&mut self, other: &Self) -> Result<(), VersionRegError> {
+        if self.req.is_strict() || other.req.is_strict() {
+            let mut req = self.req;
+            req.intersect(&other.req)?;
+            *self = other.clone();
+            return Ok(());
+        }
+
+        let lower_unset = matches!(self.req.comperator_lower, VersionComperatorLower::None);
+        let other_lower_set = !matches!(other.req.comperator_lower, VersionComperatorLower::None);
+        if other_lower_set && (lower_unset || self.cmp_lower(other).is_lt()) {
+            let [major, minor, patch] = other.req.lower();
+            self.req.set_lower(major, minor, patch, other.req.comperator_lower);
+            self.lower_pre = other.lower_pre.clone();
+        }
+
+        let upper_unset = matches!(self.req.comperator_higher, VersionComperatorUpper::None);
+        let other_upper_set = !matches!(other.req.comperator_higher, VersionComperatorUpper::None);
+        if other_upper_set && (upper_unset || self.cmp_upper(other).is_gt()) {
+            let [major, minor, patch] = other.req.upper();
+            self.req.set_upper(major, minor, patch, other.req.comperator_higher);
+            self.upper_pre = other.upper_pre.clone();
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: VersionNumber> Default for ExtendedVersionReq<N> {
+    fn default() -> Self {
+        Self::from(VersionReq::default())
+    }
+}
+
+impl<N: VersionNumber> From<VersionReq<N>> for ExtendedVersionReq<N> {
+    fn from(req: VersionReq<N>) -> Self {
+        Self { req, lower_pre: None, upper_pre: None }
+    }
+}
+
+impl<N: VersionNumber> FromStr for ExtendedVersionReq<N> {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Prints like [`VersionReq`], with the pre-release attached to the bound that names it.
+impl<N: VersionNumber> fmt::Display for ExtendedVersionReq<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.names_prerelease() {
+            return write!(f, "{}", self.req);
+        }
+
+        let [major, minor, patch] = self.req.lower();
+        let upper = self.req.upper();
+
+        if let Some(bound) = &self.lower_pre {
+            let caret = matches!(self.req.comperator_higher, VersionComperatorUpper::LesserEqMajor) && major == upper[0];
+            let tilde = matches!(self.req.comperator_higher, VersionComperatorUpper::LesserEqMinor) && major == upper[0] && minor == upper[1];
+            let op = match self.req.comperator_lower {
+                VersionComperatorLower::Strict => "=",
+                _ if !bound.inclusive => ">",
+                _ if caret => "^",
+                _ if tilde => "~",
+                _ => ">=",
+            };
+            write!(f, "{}{}.{}.{}-{}", op, major, minor, patch, bound.pre)?;
+            if matches!(self.req.comperator_lower, VersionComperatorLower::Strict) || ((caret || tilde) && self.upper_pre.is_none()) {
+                return Ok(());
+            }
+        } else if !matches!(self.req.comperator_lower, VersionComperatorLower::None) {
+            self.req.fmt_lower(f)?;
+        }
+
+        if let Some(bound) = &self.upper_pre {
+            if !matches!(self.req.comperator_lower, VersionComperatorLower::None) {
+                f.write_str(", ")?;
+            }
+            let op = if bound.inclusive { "<=" } else { "<" };
+            write!(f, "{}{}.{}.{}-{}", op, upper[0], upper[1], upper[2], bound.pre)?;
+        } else if !matches!(self.req.comperator_higher, VersionComperatorUpper::None) {
+            f.write_str(", ")?;
+            self.req.fmt_upper(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: VersionNumber> VersionReq<N> {
+    /// Like [`VersionReq::fits`], a plain requirement never matches a pre-release.
+    pub fn fits_extended(&self, version: &ExtendedVersion<N>) -> bool {
+        !version.is_prerelease() && self.fits(version.version)
+    }
+}
+
+#[cfg(test)]
+mod extended_tests {
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use super::{ExtendedVersion, ExtendedVersionReq};
+    use crate::parse::{ParseVersionError, ParseVersionErrorKind};
+    use crate::VersionReq;
+
+    fn ver(input: &str) -> ExtendedVersion<u64> {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn precedence_test() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1-0",
+            "1.0.1",
+        ];
+        let versions: Vec<_> = ordered.iter().map(|v| ver(v)).collect();
+        for window in versions.windows(2) {
+            assert!(window[0] < window[1], "{} < {}", window[0], window[1]);
+        }
+
+        let a = ver("1.0.0+build.1");
+        let b = ver("1.0.0+build.2");
+        assert!(a.cmp_precedence(&b).is_eq());
+        assert_ne!(a, b);
+        assert!(a < b);
+    }
+
+    #[test]
+    fn roundtrip_test() {
+        for input in ["1.2.0", "1.2.0-rc.1", "1.2.0+build.5", "1.2.0-rc.1+build.5", "0.0.1-x-y.0.z+001.a-b"] {
+            assert_eq!(ver(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let err = |input: &str| input.parse::<ExtendedVersion<u64>>().unwrap_err();
+
+        assert_eq!(err("1.2.0-"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedEnd, position: 6 });
+        assert_eq!(err("1.2.0-rc..1"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('.'), position: 9 });
+        assert_eq!(err("1.2.0-rc.01"), ParseVersionError { kind: ParseVersionErrorKind::LeadingZero, position: 9 });
+        assert_eq!(err("1.2.0+b_1"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('_'), position: 7 });
+        assert_eq!(err("1.2.0+"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedEnd, position: 6 });
+    }
+
+    fn assert_fits(requirement: &str, matching: &[&str], not_matching: &[&str]) {
+        let r = ExtendedVersionReq::<u64>::parse(requirement).unwrap();
+        for v in matching {
+            assert!(r.fits(&ver(v)), "{} should match {}", requirement, v);
+        }
+        for v in not_matching {
+            assert!(!r.fits(&ver(v)), "{} should not match {}", requirement, v);
+        }
+    }
+
+    #[test]
+    fn prerelease_req_test() {
+        assert_fits("^1.2.0", &["1.2.0", "1.3.0+build"], &["1.2.0-rc.1", "1.3.0-rc.1"]);
+        assert_fits(">=1.2.0-rc.1", &["1.2.0-rc.1", "1.2.0-rc.2", "1.2.0", "3.0.0"], &["1.2.0-beta", "1.3.0-rc.1", "1.1.0"]);
+        assert_fits("^1.2.0-rc.1", &["1.2.0-rc.1", "1.2.0", "1.9.0"], &["2.0.0", "1.3.0-rc.1"]);
+        assert_fits("~1.2.0-rc.1", &["1.2.0-rc.3", "1.2.5"], &["1.3.0"]);
+        assert_fits(">1.2.0-rc.1", &["1.2.0-rc.2", "1.2.0"], &["1.2.0-rc.1"]);
+        assert_fits("=1.2.0-rc.1", &["1.2.0-rc.1", "1.2.0-rc.1+build"], &["1.2.0", "1.2.0-rc.2"]);
+        assert_fits("<2.0.0-rc.1", &["1.9.9", "2.0.0-beta"], &["2.0.0", "2.0.0-rc.1", "1.9.9-rc.1"]);
+        assert_fits("<=2.0.0-rc.1", &["2.0.0-rc.1"], &["2.0.0-rc.2", "2.0.0"]);
+        assert_fits(">=1.0.0, <2.0.0-rc.1", &["1.5.0", "2.0.0-alpha"], &["0.9.0", "2.0.0"]);
+        assert_fits(">=1.2.0-alpha, >=1.2.0-beta", &["1.2.0-beta"], &["1.2.0-alpha"]);
+    }
+
+    #[test]
+    fn plain_req_never_matches_prerelease_test() {
+        let r = VersionReq::<u64>::parse(">=1.0.0").unwrap();
+        assert!(r.fits_extended(&ver("1.5.0+build")));
+        assert!(!r.fits_extended(&ver("1.5.0-rc.1")));
+        assert!(VersionReq::<u64>::parse(">=1.2.0-rc.1").is_err());
+    }
+
+    #[test]
+    fn display_test() {
+        let canonical = [
+            ("^1.2.0", "^1.2.0"),
+            ("^1.2.0-rc.1", "^1.2.0-rc.1"),
+            ("~1.2.0-rc.1", "~1.2.0-rc.1"),
+            ("=1.2.0-rc.1", "=1.2.0-rc.1"),
+            (">1.2.0-rc.1", ">1.2.0-rc.1"),
+            ("<2.0.0-rc.1", "<2.0.0-rc.1"),
+            (">=1.0.0, <2.0.0-rc.1", ">=1.0.0, <2.0.0-rc.1"),
+            (">=1.2.0-rc.1, <3", ">=1.2.0-rc.1, <3"),
+        ];
+
+        for (input, expected) in canonical {
+            let r = ExtendedVersionReq::<u64>::parse(input).unwrap();
+            assert_eq!(r.to_string(), expected, "canonical form of {}", input);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Shared by the `proptest` and `arbitrary` integrations: turns raw picks into values that are
+//! accepted by [`Version::new`] and [`VersionReq::try_from`].
+
+use crate::version::VersionNumber;
+use crate::version_req::{VersionRegCompType, VersionRegType};
+use crate::{Version, VersionReq};
+
+/// Number of [`VersionRegType`] variants, see [`reg_type`].
+pub(crate) const REG_TYPE_KINDS: u8 = 13;
+
+/// The largest valid component, `N::max() - 1`, widened to pick components uniformly for any `N`.
+pub(crate) fn top<N: VersionNumber>() -> u128 {
+    N::max().to_wire() - 1
+}
+
+/// Narrows a pick from `0..=top::<N>()` back to `N`.
+pub(crate) fn component<N: VersionNumber>(value: u128) -> N {
+    N::from_wire(value).expect("picked from the valid component range")
+}
+
+/// Values outside of `[0, N::max())`: the open bound marker `N::max()` and, if negative, `N::min()`.
+pub(crate) fn invalid_components<N: VersionNumber>() -> impl Iterator<Item = N> {
+    let min = (N::min() < N::zero()).then(N::min);
+    core::iter::once(N::max()).chain(min)
+}
+
+/// The variant `kind % REG_TYPE_KINDS` with the given components, nudged where the variant would
+/// otherwise be rejected: `>x` needs room for `x + 1`, and `<0.0.0` accepts nothing.
+pub(crate) fn reg_type<N: VersionNumber>(kind: u8, [major, minor, patch]: [N; 3]) -> VersionRegType<N> {
+    let zero = N::zero();
+    let one = N::one();
+    let top = N::max() - one;
+    let room = |n: N| if n == top { n - one } else { n };
+    let nonzero = |n: N| if n == zero { one } else { n };
+
+    match kind % REG_TYPE_KINDS {
+        0 => VersionRegType::Strict(Version { major, minor, patch }),
+        1 => VersionRegType::GreaterMajor { major: room(major) },
+        2 => VersionRegType::GreaterMinor { major, minor: room(minor) },
+        3 => VersionRegType::GreaterPatch { major, minor, patch: room(patch) },
+        4 => VersionRegType::GreaterEqMajor { major },
+        5 => VersionRegType::GreaterEqMinor { major, minor },
+        6 => VersionRegType::GreaterEqPatch { major, minor, patch },
+        7 => VersionRegType::LesserMajor { major: nonzero(major) },
+        8 if major == zero => VersionRegType::LesserMinor { major, minor: nonzero(minor) },
+        8 => VersionRegType::LesserMinor { major, minor },
+        9 if major == zero && minor == zero => VersionRegType::LesserPatch { major, minor, patch: nonzero(patch) },
+        9 => VersionRegType::LesserPatch { major, minor, patch },
+        10 => VersionRegType::LesserEqMajor { major },
+        11 => VersionRegType::LesserEqMinor { major, minor },
+        _ => VersionRegType::LesserEqPatch { major, minor, patch },
+    }
+}
+
+/// A composite of both bounds in whichever order the conversion accepts, or just `first` if
+/// they can't be combined.
+pub(crate) fn comp_type<N: VersionNumber>(first: VersionRegType<N>, second: VersionRegType<N>) -> VersionRegCompType<N> {
+    [
+        VersionRegCompType::Composite { lower: first, higher: second },
+        VersionRegCompType::Composite { lower: second, higher: first },
+    ]
+    .into_iter()
+    .find(|comp| VersionReq::try_from(*comp).is_ok())
+    .unwrap_or(VersionRegCompType::Pure(first))
+}
+
+/// Requirements that are rejected by [`VersionReq::try_from`], built from valid `components`.
+///
+/// `bad` replaces a component, `kind` picks the way the requirement is broken.
+pub(crate) fn invalid_comp_type<N: VersionNumber>(kind: u8, components: [N; 3], bad: N) -> VersionRegCompType<N> {
+    let zero = N::zero();
+    let [major, minor, patch] = components;
+    let valid = reg_type(kind / 4, components);
+
+    match kind % 4 {
+        // A component that isn't a valid version component.
+        0 => VersionRegCompType::Pure(reg_type(1 + kind / 4 % (REG_TYPE_KINDS - 1), [bad, bad, bad])),
+        // `<0`, `<0.0` and `<0.0.0`.
+        1 => VersionRegCompType::Pure(match kind / 4 % 3 {
+            0 => VersionRegType::LesserMajor { major: zero },
+            1 => VersionRegType::LesserMinor { major: zero, minor: zero },
+            _ => VersionRegType::LesserPatch { major: zero, minor: zero, patch: zero },
+        }),
+        // An exact version can't be one side of a range.
+        2 => VersionRegCompType::Composite { lower: VersionRegType::Strict(Version { major, minor, patch }), higher: valid },
+        // Both bounds on the same side.
+        _ => {
+            let side = if (kind / 4).is_multiple_of(2) { 1 } else { 7 };
+            VersionRegCompType::Composite {
+                lower: reg_type(side + kind / 8 % 6, components),
+                higher: reg_type(side + kind / 16 % 6, components),
+            }
+        }
+    }
+}
@@ -1,21 +1,94 @@
 // #!no_std]
-// #![feature(repr_simd)]
 // #![feature(portable_simd)]
 
-#![cfg_attr(not(feature = "serde"), no_std)]
-#![cfg_attr(nightly, feature(repr_simd))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(nightly, feature(portable_simd))]
 
+#[cfg(any(feature = "alloc", test))]
 extern crate alloc;
 
 
 pub mod version;
 pub mod version_req;
+pub mod offset;
+#[cfg(feature = "alloc")]
+pub mod version_req_set;
+pub mod parse;
+mod const_parse;
+#[cfg(feature = "alloc")]
+pub mod extended;
 
 pub mod simd;
+#[cfg(not(nightly))]
+mod arch;
 
 pub mod serde;
+pub mod wire;
+pub mod error;
+#[cfg(feature = "semver")]
+pub mod semver;
+#[cfg(any(feature = "proptest", feature = "arbitrary"))]
+mod generate;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
 
-pub use version::Version;
-pub use version_req::VersionReq;
+pub use version::{Version, VersionDelta};
+pub use version_req::{Mismatch, VersionReq};
+#[cfg(feature = "alloc")]
+pub use version_req_set::VersionReqSet;
+pub use parse::ParseVersionError;
+pub use error::VersionError;
+#[cfg(feature = "alloc")]
+pub use extended::{ExtendedVersion, ExtendedVersionReq};
 
+/// Parses a `major.minor.patch` literal into a `Version<u64>` at compile time.
+///
+/// ```
+/// use fast_version::{version, Version};
+///
+/// const VERSION: Version<u64> = version!("1.1.1");
+/// assert_eq!(VERSION, Version::new(1, 1, 1).unwrap());
+/// ```
+///
+/// A malformed literal fails the build:
+///
+/// ```compile_fail
+/// const VERSION: fast_version::Version<u64> = fast_version::version!("1.1");
+/// ```
+#[macro_export]
+macro_rules! version {
+    ($version:expr) => {{
+        const VERSION: $crate::Version<u64> = match $crate::Version::<u64>::const_parse($version) {
+            Ok(version) => version,
+            Err(e) => panic!("{}", e.kind.description()),
+        };
+        VERSION
+    }};
+}
+
+/// Parses a requirement literal such as `">=1.1, <2"` into a `VersionReq<u64>` at compile time.
+///
+/// ```
+/// use fast_version::{version, version_req, VersionReq};
+///
+/// const VERSION_REQ: VersionReq<u64> = version_req!(">=1.1, <2");
+/// assert!(VERSION_REQ.fits(version!("1.4.0")));
+/// ```
+///
+/// A malformed literal fails the build:
+///
+/// ```compile_fail
+/// const VERSION_REQ: fast_version::VersionReq<u64> = fast_version::version_req!(">=1.x.1");
+/// ```
+#[macro_export]
+macro_rules! version_req {
+    ($version_req:expr) => {{
+        const VERSION_REQ: $crate::VersionReq<u64> = match $crate::VersionReq::<u64>::const_parse($version_req) {
+            Ok(version_req) => version_req,
+            Err(e) => panic!("{}", e.kind.description()),
+        };
+        VERSION_REQ
+    }};
+}
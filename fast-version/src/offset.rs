@@ -0,0 +1,399 @@
+//! Version components stored with an offset of one, for a niche in `Option<Version<_>>`.
+//!
+//! The `core::num::NonZero*` types can't be components themselves: zero components are valid
+//! since 0.x versions were allowed, so `0.1.0` has to be representable, and the bounds of a
+//! [`VersionReq`](crate::VersionReq) are computed with `Add` and `Sub`, which they don't
+//! implement. The types here are plain components instead, `OffsetU8` holds `0` like `u8` does,
+//! and only their storage is a `NonZero`: component `n` is stored as `n + 1`, which makes
+//! `Option<Version<OffsetU64>>` as small as `Version<OffsetU64>`.
+//!
+//! Arithmetic, ordering, formatting and serialization all work on the component value. The
+//! largest stored value is [`VersionNumber::max`], so `OffsetU8` accepts components in
+//! `[0, 254)` and `u8::MAX` has no representation at all. Negative values of the signed types
+//! are stored as they are, and rejected by [`Version::new`] like negative primitive components.
+
+use core::fmt;
+use core::num::{NonZero, ParseIntError};
+use core::ops::{Add, Shl, ShlAssign, Shr, ShrAssign, Sub};
+use core::str::FromStr;
+
+use crate::version::{NewVersionError, VersionNumber};
+use crate::Version;
+
+/// Why a string isn't an offset component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseOffsetError {
+    /// The string isn't an integer of the wrapped primitive.
+    Int(ParseIntError),
+    /// The `MAX` of the wrapped primitive, which has no stored representation.
+    Unrepresentable,
+}
+
+impl fmt::Display for ParseOffsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseOffsetError::Int(_) => f.write_str("invalid integer"),
+            ParseOffsetError::Unrepresentable => f.write_str("value has no offset representation"),
+        }
+    }
+}
+
+impl core::error::Error for ParseOffsetError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ParseOffsetError::Int(e) => Some(e),
+            ParseOffsetError::Unrepresentable => None,
+        }
+    }
+}
+
+macro_rules! impl_component {
+    ($($name:ident($prim:ty)),*) => {
+        $(
+            impl $name {
+                const ZERO: Self = Self::new(0).unwrap();
+                const ONE: Self = Self::new(1).unwrap();
+
+                /// The stored value, `get() + 1` unless negative.
+                #[inline]
+                pub const fn raw(self) -> NonZero<$prim> {
+                    self.0
+                }
+            }
+
+            impl Add for $name {
+                type Output = Self;
+
+                #[inline]
+                fn add(self, rhs: Self) -> Self {
+                    self.get().checked_add(rhs.get()).and_then(Self::new).expect("attempt to add with overflow")
+                }
+            }
+
+            impl Sub for $name {
+                type Output = Self;
+
+                #[inline]
+                fn sub(self, rhs: Self) -> Self {
+                    self.get().checked_sub(rhs.get()).and_then(Self::new).expect("attempt to subtract with overflow")
+                }
+            }
+
+            impl Shl for $name {
+                type Output = Self;
+
+                #[inline]
+                fn shl(self, rhs: Self) -> Self {
+                    Self::new(self.get() << rhs.get()).expect("attempt to shift left with overflow")
+                }
+            }
+
+            impl Shr for $name {
+                type Output = Self;
+
+                #[inline]
+                fn shr(self, rhs: Self) -> Self {
+                    Self::new(self.get() >> rhs.get()).expect("attempt to shift right with overflow")
+                }
+            }
+
+            impl ShlAssign for $name {
+                #[inline]
+                fn shl_assign(&mut self, rhs: Self) {
+                    *self = *self << rhs;
+                }
+            }
+
+            impl ShrAssign for $name {
+                #[inline]
+                fn shr_assign(&mut self, rhs: Self) {
+                    *self = *self >> rhs;
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&self.get(), f)
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.get(), f)
+                }
+            }
+
+            impl FromStr for $name {
+                type Err = ParseOffsetError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    let value = s.parse::<$prim>().map_err(ParseOffsetError::Int)?;
+                    Self::new(value).ok_or(ParseOffsetError::Unrepresentable)
+                }
+            }
+
+            impl From<$name> for $prim {
+                #[inline]
+                fn from(value: $name) -> Self {
+                    value.get()
+                }
+            }
+
+            impl VersionNumber for $name {
+                #[inline]
+                fn max() -> Self {
+                    Self(NonZero::<$prim>::MAX)
+                }
+
+                #[inline]
+                fn min() -> Self {
+                    Self(NonZero::<$prim>::MIN)
+                }
+
+                #[inline]
+                fn zero() -> Self {
+                    Self::ZERO
+                }
+
+                #[inline]
+                fn one() -> Self {
+                    Self::ONE
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! unsigned_component {
+    ($($name:ident($prim:ty)),*) => {
+        $(
+            #[doc = concat!("A `", stringify!($prim), "` component stored as `n + 1` in a `NonZero<", stringify!($prim), ">`, see the [module docs](self).")]
+            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(NonZero<$prim>);
+
+            impl $name {
+                #[doc = concat!("`None` for `", stringify!($prim), "::MAX`, the only value without a stored representation.")]
+                #[inline]
+                pub const fn new(value: $prim) -> Option<Self> {
+                    match value.checked_add(1) {
+                        Some(stored) => Some(Self(NonZero::<$prim>::new(stored).unwrap())),
+                        None => None,
+                    }
+                }
+
+                #[inline]
+                pub const fn get(self) -> $prim {
+                    self.0.get() - 1
+                }
+            }
+
+            impl Version<$name> {
+                pub const fn const_try_new(major: $name, minor: $name, patch: $name) -> Result<Self, NewVersionError> {
+                    const MAX: $prim = <$prim>::MAX;
+
+                    if major.0.get() == MAX {
+                        return Err(NewVersionError::MajorIsMax);
+                    }
+
+                    if minor.0.get() == MAX {
+                        return Err(NewVersionError::MinorIsMax);
+                    }
+
+                    if patch.0.get() == MAX {
+                        return Err(NewVersionError::PatchIsMax);
+                    }
+
+                    let ret = Version { major, minor, patch };
+                    Ok(ret)
+                }
+            }
+        )*
+
+        impl_component!($($name($prim)),*);
+    };
+}
+
+macro_rules! signed_component {
+    ($($name:ident($prim:ty)),*) => {
+        $(
+            #[doc = concat!("An `", stringify!($prim), "` component stored in a `NonZero<", stringify!($prim), ">`, offset by one unless negative, see the [module docs](self).")]
+            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(NonZero<$prim>);
+
+            impl $name {
+                #[doc = concat!("`None` for `", stringify!($prim), "::MAX`, the only value without a stored representation.")]
+                #[inline]
+                pub const fn new(value: $prim) -> Option<Self> {
+                    if value < 0 {
+                        return Some(Self(NonZero::<$prim>::new(value).unwrap()));
+                    }
+                    match value.checked_add(1) {
+                        Some(stored) => Some(Self(NonZero::<$prim>::new(stored).unwrap())),
+                        None => None,
+                    }
+                }
+
+                #[inline]
+                pub const fn get(self) -> $prim {
+                    let stored = self.0.get();
+                    if stored < 0 {
+                        stored
+                    } else {
+                        stored - 1
+                    }
+                }
+            }
+
+            impl Version<$name> {
+                pub const fn const_try_new(major: $name, minor: $name, patch: $name) -> Result<Self, NewVersionError> {
+                    const MAX: $prim = <$prim>::MAX;
+
+                    if major.0.get() == MAX {
+                        return Err(NewVersionError::MajorIsMax);
+                    } else if major.0.get() < 0 {
+                        return Err(NewVersionError::MajorIsMin);
+                    }
+
+                    if minor.0.get() == MAX {
+                        return Err(NewVersionError::MinorIsMax);
+                    } else if minor.0.get() < 0 {
+                        return Err(NewVersionError::MinorIsMin);
+                    }
+
+                    if patch.0.get() == MAX {
+                        return Err(NewVersionError::PatchIsMax);
+                    } else if patch.0.get() < 0 {
+                        return Err(NewVersionError::PatchIsMin);
+                    }
+
+                    let ret = Version { major, minor, patch };
+                    Ok(ret)
+                }
+            }
+        )*
+
+        impl_component!($($name($prim)),*);
+    };
+}
+
+unsigned_component!(OffsetU8(u8), OffsetU16(u16), OffsetU32(u32), OffsetU64(u64), OffsetUsize(usize), OffsetU128(u128));
+signed_component!(OffsetI8(i8), OffsetI16(i16), OffsetI32(i32), OffsetI64(i64), OffsetIsize(isize), OffsetI128(i128));
+
+/// Samples components through the sampler of the primitive, so the generic `rand` based tests
+/// cover these types too.
+#[cfg(test)]
+pub struct UniformComponent<P: rand::distributions::uniform::SampleUniform>(P::Sampler);
+
+#[cfg(test)]
+macro_rules! impl_sample_uniform {
+    ($($name:ident($prim:ty)),*) => {
+        $(
+            impl rand::distributions::uniform::SampleUniform for $name {
+                type Sampler = UniformComponent<$prim>;
+            }
+
+            impl rand::distributions::uniform::UniformSampler for UniformComponent<$prim> {
+                type X = $name;
+
+                fn new<B1, B2>(low: B1, high: B2) -> Self
+                where
+                    B1: rand::distributions::uniform::SampleBorrow<$name> + Sized,
+                    B2: rand::distributions::uniform::SampleBorrow<$name> + Sized,
+                {
+                    Self(<$prim as rand::distributions::uniform::SampleUniform>::Sampler::new(low.borrow().get(), high.borrow().get()))
+                }
+
+                fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self
+                where
+                    B1: rand::distributions::uniform::SampleBorrow<$name> + Sized,
+                    B2: rand::distributions::uniform::SampleBorrow<$name> + Sized,
+                {
+                    Self(<$prim as rand::distributions::uniform::SampleUniform>::Sampler::new_inclusive(low.borrow().get(), high.borrow().get()))
+                }
+
+                fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> $name {
+                    $name::new(self.0.sample(rng)).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+impl_sample_uniform!(
+    OffsetU8(u8), OffsetU16(u16), OffsetU32(u32), OffsetU64(u64), OffsetUsize(usize), OffsetU128(u128),
+    OffsetI8(i8), OffsetI16(i16), OffsetI32(i32), OffsetI64(i64), OffsetIsize(isize), OffsetI128(i128)
+);
+
+#[cfg(test)]
+mod offset_tests {
+    use alloc::string::ToString;
+    use core::mem::size_of;
+    use core::num::IntErrorKind;
+
+    use super::{OffsetI8, OffsetU64, OffsetU8, ParseOffsetError};
+    use crate::version::{NewVersionError, VersionNumber};
+    use crate::{Version, VersionReq};
+
+    #[test]
+    fn mapping_test() {
+        for value in 0..u8::MAX {
+            let component = OffsetU8::new(value).unwrap();
+            assert_eq!(component.get(), value);
+            assert_eq!(component.raw().get(), value + 1);
+        }
+        assert_eq!(OffsetU8::new(u8::MAX), None);
+
+        for value in i8::MIN..i8::MAX {
+            assert_eq!(OffsetI8::new(value).unwrap().get(), value);
+        }
+        assert_eq!(OffsetI8::new(i8::MAX), None);
+        assert!(OffsetI8::new(-1).unwrap() < OffsetI8::zero());
+
+        assert_eq!(OffsetU8::zero().get(), 0);
+        assert_eq!(<OffsetU8 as VersionNumber>::min(), OffsetU8::zero());
+        assert_eq!(<OffsetU8 as VersionNumber>::max().get(), u8::MAX - 1);
+        assert_eq!(<OffsetI8 as VersionNumber>::min().get(), i8::MIN);
+        assert_eq!(size_of::<Option<Version<OffsetU64>>>(), size_of::<Version<OffsetU64>>());
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let n = |v: u8| OffsetU8::new(v).unwrap();
+        assert_eq!(n(2) + n(3), n(5));
+        assert_eq!(n(5) - n(3), n(2));
+        assert_eq!(n(1) << n(3), n(8));
+        assert_eq!(n(8) >> n(3), n(1));
+        assert_eq!(<OffsetU8 as VersionNumber>::max() - OffsetU8::one(), n(253));
+
+        let req = VersionReq::<OffsetU8>::parse("^1.2, <1.5").unwrap();
+        assert!(req.fits(Version::new(n(1), n(4), n(253)).unwrap()));
+        assert!(!req.fits(Version::new(n(1), n(5), n(0)).unwrap()));
+        assert_eq!(req.to_string(), ">=1.2, <1.5");
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn overflow_test() {
+        let _ = <OffsetU8 as VersionNumber>::max() + OffsetU8::one();
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!("0".parse::<OffsetU8>().unwrap(), OffsetU8::zero());
+        assert_eq!("254".parse::<OffsetU8>().unwrap(), <OffsetU8 as VersionNumber>::max());
+        assert_eq!("255".parse::<OffsetU8>(), Err(ParseOffsetError::Unrepresentable));
+        assert!(matches!("256".parse::<OffsetU8>(), Err(ParseOffsetError::Int(e)) if *e.kind() == IntErrorKind::PosOverflow));
+        assert_eq!("-3".parse::<OffsetI8>().unwrap().get(), -3);
+        assert_eq!(alloc::format!("{:?} {:>3}", OffsetU8::one(), OffsetU8::one()), "1   1");
+
+        assert_eq!("1.2.3".parse::<Version<OffsetU8>>().unwrap().to_string(), "1.2.3");
+        assert!("1.2.255".parse::<Version<OffsetU8>>().is_err());
+
+        let n = |v: u8| OffsetU8::new(v).unwrap();
+        assert_eq!(Version::<OffsetU8>::const_try_new(n(1), n(2), n(3)), Ok(Version::new(n(1), n(2), n(3)).unwrap()));
+        assert_eq!(Version::<OffsetU8>::const_try_new(n(1), <OffsetU8 as VersionNumber>::max(), n(3)), Err(NewVersionError::MinorIsMax));
+        let negative = OffsetI8::new(-1).unwrap();
+        assert_eq!(Version::<OffsetI8>::const_try_new(OffsetI8::zero(), OffsetI8::zero(), negative), Err(NewVersionError::PatchIsMin));
+    }
+}
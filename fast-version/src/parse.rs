@@ -0,0 +1,674 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::extended::{BuildMetadata, ExtendedVersion, ExtendedVersionReq, Identifier, Prerelease, PrereleaseBound};
+use crate::version::{NewVersionError, VersionNumber};
+use crate::version_req::{VersionRegError, VersionRegType};
+use crate::{Version, VersionReq};
+#[cfg(feature = "alloc")]
+use crate::VersionReqSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseVersionErrorKind {
+    Empty,
+    UnexpectedEnd,
+    UnexpectedCharacter(char),
+    LeadingZero,
+    NumberToBig,
+    InvalidVersion(NewVersionError),
+    InvalidRequirement(VersionRegError),
+}
+
+/// Error returned when parsing a version from text fails.
+///
+/// `position` is the byte offset into the input at which parsing failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseVersionError {
+    pub kind: ParseVersionErrorKind,
+    pub position: usize,
+}
+
+impl ParseVersionError {
+    pub(crate) fn new(kind: ParseVersionErrorKind, position: usize) -> Self {
+        Self { kind, position }
+    }
+}
+
+impl ParseVersionErrorKind {
+    /// Short description of the error, usable in `const` contexts.
+    pub const fn description(&self) -> &'static str {
+        match self {
+            ParseVersionErrorKind::Empty => "empty string, expected a version",
+            ParseVersionErrorKind::UnexpectedEnd => "unexpected end of input",
+            ParseVersionErrorKind::UnexpectedCharacter(_) => "unexpected character",
+            ParseVersionErrorKind::LeadingZero => "leading zero in number",
+            ParseVersionErrorKind::NumberToBig => "number is to big",
+            ParseVersionErrorKind::InvalidVersion(_) => "invalid version component",
+            ParseVersionErrorKind::InvalidRequirement(_) => "invalid version requirement",
+        }
+    }
+}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseVersionErrorKind::Empty => f.write_str("empty string, expected a version"),
+            ParseVersionErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at position {}", self.position),
+            ParseVersionErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character `{}` at position {}", c, self.position),
+            ParseVersionErrorKind::LeadingZero => write!(f, "leading zero in number at position {}", self.position),
+            ParseVersionErrorKind::NumberToBig => write!(f, "number at position {} is to big", self.position),
+            ParseVersionErrorKind::InvalidVersion(_) => write!(f, "invalid version component at position {}", self.position),
+            ParseVersionErrorKind::InvalidRequirement(_) => write!(f, "invalid version requirement at position {}", self.position),
+        }
+    }
+}
+
+/// The component or requirement error is available as the source.
+impl core::error::Error for ParseVersionError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self.kind {
+            ParseVersionErrorKind::InvalidVersion(e) => Some(e),
+            ParseVersionErrorKind::InvalidRequirement(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Byte cursor shared by the version and version requirement parsers.
+pub(crate) struct Cursor<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    #[inline]
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    #[inline]
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    pub(crate) fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_whitespace() {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+        &self.input[start..self.position]
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn slice(&self, start: usize, end: usize) -> &'a str {
+        &self.input[start..end]
+    }
+
+    fn eat_wildcard(&mut self) -> bool {
+        self.eat('*') || self.eat('x') || self.eat('X')
+    }
+
+    pub(crate) fn error(&self, kind: ParseVersionErrorKind) -> ParseVersionError {
+        ParseVersionError::new(kind, self.position)
+    }
+
+    /// Error for whatever is at the current position.
+    pub(crate) fn unexpected(&self) -> ParseVersionError {
+        match self.peek() {
+            Some(c) => self.error(ParseVersionErrorKind::UnexpectedCharacter(c)),
+            None => self.error(ParseVersionErrorKind::UnexpectedEnd),
+        }
+    }
+
+    pub(crate) fn expect(&mut self, expected: char) -> Result<(), ParseVersionError> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+        Err(self.unexpected())
+    }
+
+    /// Parses a semver numeric identifier: ascii digits without a leading zero.
+    pub(crate) fn number<N: VersionNumber>(&mut self) -> Result<N, ParseVersionError> {
+        let start = self.position;
+        let bytes = self.input.as_bytes();
+        let mut end = start;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+
+        if end == start {
+            return Err(self.unexpected());
+        }
+
+        if bytes[start] == b'0' && end - start > 1 {
+            return Err(self.error(ParseVersionErrorKind::LeadingZero));
+        }
+
+        let ret = self.input[start..end]
+            .parse::<N>()
+            .map_err(|_| self.error(ParseVersionErrorKind::NumberToBig))?;
+        self.position = end;
+        Ok(ret)
+    }
+}
+
+/// Parses `major.minor.patch` from the cursor without validating the components.
+pub(crate) fn parse_triple<N: VersionNumber>(cursor: &mut Cursor<'_>) -> Result<([N; 3], [usize; 3]), ParseVersionError> {
+    let major_position = cursor.position();
+    let major = cursor.number()?;
+    cursor.expect('.')?;
+    let minor_position = cursor.position();
+    let minor = cursor.number()?;
+    cursor.expect('.')?;
+    let patch_position = cursor.position();
+    let patch = cursor.number()?;
+
+    Ok(([major, minor, patch], [major_position, minor_position, patch_position]))
+}
+
+/// Maps a [`NewVersionError`] onto the position of the offending component.
+pub(crate) fn version_error_position(error: NewVersionError, positions: [usize; 3]) -> ParseVersionError {
+    let position = match error {
+        NewVersionError::MajorIsMax | NewVersionError::MajorIsMin => positions[0],
+        NewVersionError::MinorIsMax | NewVersionError::MinorIsMin => positions[1],
+        NewVersionError::PatchIsMax | NewVersionError::PatchIsMin => positions[2],
+    };
+    ParseVersionError::new(ParseVersionErrorKind::InvalidVersion(error), position)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operator {
+    Exact,
+    Greater,
+    GreaterEq,
+    Lesser,
+    LesserEq,
+    Caret,
+    Tilde,
+}
+
+/// A possibly partial version such as `1`, `1.2`, `1.2.*` or `*`.
+pub(crate) struct Partial<N: VersionNumber> {
+    pub(crate) parts: [N; 3],
+    pub(crate) len: usize,
+    pub(crate) wildcard: bool,
+}
+
+fn parse_operator(cursor: &mut Cursor<'_>) -> Option<Operator> {
+    let op = if cursor.eat('=') {
+        Operator::Exact
+    } else if cursor.eat('>') {
+        if cursor.eat('=') { Operator::GreaterEq } else { Operator::Greater }
+    } else if cursor.eat('<') {
+        if cursor.eat('=') { Operator::LesserEq } else { Operator::Lesser }
+    } else if cursor.eat('^') {
+        Operator::Caret
+    } else if cursor.eat('~') {
+        Operator::Tilde
+    } else {
+        return None;
+    };
+    Some(op)
+}
+
+fn parse_partial<N: VersionNumber>(cursor: &mut Cursor<'_>) -> Result<Partial<N>, ParseVersionError> {
+    let placeholder = N::one();
+    let mut ret = Partial { parts: [placeholder; 3], len: 0, wildcard: false };
+
+    if cursor.eat_wildcard() {
+        ret.wildcard = true;
+        return Ok(ret);
+    }
+
+    ret.parts[0] = cursor.number()?;
+    ret.len = 1;
+    while ret.len < 3 && cursor.eat('.') {
+        if cursor.eat_wildcard() {
+            ret.wildcard = true;
+            break;
+        }
+        ret.parts[ret.len] = cursor.number()?;
+        ret.len += 1;
+    }
+
+    Ok(ret)
+}
+
+fn greater_eq<N: VersionNumber>(p: &Partial<N>) -> VersionRegType<N> {
+    let [major, minor, patch] = p.parts;
+    match p.len {
+        1 => VersionRegType::GreaterEqMajor { major },
+        2 => VersionRegType::GreaterEqMinor { major, minor },
+        _ => VersionRegType::GreaterEqPatch { major, minor, patch },
+    }
+}
+
+fn lesser_eq<N: VersionNumber>(p: &Partial<N>) -> VersionRegType<N> {
+    let [major, minor, patch] = p.parts;
+    match p.len {
+        1 => VersionRegType::LesserEqMajor { major },
+        2 => VersionRegType::LesserEqMinor { major, minor },
+        _ => VersionRegType::LesserEqPatch { major, minor, patch },
+    }
+}
+
+/// Upper bound of a caret requirement: the left-most non-zero component may not change, so for
+/// `0.x` versions the minor (and for `0.0.x` the patch) version is the breaking one.
+fn caret_upper<N: VersionNumber>(p: &Partial<N>) -> VersionRegType<N> {
+    let zero = N::zero();
+    let [major, minor, patch] = p.parts;
+    if major != zero || p.len == 1 {
+        VersionRegType::LesserEqMajor { major }
+    } else if minor != zero || p.len == 2 {
+        VersionRegType::LesserEqMinor { major, minor }
+    } else {
+        VersionRegType::LesserEqPatch { major, minor, patch }
+    }
+}
+
+/// Maps one comparator onto the lower and upper [`VersionRegType`] it stands for.
+pub(crate) fn comparator_types<N: VersionNumber>(op: Operator, p: &Partial<N>) -> [Option<VersionRegType<N>>; 2] {
+    let [major, minor, patch] = p.parts;
+    match (op, p.len) {
+        (_, 0) => [None, None],
+        (Operator::Exact, 3) => [Some(VersionRegType::Strict(Version { major, minor, patch })), None],
+        (Operator::Exact, _) => [Some(greater_eq(p)), Some(lesser_eq(p))],
+        (Operator::Greater, 1) => [Some(VersionRegType::GreaterMajor { major }), None],
+        (Operator::Greater, 2) => [Some(VersionRegType::GreaterMinor { major, minor }), None],
+        (Operator::Greater, _) => [Some(VersionRegType::GreaterPatch { major, minor, patch }), None],
+        (Operator::GreaterEq, _) => [Some(greater_eq(p)), None],
+        (Operator::Lesser, 1) => [None, Some(VersionRegType::LesserMajor { major })],
+        (Operator::Lesser, 2) => [None, Some(VersionRegType::LesserMinor { major, minor })],
+        (Operator::Lesser, _) => [None, Some(VersionRegType::LesserPatch { major, minor, patch })],
+        (Operator::LesserEq, _) => [None, Some(lesser_eq(p))],
+        (Operator::Caret, _) => [Some(greater_eq(p)), Some(caret_upper(p))],
+        (Operator::Tilde, 1) => [Some(greater_eq(p)), Some(VersionRegType::LesserEqMajor { major })],
+        (Operator::Tilde, _) => [Some(greater_eq(p)), Some(VersionRegType::LesserEqMinor { major, minor })],
+    }
+}
+
+/// Parses the operator and version of a comparator, returning the position the version starts at.
+fn parse_comparator_head<N: VersionNumber>(cursor: &mut Cursor<'_>) -> Result<(Operator, Partial<N>, usize), ParseVersionError> {
+    let op = parse_operator(cursor);
+    cursor.skip_whitespace();
+
+    let start = cursor.position();
+    let partial = parse_partial::<N>(cursor)?;
+
+    // A bare version is a caret requirement unless it contains a wildcard, like in cargo.
+    let op = match op {
+        Some(op) => op,
+        None if partial.wildcard => Operator::Exact,
+        None => Operator::Caret,
+    };
+
+    if partial.len == 0 && op != Operator::Exact {
+        return Err(ParseVersionError::new(ParseVersionErrorKind::UnexpectedCharacter('*'), start));
+    }
+
+    Ok((op, partial, start))
+}
+
+fn apply_types<N: VersionNumber>(req: &mut VersionReq<N>, types: [Option<VersionRegType<N>>; 2], start: usize) -> Result<(), ParseVersionError> {
+    for reg_type in types.into_iter().flatten() {
+        req.apply_reg_type(reg_type)
+            .map_err(|e| ParseVersionError::new(ParseVersionErrorKind::InvalidRequirement(e), start))?;
+    }
+    Ok(())
+}
+
+fn parse_comparator<N: VersionNumber>(cursor: &mut Cursor<'_>) -> Result<VersionReq<N>, ParseVersionError> {
+    let (op, partial, start) = parse_comparator_head(cursor)?;
+
+    let mut ret = VersionReq::default();
+    apply_types(&mut ret, comparator_types(op, &partial), start)?;
+    Ok(ret)
+}
+
+/// Parses a comma separated list of cargo style comparators into a single [`VersionReq`].
+pub(crate) fn parse_version_req<N: VersionNumber>(input: &str) -> Result<VersionReq<N>, ParseVersionError> {
+    let mut cursor = Cursor::new(input);
+    cursor.skip_whitespace();
+    if cursor.is_empty() {
+        return Err(cursor.error(ParseVersionErrorKind::Empty));
+    }
+
+    let mut ret = VersionReq::default();
+    loop {
+        cursor.skip_whitespace();
+        let start = cursor.position();
+        let comparator = parse_comparator(&mut cursor)?;
+        ret.intersect(&comparator)
+            .map_err(|e| ParseVersionError::new(ParseVersionErrorKind::InvalidRequirement(e), start))?;
+
+        cursor.skip_whitespace();
+        if cursor.is_empty() {
+            break;
+        }
+        cursor.expect(',')?;
+    }
+
+    Ok(ret)
+}
+
+#[cfg(feature = "alloc")]
+/// Parses `||` separated requirements, an unsatisfiable requirement such as `<0.0.0` or `>2, <1`
+/// contributes no versions.
+pub(crate) fn parse_version_req_set<N: VersionNumber>(input: &str) -> Result<VersionReqSet<N>, ParseVersionError> {
+    let mut ret = VersionReqSet::empty();
+    let mut offset = 0;
+    for part in input.split("||") {
+        match parse_version_req::<N>(part) {
+            Ok(req) => ret = ret.union(&VersionReqSet::from(req)),
+            Err(ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(VersionRegError::Unsatisfiable | VersionRegError::EmptyRange), .. }) => {}
+            Err(e) => return Err(ParseVersionError::new(e.kind, e.position + offset)),
+        }
+        offset += part.len() + 2;
+    }
+
+    Ok(ret)
+}
+
+#[cfg(feature = "alloc")]
+/// Parses dot separated identifiers made of `[0-9A-Za-z-]`.
+fn parse_identifiers<'a>(cursor: &mut Cursor<'a>, mut on_identifier: impl FnMut(&'a str, usize) -> Result<(), ParseVersionError>) -> Result<(), ParseVersionError> {
+    loop {
+        let start = cursor.position();
+        let identifier = cursor.take_while(|c| c.is_ascii_alphanumeric() || c == '-');
+        if identifier.is_empty() {
+            return Err(cursor.unexpected());
+        }
+        on_identifier(identifier, start)?;
+
+        if !cursor.eat('.') {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_prerelease(cursor: &mut Cursor<'_>) -> Result<Prerelease, ParseVersionError> {
+    let mut identifiers = Vec::new();
+    parse_identifiers(cursor, |identifier, start| {
+        if !identifier.bytes().all(|b| b.is_ascii_digit()) {
+            identifiers.push(Identifier::AlphaNumeric(identifier.to_string()));
+            return Ok(());
+        }
+
+        if identifier.len() > 1 && identifier.starts_with('0') {
+            return Err(ParseVersionError::new(ParseVersionErrorKind::LeadingZero, start));
+        }
+        let number = identifier
+            .parse()
+            .map_err(|_| ParseVersionError::new(ParseVersionErrorKind::NumberToBig, start))?;
+        identifiers.push(Identifier::Numeric(number));
+        Ok(())
+    })?;
+
+    Ok(Prerelease { identifiers })
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_build(cursor: &mut Cursor<'_>) -> Result<BuildMetadata, ParseVersionError> {
+    let start = cursor.position();
+    parse_identifiers(cursor, |_, _| Ok(()))?;
+    let end = cursor.position();
+
+    Ok(BuildMetadata { identifier: cursor.slice(start, end).to_string() })
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_extended_version<N: VersionNumber>(input: &str) -> Result<ExtendedVersion<N>, ParseVersionError> {
+    let mut cursor = Cursor::new(input);
+    if cursor.is_empty() {
+        return Err(cursor.error(ParseVersionErrorKind::Empty));
+    }
+
+    let ([major, minor, patch], positions) = parse_triple(&mut cursor)?;
+    let version = Version::new(major, minor, patch).map_err(|e| version_error_position(e, positions))?;
+
+    let mut pre = Prerelease::default();
+    if cursor.eat('-') {
+        pre = parse_prerelease(&mut cursor)?;
+    }
+
+    let mut build = BuildMetadata::default();
+    if cursor.eat('+') {
+        build = parse_build(&mut cursor)?;
+    }
+
+    if !cursor.is_empty() {
+        return Err(cursor.unexpected());
+    }
+
+    Ok(ExtendedVersion { version, pre, build })
+}
+
+#[cfg(feature = "alloc")]
+/// Builds a comparator naming a pre-release, e.g. `>=1.2.0-rc.1`.
+///
+/// The core requirement gets the bounds plain versions are checked against: `1.2.0` is above
+/// `1.2.0-rc.1`, so `>1.2.0-rc.1` still starts at `>=1.2.0` while `<1.2.0-rc.1` ends below it.
+fn prerelease_comparator<N: VersionNumber>(op: Operator, partial: &Partial<N>, pre: Prerelease, start: usize) -> Result<ExtendedVersionReq<N>, ParseVersionError> {
+    let [major, minor, patch] = partial.parts;
+    let lower = Some(VersionRegType::GreaterEqPatch { major, minor, patch });
+    let upper = Some(VersionRegType::LesserEqPatch { major, minor, patch });
+
+    let inclusive = PrereleaseBound { pre: pre.clone(), inclusive: true };
+    let exclusive = PrereleaseBound { pre, inclusive: false };
+
+    let (types, lower_pre, upper_pre) = match op {
+        Operator::Exact => (comparator_types(op, partial), Some(inclusive.clone()), Some(inclusive)),
+        Operator::GreaterEq | Operator::Caret | Operator::Tilde => (comparator_types(op, partial), Some(inclusive), None),
+        Operator::Greater => ([lower, None], Some(exclusive), None),
+        Operator::LesserEq => ([None, upper], None, Some(inclusive)),
+        Operator::Lesser => ([None, upper], None, Some(exclusive)),
+    };
+
+    let mut req = VersionReq::default();
+    apply_types(&mut req, types, start)?;
+    Ok(ExtendedVersionReq { req, lower_pre, upper_pre })
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_extended_version_req<N: VersionNumber>(input: &str) -> Result<ExtendedVersionReq<N>, ParseVersionError> {
+    let mut cursor = Cursor::new(input);
+    cursor.skip_whitespace();
+    if cursor.is_empty() {
+        return Err(cursor.error(ParseVersionErrorKind::Empty));
+    }
+
+    let mut ret = ExtendedVersionReq::default();
+    loop {
+        cursor.skip_whitespace();
+        let start = cursor.position();
+        let (op, partial, version_start) = parse_comparator_head::<N>(&mut cursor)?;
+
+        let comparator = if partial.len == 3 && cursor.eat('-') {
+            let pre = parse_prerelease(&mut cursor)?;
+            prerelease_comparator(op, &partial, pre, version_start)?
+        } else {
+            let mut req = VersionReq::default();
+            apply_types(&mut req, comparator_types(op, &partial), version_start)?;
+            ExtendedVersionReq::from(req)
+        };
+
+        ret.intersect(&comparator)
+            .map_err(|e| ParseVersionError::new(ParseVersionErrorKind::InvalidRequirement(e), start))?;
+
+        cursor.skip_whitespace();
+        if cursor.is_empty() {
+            break;
+        }
+        cursor.expect(',')?;
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod parse_tests {
+    use alloc::string::ToString;
+    use rand::{distributions::uniform::SampleUniform, thread_rng, Rng};
+
+    use super::{ParseVersionError, ParseVersionErrorKind};
+    use crate::offset::{OffsetI32, OffsetU64, OffsetU8};
+    use crate::version::{NewVersionError, VersionNumber};
+    use crate::Version;
+
+    const SAMPLE_SIZE: usize = 1_000;
+
+    fn generate_valid_version_part<N: VersionNumber + SampleUniform>() -> N {
+        let one = N::one();
+        let upper = N::max() - one;
+
+        thread_rng().gen_range(N::zero()..=upper)
+    }
+
+    fn roundtrip_test<N: VersionNumber + SampleUniform + core::fmt::Debug>() {
+        (0..SAMPLE_SIZE).for_each(|_| {
+            let major = generate_valid_version_part::<N>();
+            let minor = generate_valid_version_part::<N>();
+            let patch = generate_valid_version_part::<N>();
+
+            let version = Version::new(major, minor, patch).unwrap();
+            let text = version.to_string();
+            let parsed: Version<N> = text.parse().unwrap();
+
+            assert_eq!(version, parsed, "Roundtrip failed for {}", text);
+        });
+
+        let max = N::max().to_string();
+        let input = alloc::format!("1.1.{}", max);
+        let err = input.parse::<Version<N>>().unwrap_err();
+        assert_eq!(err, ParseVersionError { kind: ParseVersionErrorKind::InvalidVersion(NewVersionError::PatchIsMax), position: 4 });
+    }
+
+    #[test]
+    fn roundtrip_u8_test() {
+        roundtrip_test::<u8>();
+    }
+
+    #[test]
+    fn roundtrip_u16_test() {
+        roundtrip_test::<u16>();
+    }
+
+    #[test]
+    fn roundtrip_u32_test() {
+        roundtrip_test::<u32>();
+    }
+
+    #[test]
+    fn roundtrip_u64_test() {
+        roundtrip_test::<u64>();
+    }
+
+    #[test]
+    fn roundtrip_usize_test() {
+        roundtrip_test::<usize>();
+    }
+
+    #[test]
+    fn roundtrip_i8_test() {
+        roundtrip_test::<i8>();
+    }
+
+    #[test]
+    fn roundtrip_i16_test() {
+        roundtrip_test::<i16>();
+    }
+
+    #[test]
+    fn roundtrip_i32_test() {
+        roundtrip_test::<i32>();
+    }
+
+    #[test]
+    fn roundtrip_i64_test() {
+        roundtrip_test::<i64>();
+    }
+
+    #[test]
+    fn roundtrip_isize_test() {
+        roundtrip_test::<isize>();
+    }
+
+    #[test]
+    fn roundtrip_u128_test() {
+        roundtrip_test::<u128>();
+    }
+
+    #[test]
+    fn roundtrip_i128_test() {
+        roundtrip_test::<i128>();
+    }
+
+    #[test]
+    fn roundtrip_offset_u8_test() {
+        roundtrip_test::<OffsetU8>();
+    }
+
+    #[test]
+    fn roundtrip_offset_u64_test() {
+        roundtrip_test::<OffsetU64>();
+    }
+
+    #[test]
+    fn roundtrip_offset_i32_test() {
+        roundtrip_test::<OffsetI32>();
+    }
+
+    fn parse_err(input: &str) -> ParseVersionError {
+        input.parse::<Version<u64>>().unwrap_err()
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        assert_eq!(parse_err("").kind, ParseVersionErrorKind::Empty);
+        assert_eq!(parse_err("1.2"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedEnd, position: 3 });
+        assert_eq!(parse_err("1.2.3.4"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('.'), position: 5 });
+        assert_eq!(parse_err("1.02.3"), ParseVersionError { kind: ParseVersionErrorKind::LeadingZero, position: 2 });
+        assert_eq!(parse_err("1.x.3"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('x'), position: 2 });
+        assert_eq!(parse_err(" 1.2.3"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter(' '), position: 0 });
+        assert_eq!(parse_err("+1.2.3"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('+'), position: 0 });
+        assert_eq!(parse_err("1.2.99999999999999999999"), ParseVersionError { kind: ParseVersionErrorKind::NumberToBig, position: 4 });
+        assert_eq!("1.256.1".parse::<Version<u8>>().unwrap_err(), ParseVersionError { kind: ParseVersionErrorKind::NumberToBig, position: 2 });
+        assert_eq!("128.1.1".parse::<Version<i8>>().unwrap_err(), ParseVersionError { kind: ParseVersionErrorKind::NumberToBig, position: 0 });
+    }
+}
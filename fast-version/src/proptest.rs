@@ -0,0 +1,190 @@
+//! [`proptest`] strategies for the types of this crate.
+//!
+//! The strategies only generate valid values: components in `[0, N::max())`, requirements that
+//! [`VersionReq::try_from`] accepts. Components are biased towards `0`, `1` and `N::max() - 1`,
+//! where most edge cases live. `Arbitrary` is implemented with the same strategies, so
+//! `any::<Version<u8>>()` works as well.
+//!
+//! [`invalid_components`] and [`invalid_version_reg_comp_type`] generate values that are
+//! rejected instead, to test the error paths.
+
+use core::fmt::Debug;
+
+use proptest::arbitrary::Arbitrary;
+use proptest::prelude::{any, prop_oneof, Just, Strategy};
+use proptest::strategy::BoxedStrategy;
+
+use crate::generate::{self, REG_TYPE_KINDS};
+use crate::version::VersionNumber;
+use crate::version_req::{VersionRegCompType, VersionRegType};
+use crate::{Version, VersionReq};
+
+/// A valid version component.
+pub fn component<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = N> {
+    let top = generate::top::<N>();
+    prop_oneof![
+        1 => Just(0),
+        1 => Just(1.min(top)),
+        1 => Just(top),
+        5 => 0..=top,
+    ]
+    .prop_map(generate::component)
+}
+
+pub fn components<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = [N; 3]> {
+    [component(), component(), component()]
+}
+
+pub fn version<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = Version<N>> {
+    components().prop_map(|[major, minor, patch]| Version { major, minor, patch })
+}
+
+pub fn version_reg_type<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = VersionRegType<N>> {
+    (0..REG_TYPE_KINDS, components()).prop_map(|(kind, components)| generate::reg_type(kind, components))
+}
+
+/// Pure requirements and ranges with a lower and an upper bound.
+pub fn version_reg_comp_type<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = VersionRegCompType<N>> {
+    prop_oneof![
+        version_reg_type().prop_map(VersionRegCompType::Pure),
+        (version_reg_type(), version_reg_type()).prop_map(|(first, second)| generate::comp_type(first, second)),
+    ]
+}
+
+pub fn version_req<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = VersionReq<N>> {
+    version_reg_comp_type().prop_map(|comp| VersionReq::try_from(comp).expect("generated requirements are valid"))
+}
+
+/// Components [`Version::new`] rejects: at least one of them is `N::max()`, or negative.
+pub fn invalid_components<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = [N; 3]> {
+    let bad: Vec<N> = generate::invalid_components().collect();
+    (components(), proptest::sample::select(bad), 1..8_u8).prop_map(|(mut components, bad, positions)| {
+        for (i, component) in components.iter_mut().enumerate() {
+            if positions & (1 << i) != 0 {
+                *component = bad;
+            }
+        }
+        components
+    })
+}
+
+/// Requirements [`VersionReq::try_from`] rejects: invalid components, `<0.0.0`, exact versions
+/// in a range and ranges with both bounds on the same side.
+pub fn invalid_version_reg_comp_type<N: VersionNumber + Debug + 'static>() -> impl Strategy<Value = VersionRegCompType<N>> {
+    let bad: Vec<N> = generate::invalid_components().collect();
+    (any::<u8>(), components(), proptest::sample::select(bad))
+        .prop_map(|(kind, components, bad)| generate::invalid_comp_type(kind, components, bad))
+}
+
+impl<N: VersionNumber + Debug + 'static> Arbitrary for Version<N> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        version().boxed()
+    }
+}
+
+impl<N: VersionNumber + Debug + 'static> Arbitrary for VersionRegType<N> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        version_reg_type().boxed()
+    }
+}
+
+impl<N: VersionNumber + Debug + 'static> Arbitrary for VersionRegCompType<N> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        version_reg_comp_type().boxed()
+    }
+}
+
+impl<N: VersionNumber + Debug + 'static> Arbitrary for VersionReq<N> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        version_req().boxed()
+    }
+}
+
+#[cfg(test)]
+mod proptest_tests {
+    use core::fmt::Debug;
+
+    use proptest::prelude::{any, proptest};
+    use proptest::strategy::{Strategy, ValueTree};
+    use proptest::test_runner::TestRunner;
+
+    use super::{invalid_components, invalid_version_reg_comp_type};
+    use crate::offset::{OffsetI8, OffsetU64};
+    use crate::version::VersionNumber;
+    use crate::version_req::VersionRegCompType;
+    use crate::{Version, VersionReq};
+
+    fn validity_test<N: VersionNumber + Debug + 'static>() {
+        let mut runner = TestRunner::default();
+        for _ in 0..1000 {
+            let version = any::<Version<N>>().new_tree(&mut runner).unwrap().current();
+            assert!(Version::new(version.major, version.minor, version.patch).is_ok(), "{:?}", version);
+
+            let comp = any::<VersionRegCompType<N>>().new_tree(&mut runner).unwrap().current();
+            assert!(VersionReq::try_from(comp).is_ok(), "{:?}", comp);
+
+            let req = any::<VersionReq<N>>().new_tree(&mut runner).unwrap().current();
+            assert_eq!(req.to_string().parse::<VersionReq<N>>().unwrap(), req);
+
+            let [major, minor, patch] = invalid_components::<N>().new_tree(&mut runner).unwrap().current();
+            assert!(Version::new(major, minor, patch).is_err(), "{} {} {}", major, minor, patch);
+
+            let comp = invalid_version_reg_comp_type::<N>().new_tree(&mut runner).unwrap().current();
+            assert!(VersionReq::try_from(comp).is_err(), "{:?}", comp);
+        }
+    }
+
+    #[test]
+    fn validity_u8_test() {
+        validity_test::<u8>();
+    }
+
+    #[test]
+    fn validity_i8_test() {
+        validity_test::<i8>();
+    }
+
+    #[test]
+    fn validity_u64_test() {
+        validity_test::<u64>();
+    }
+
+    #[test]
+    fn validity_i128_test() {
+        validity_test::<i128>();
+    }
+
+    #[test]
+    fn validity_offset_u64_test() {
+        validity_test::<OffsetU64>();
+    }
+
+    #[test]
+    fn validity_offset_i8_test() {
+        validity_test::<OffsetI8>();
+    }
+
+    proptest! {
+        #[test]
+        fn fits_bounds_test(req in any::<VersionReq<u16>>(), version in any::<Version<u16>>()) {
+            let triple = [version.major, version.minor, version.patch];
+            let fits = match req.normalized_bounds() {
+                Some((lower, upper)) => lower <= triple && triple <= upper,
+                None => false,
+            };
+            assert_eq!(req.fits(version), fits, "{} {}", req, version);
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Conversions between this crate and the [`semver`] crate.
+//!
+//! Every [`Version`] and [`VersionReq`] converts into its `semver` counterpart. The other direction
+//! is fallible: pre-release and build metadata have no equivalent here, and components must fit
+//! into `N` without reaching `N::max()`.
+
+use core::convert::Infallible;
+use core::fmt;
+
+use semver::{BuildMetadata, Comparator, Op, Prerelease};
+
+use crate::parse::{comparator_types, Operator, Partial};
+use crate::version::VersionNumber;
+use crate::version_req::VersionRegError;
+use crate::{Version, VersionReq};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemverConversionError {
+    /// The version or a comparator carries a pre-release such as `-alpha.1`.
+    Prerelease,
+    /// The version carries build metadata such as `+build.5`.
+    BuildMetadata,
+    /// The component doesn't fit into `N` or is `N::max()`.
+    OutOfRange(u64),
+    /// A comparator operator added to `semver` after this crate was written.
+    UnsupportedOperator,
+}
+
+impl fmt::Display for SemverConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SemverConversionError::Prerelease => f.write_str("pre-releases are not supported"),
+            SemverConversionError::BuildMetadata => f.write_str("build metadata is not supported"),
+            SemverConversionError::OutOfRange(value) => write!(f, "component {} is out of range", value),
+            SemverConversionError::UnsupportedOperator => f.write_str("unsupported comparator operator"),
+        }
+    }
+}
+
+impl core::error::Error for SemverConversionError {}
+
+/// Inclusive `[lower, upper]` bounds as returned by `VersionReq::normalized_bounds`.
+type Bounds<N> = ([N; 3], [N; 3]);
+
+fn component<N: VersionNumber + TryFrom<u64>>(value: u64) -> Result<N, SemverConversionError> {
+    match N::try_from(value) {
+        Ok(n) if n != N::max() => Ok(n),
+        _ => Err(SemverConversionError::OutOfRange(value)),
+    }
+}
+
+impl<N: VersionNumber + TryFrom<u64>> TryFrom<semver::Version> for Version<N> {
+    type Error = SemverConversionError;
+
+    fn try_from(value: semver::Version) -> Result<Self, Self::Error> {
+        if !value.pre.is_empty() {
+            return Err(SemverConversionError::Prerelease);
+        }
+        if !value.build.is_empty() {
+            return Err(SemverConversionError::BuildMetadata);
+        }
+
+        Ok(Version {
+            major: component(value.major)?,
+            minor: component(value.minor)?,
+            patch: component(value.patch)?,
+        })
+    }
+}
+
+/// The versions one comparator accepts as `[lower, upper]`, `None` if it accepts none.
+fn comparator_bounds<N: VersionNumber + TryFrom<u64>>(comparator: &Comparator) -> Result<Option<Bounds<N>>, SemverConversionError> {
+    if !comparator.pre.is_empty() {
+        return Err(SemverConversionError::Prerelease);
+    }
+
+    let op = match comparator.op {
+        Op::Exact | Op::Wildcard => Operator::Exact,
+        Op::Greater => Operator::Greater,
+        Op::GreaterEq => Operator::GreaterEq,
+        Op::Less => Operator::Lesser,
+        Op::LessEq => Operator::LesserEq,
+        Op::Tilde => Operator::Tilde,
+        Op::Caret => Operator::Caret,
+        _ => return Err(SemverConversionError::UnsupportedOperator),
+    };
+
+    let zero = N::zero();
+    let mut partial = Partial { parts: [zero; 3], len: 1, wildcard: comparator.op == Op::Wildcard };
+    partial.parts[0] = component(comparator.major)?;
+    for value in [comparator.minor, comparator.patch].into_iter().map_while(|c| c) {
+        partial.parts[partial.len] = component(value)?;
+        partial.len += 1;
+    }
+
+    let mut ret = VersionReq::default();
+    for reg_type in comparator_types(op, &partial).into_iter().flatten() {
+        match ret.apply_reg_type(reg_type) {
+            Ok(()) => {}
+            Err(VersionRegError::Unsatisfiable) => return Ok(None),
+            Err(_) => unreachable!("components are validated above"),
+        }
+    }
+
+    Ok(ret.normalized_bounds())
+}
+
+/// Comparators are intersected like `semver` does, so any requirement without pre-releases is
+/// representable as long as its components fit into `N`.
+impl<N: VersionNumber + TryFrom<u64>> TryFrom<&semver::VersionReq> for VersionReq<N> {
+    type Error = SemverConversionError;
+
+    fn try_from(value: &semver::VersionReq) -> Result<Self, Self::Error> {
+        let zero = N::zero();
+        let one = N::one();
+        let top = N::max() - one;
+
+        let mut lower = [zero; 3];
+        let mut upper = [top; 3];
+        let mut empty = false;
+        for comparator in &value.comparators {
+            match comparator_bounds::<N>(comparator)? {
+                Some((l, u)) => {
+                    if l > lower {
+                        lower = l;
+                    }
+                    if u < upper {
+                        upper = u;
+                    }
+                }
+                None => empty = true,
+            }
+        }
+
+        if empty {
+            return Ok(VersionReq::from_bounds([zero, zero, one], [zero; 3]));
+        }
+        Ok(VersionReq::from_bounds(lower, upper))
+    }
+}
+
+impl<N: VersionNumber + TryFrom<u64>> TryFrom<semver::VersionReq> for VersionReq<N> {
+    type Error = SemverConversionError;
+
+    fn try_from(value: semver::VersionReq) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
+
+fn semver_comparator(op: Op, parts: &[u64]) -> Comparator {
+    Comparator {
+        op,
+        major: parts[0],
+        minor: parts.get(1).copied(),
+        patch: parts.get(2).copied(),
+        pre: Prerelease::EMPTY,
+    }
+}
+
+/// Builds the `semver` requirement for `[lower, upper]`, an open upper bound (made of
+/// `N::max() - 1` components) has no comparator.
+fn semver_req<N: VersionNumber, E>(bounds: Option<Bounds<N>>, to_u64: impl Fn(N) -> Result<u64, E>) -> Result<semver::VersionReq, E> {
+    let (lower, upper) = match bounds {
+        Some(bounds) => bounds,
+        None => return Ok(semver::VersionReq { comparators: alloc::vec![semver_comparator(Op::Less, &[0, 0, 0])] }),
+    };
+
+    let zero = N::zero();
+    let top = N::max() - N::one();
+
+    let mut comparators = alloc::vec::Vec::new();
+    if lower != [zero; 3] {
+        let lower = [to_u64(lower[0])?, to_u64(lower[1])?, to_u64(lower[2])?];
+        comparators.push(semver_comparator(Op::GreaterEq, &lower));
+    }
+    let len = upper.iter().rposition(|c| *c != top).map_or(0, |i| i + 1);
+    if len > 0 {
+        let mut parts = [0; 3];
+        for (part, c) in parts.iter_mut().zip(&upper[..len]) {
+            *part = to_u64(*c)?;
+        }
+        comparators.push(semver_comparator(Op::LessEq, &parts[..len]));
+    }
+
+    Ok(semver::VersionReq { comparators })
+}
+
+macro_rules! impl_into_semver {
+    ($($t:ty),*) => {
+        $(
+            impl From<Version<$t>> for semver::Version {
+                fn from(value: Version<$t>) -> Self {
+                    Self {
+                        major: value.major as u64,
+                        minor: value.minor as u64,
+                        patch: value.patch as u64,
+                        pre: Prerelease::EMPTY,
+                        build: BuildMetadata::EMPTY,
+                    }
+                }
+            }
+
+            impl From<&VersionReq<$t>> for semver::VersionReq {
+                fn from(value: &VersionReq<$t>) -> Self {
+                    match semver_req(value.normalized_bounds(), |c| Ok::<_, Infallible>(c as u64)) {
+                        Ok(req) => req,
+                        Err(never) => match never {},
+                    }
+                }
+            }
+
+            impl From<VersionReq<$t>> for semver::VersionReq {
+                fn from(value: VersionReq<$t>) -> Self {
+                    Self::from(&value)
+                }
+            }
+        )*
+    };
+}
+
+// Valid components are never negative, so the casts can't wrap.
+impl_into_semver!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Components that exceed `u64::MAX` don't fit into `semver`, the conversion returns
+/// `OutOfRange(u64::MAX)` for them.
+macro_rules! impl_try_into_semver {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Version<$t>> for semver::Version {
+                type Error = SemverConversionError;
+
+                fn try_from(value: Version<$t>) -> Result<Self, Self::Error> {
+                    let component = |c: $t| u64::try_from(c).map_err(|_| SemverConversionError::OutOfRange(u64::MAX));
+                    Ok(semver::Version::new(component(value.major)?, component(value.minor)?, component(value.patch)?))
+                }
+            }
+
+            impl TryFrom<&VersionReq<$t>> for semver::VersionReq {
+                type Error = SemverConversionError;
+
+                fn try_from(value: &VersionReq<$t>) -> Result<Self, Self::Error> {
+                    semver_req(value.normalized_bounds(), |c| u64::try_from(c).map_err(|_| SemverConversionError::OutOfRange(u64::MAX)))
+                }
+            }
+
+            impl TryFrom<VersionReq<$t>> for semver::VersionReq {
+                type Error = SemverConversionError;
+
+                fn try_from(value: VersionReq<$t>) -> Result<Self, Self::Error> {
+                    Self::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_into_semver!(u128, i128);
+
+#[cfg(test)]
+mod semver_tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    use rand::Rng;
+
+    use super::SemverConversionError;
+    use crate::{Version, VersionReq};
+
+    fn semver_req(input: &str) -> semver::VersionReq {
+        semver::VersionReq::parse(input).unwrap()
+    }
+
+    #[test]
+    fn version_test() {
+        let version: Version<u32> = semver::Version::parse("1.2.3").unwrap().try_into().unwrap();
+        assert_eq!(version, Version::new(1, 2, 3).unwrap());
+        assert_eq!(semver::Version::from(version), semver::Version::new(1, 2, 3));
+
+        let version: Version<i8> = semver::Version::new(0, 127 - 1, 3).try_into().unwrap();
+        assert_eq!(semver::Version::from(version), semver::Version::new(0, 126, 3));
+    }
+
+    #[test]
+    fn version_error_test() {
+        let convert = |input: &str| Version::<u8>::try_from(semver::Version::parse(input).unwrap());
+        assert_eq!(convert("1.2.3-alpha"), Err(SemverConversionError::Prerelease));
+        assert_eq!(convert("1.2.3+build.5"), Err(SemverConversionError::BuildMetadata));
+        assert_eq!(convert("1.256.3"), Err(SemverConversionError::OutOfRange(256)));
+        assert_eq!(convert("1.2.255"), Err(SemverConversionError::OutOfRange(255)));
+        assert!(convert("1.2.254").is_ok());
+    }
+
+    #[test]
+    fn version_req_test() {
+        for (input, expected) in [
+            ("^1.2", ">=1.2, <=1"),
+            ("~1.2.3", ">=1.2.3, <=1.2"),
+            ("=1.2.3", "=1.2.3"),
+            ("=1.2", "1.2.*"),
+            ("1.*", "1.*"),
+            ("*", "*"),
+            (">1.2, <1.5.0", ">=1.3, <=1.4"),
+        ] {
+            let converted = VersionReq::<u64>::try_from(semver_req(input)).unwrap();
+            assert_eq!(converted, VersionReq::parse(expected).unwrap(), "{}", input);
+        }
+
+        // `semver` accepts requirements without versions, they convert to an empty requirement.
+        let empty = VersionReq::<u64>::from_bounds([0, 0, 1], [0; 3]);
+        for input in ["<0.0.0", ">=2, <1"] {
+            assert_eq!(VersionReq::<u64>::try_from(semver_req(input)).unwrap(), empty, "{}", input);
+        }
+
+        assert_eq!(semver::VersionReq::from(VersionReq::<u64>::parse("^1.2").unwrap()), semver_req(">=1.2.0, <=1"));
+        assert_eq!(semver::VersionReq::from(VersionReq::<u64>::parse("~1.2.3").unwrap()), semver_req(">=1.2.3, <=1.2"));
+        assert_eq!(semver::VersionReq::from(VersionReq::<u64>::parse("*").unwrap()), semver::VersionReq::STAR);
+        assert_eq!(semver::VersionReq::from(VersionReq::<u8>::from_bounds([2, 0, 0], [0; 3])), semver_req("<0.0.0"));
+    }
+
+    #[test]
+    fn version_req_error_test() {
+        let convert = |input: &str| VersionReq::<u8>::try_from(semver_req(input));
+        assert_eq!(convert(">=1.2.3-alpha"), Err(SemverConversionError::Prerelease));
+        assert_eq!(convert("^1.300"), Err(SemverConversionError::OutOfRange(300)));
+        assert_eq!(convert("<=255"), Err(SemverConversionError::OutOfRange(255)));
+        assert!(convert("<=254").is_ok());
+    }
+
+    #[test]
+    fn wide_test() {
+        let timestamp = u64::MAX as u128 + 1;
+        let version = Version::<u128>::new(1, 2, timestamp).unwrap();
+        assert_eq!(semver::Version::try_from(version), Err(SemverConversionError::OutOfRange(u64::MAX)));
+        let version = Version::<u128>::new(1, 2, 20240101).unwrap();
+        assert_eq!(semver::Version::try_from(version), Ok(semver::Version::new(1, 2, 20240101)));
+        assert_eq!(Version::<i128>::try_from(semver::Version::new(1, 2, 3)), Ok(Version::new(1, 2, 3).unwrap()));
+
+        assert_eq!(semver::VersionReq::try_from(VersionReq::<u128>::parse("^1.2").unwrap()), Ok(semver_req(">=1.2.0, <=1")));
+        assert_eq!(semver::VersionReq::try_from(VersionReq::<u128>::parse("*").unwrap()), Ok(semver::VersionReq::STAR));
+        let wide = VersionReq::<u128>::parse(&alloc::format!(">={}", timestamp)).unwrap();
+        assert_eq!(semver::VersionReq::try_from(wide), Err(SemverConversionError::OutOfRange(u64::MAX)));
+    }
+
+    fn random_comparator(rng: &mut impl Rng) -> String {
+        const OPERATORS: [&str; 8] = ["", "=", ">", ">=", "<", "<=", "~", "^"];
+        let op = OPERATORS[rng.gen_range(0..OPERATORS.len())];
+        let mut parts: Vec<String> = (0..rng.gen_range(1..=3)).map(|_| rng.gen_range(0..=3).to_string()).collect();
+        if (op.is_empty() || op == "=") && parts.len() < 3 && rng.gen_bool(0.3) {
+            parts.push("*".to_string());
+        }
+        alloc::format!("{}{}", op, parts.join("."))
+    }
+
+    /// `fits` agrees with `semver::VersionReq::matches` on requirements converted from `semver`,
+    /// on the same input parsed by this crate and after converting back to `semver`.
+    #[test]
+    fn differential_test() {
+        let mut rng = rand::thread_rng();
+        let grid: Vec<Version<u64>> = (0..=4)
+            .flat_map(|major| (0..=4).flat_map(move |minor| (0..=4).map(move |patch| Version::new(major, minor, patch).unwrap())))
+            .collect();
+
+        for _ in 0..5000 {
+            let input = (0..rng.gen_range(1..=3)).map(|_| random_comparator(&mut rng)).collect::<Vec<_>>().join(", ");
+            let expected = semver_req(&input);
+            let converted = VersionReq::<u64>::try_from(&expected).unwrap();
+            let parsed = VersionReq::<u64>::parse(&input).ok();
+            let back = semver::VersionReq::from(&converted);
+
+            for version in &grid {
+                let matches = expected.matches(&semver::Version::from(*version));
+                assert_eq!(converted.fits(*version), matches, "{} converted as {} on {}", input, converted, version);
+                assert_eq!(back.matches(&semver::Version::from(*version)), matches, "{} converted back as {} on {}", input, back, version);
+                if let Some(parsed) = &parsed {
+                    assert_eq!(parsed.fits(*version), matches, "{} parsed as {} on {}", input, parsed, version);
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,23 @@
+//! Serde support for [`Version`] and [`VersionReq`].
+//!
+//! Human readable formats use the string form, `"1.2.3"` and `"^1.2"`. Binary formats use a
+//! compact tuple: `(major, minor, patch)` for a version and `(lower, upper)` for a requirement,
+//! where both bounds are the inclusive smallest and largest accepted version. A requirement that
+//! accepts nothing is encoded with `lower > upper`.
+//!
+//! Deserialization validates every component through the same rules as [`Version::new`].
+
 #[cfg(feature = "serde")]
-use serde::{Serialize, de::DeserializeOwned};
+use core::{fmt, marker::PhantomData, str::FromStr};
 
 #[cfg(feature = "serde")]
-use crate::{Version, VersionReq, version_req::{VersionComperatorUpper, VersionComperatorLower}};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned};
 
 #[cfg(feature = "serde")]
-use thiserror::Error;
+use crate::{version::VersionNumber, Version, VersionReq};
+
+use crate::offset::{OffsetI128, OffsetI16, OffsetI32, OffsetI64, OffsetI8, OffsetIsize};
+use crate::offset::{OffsetU128, OffsetU16, OffsetU32, OffsetU64, OffsetU8, OffsetUsize};
 
 #[cfg(feature = "serde")]
 pub trait SerdeNumber: Serialize + DeserializeOwned {}
@@ -33,164 +45,220 @@ impl SerdeNumber for i64 {}
 
 impl SerdeNumber for isize {}
 
-#[cfg(feature = "serde")]
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct VersionSerde {
-    pub major: u64,
-    pub minor: u64,
-    pub patch: u64
-}
+impl SerdeNumber for u128 {}
+
+impl SerdeNumber for i128 {}
+
+impl SerdeNumber for OffsetU8 {}
+
+impl SerdeNumber for OffsetU16 {}
+
+impl SerdeNumber for OffsetU32 {}
+
+impl SerdeNumber for OffsetU64 {}
+
+impl SerdeNumber for OffsetUsize {}
+
+impl SerdeNumber for OffsetU128 {}
+
+impl SerdeNumber for OffsetI8 {}
 
+impl SerdeNumber for OffsetI16 {}
+
+impl SerdeNumber for OffsetI32 {}
+
+impl SerdeNumber for OffsetI64 {}
+
+impl SerdeNumber for OffsetIsize {}
+
+impl SerdeNumber for OffsetI128 {}
+
+/// Offset components are written as their component value, like the primitive they wrap.
 #[cfg(feature = "serde")]
-impl From<Version<u64>> for VersionSerde {
-    fn from(v: Version<u64>) -> Self {
-        Self {
-            major: v.major,
-            minor: v.minor,
-            patch: v.patch
-        }
-    }
+macro_rules! impl_offset_serde {
+    ($($t:ty => $prim:ty),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.get().serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value = <$prim>::deserialize(deserializer)?;
+                    Self::new(value).ok_or_else(|| de::Error::custom(format_args!("component {} is out of range", value)))
+                }
+            }
+        )*
+    };
 }
 
 #[cfg(feature = "serde")]
-impl TryFrom<VersionSerde> for Version<u64> {
-    type Error = VersionSerdeError;
+impl_offset_serde!(
+    OffsetU8 => u8, OffsetU16 => u16, OffsetU32 => u32, OffsetU64 => u64, OffsetUsize => usize, OffsetU128 => u128,
+    OffsetI8 => i8, OffsetI16 => i16, OffsetI32 => i32, OffsetI64 => i64, OffsetIsize => isize, OffsetI128 => i128
+);
 
-    fn try_from(value: VersionSerde) -> Result<Self, Self::Error> {
-        let major = value.major;
-        let minor = value.minor;
-        let patch = value.patch;
+/// Deserializes the string form of `T` through its `FromStr` implementation.
+#[cfg(feature = "serde")]
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
 
-        Version::try_new(major, minor, patch).ok_or(VersionSerdeError::VersionInvalid)
+#[cfg(feature = "serde")]
+impl<T> FromStrVisitor<T> {
+    fn new(expecting: &'static str) -> Self {
+        Self { expecting, marker: PhantomData }
     }
 }
 
 #[cfg(feature = "serde")]
-impl From<Version<u32>> for VersionSerde {
-    fn from(v: Version<u32>) -> Self {
-        let major = v.major as u64;
-        let minor = v.minor as u64;
-        let patch = v.patch as u64;
-
-        Self {
-            major,
-            minor,
-            patch
-        }
+impl<'de, T> de::Visitor<'de> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::from_str(v).map_err(E::custom)
     }
 }
 
 #[cfg(feature = "serde")]
-#[derive(Debug, Error)]
-pub enum VersionSerdeError {
-    #[error("The number is to big for conversion")]
-    NumberToBig,
-    #[error("The version is invalid")]
-    VersionInvalid
+fn version_from_triple<N: VersionNumber, E: de::Error>([major, minor, patch]: [N; 3]) -> Result<Version<N>, E> {
+    Version::new(major, minor, patch)
+        .map_err(|e| E::custom(format_args!("invalid version {}.{}.{}: {:?}", major, minor, patch, e)))
 }
 
 #[cfg(feature = "serde")]
-fn convert_to_u32(input: u64) -> Result<u32, VersionSerdeError> {
-    const U32_MAX: u64 = {
-        u32::MAX as u64
-    };
-
-    if input > U32_MAX {
-        return Err(VersionSerdeError::NumberToBig);
+impl<N: VersionNumber> Serialize for Version<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            (self.major, self.minor, self.patch).serialize(serializer)
+        }
     }
-    Ok(input as u32)
 }
 
 #[cfg(feature = "serde")]
-impl TryFrom<VersionSerde> for Version<u32> {
-    type Error = VersionSerdeError;
-    fn try_from(value: VersionSerde) -> Result<Self, Self::Error> {
-        let major = convert_to_u32(value.major)?;
-        let minor = convert_to_u32(value.minor)?;
-        let patch = convert_to_u32(value.patch)?;
-
-        Version::try_new(major, minor, patch).ok_or(VersionSerdeError::VersionInvalid)
+impl<'de, N: VersionNumber> Deserialize<'de> for Version<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(FromStrVisitor::new("a version such as \"1.2.3\""))
+        } else {
+            let (major, minor, patch) = <(N, N, N)>::deserialize(deserializer)?;
+            version_from_triple([major, minor, patch])
+        }
     }
 }
 
 #[cfg(feature = "serde")]
-impl From<Version<usize>> for VersionSerde {
-    fn from(v: Version<usize>) -> Self {
-       let major = v.major as u64; 
-       let minor = v.minor as u64;
-       let patch = v.patch as u64;
+impl<N: VersionNumber> Serialize for VersionReq<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.collect_str(self);
+        }
 
-       Self { major, minor, patch }
+        let zero = N::zero();
+        let (lower, upper) = self
+            .normalized_bounds()
+            .unwrap_or(([zero, zero, N::one()], [zero, zero, zero]));
+        (lower, upper).serialize(serializer)
     }
 }
 
 #[cfg(feature = "serde")]
-fn convert_to_usize(input: u64) -> Result<usize, VersionSerdeError> {
-    const USIZE_MAX: u64 = {
-        usize::MAX as u64 
-    };
-
-    if input > USIZE_MAX {
-        return Err(VersionSerdeError::NumberToBig);
+impl<'de, N: VersionNumber> Deserialize<'de> for VersionReq<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(FromStrVisitor::new("a version requirement such as \"^1.2\""))
+        } else {
+            let (lower, upper) = <([N; 3], [N; 3])>::deserialize(deserializer)?;
+            let lower = version_from_triple::<N, D::Error>(lower)?;
+            let upper = version_from_triple::<N, D::Error>(upper)?;
+            Ok(VersionReq::from_bounds(
+                [lower.major, lower.minor, lower.patch],
+                [upper.major, upper.minor, upper.patch],
+            ))
+        }
     }
-
-    Ok(input as usize)
 }
 
-#[cfg(feature = "serde")]
-impl TryFrom<VersionSerde> for Version<usize> {
-    type Error = VersionSerdeError;
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use alloc::string::ToString;
 
-    fn try_from(value: VersionSerde) -> Result<Self, Self::Error> {
-        let major = convert_to_usize(value.major)?;
-        let minor = convert_to_usize(value.minor)?;
-        let patch = convert_to_usize(value.patch)?;
+    use crate::offset::OffsetU16;
+    use crate::{Version, VersionReq};
 
-        Version::try_new(major, minor, patch).ok_or(VersionSerdeError::VersionInvalid)
+    fn ver(input: &str) -> Version<u32> {
+        input.parse().unwrap()
     }
-}
 
-#[cfg(feature = "serde")]
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct VersionReqSerde {
-    comperator_lower: VersionComperatorLower,
-    comperator_higher: VersionComperatorUpper,
-    major_lower: u64,
-    minor_lower: u64,
-    patch_lower: u64,
-    major_upper: u64,
-    minor_upper: u64,
-    patch_upper: u64
-}
+    fn req(input: &str) -> VersionReq<u32> {
+        input.parse().unwrap()
+    }
 
-#[cfg(feature = "serde")]
-impl From<VersionReq<u64>> for VersionReqSerde {
-   fn from(v: VersionReq<u64>) -> Self {
-       Self {
-           comperator_lower: v.comperator_lower,
-           comperator_higher: v.comperator_higher,
-           major_lower: v.major_lower,
-           minor_lower: v.minor_lower,
-           patch_lower: v.patch_lower,
-           major_upper: v.major_upper,
-           minor_upper: v.minor_upper,
-           patch_upper: v.patch_upper
-       }
-   }
-}
+    #[test]
+    fn human_readable_test() {
+        assert_eq!(serde_json::to_string(&ver("1.2.3")).unwrap(), "\"1.2.3\"");
+        assert_eq!(serde_json::from_str::<Version<u32>>("\"1.2.3\"").unwrap(), ver("1.2.3"));
 
-#[cfg(feature = "serde")]
-impl From<VersionReqSerde> for VersionReq<u64> {
-    fn from(v: VersionReqSerde) -> Self {
-        Self {
-            comperator_lower: v.comperator_lower,
-            comperator_higher: v.comperator_higher,
-            major_lower: v.major_lower,
-            minor_lower: v.minor_lower,
-            patch_lower: v.patch_lower,
-            major_upper: v.major_upper,
-            minor_upper: v.minor_upper,
-            patch_upper: v.patch_upper
+        for input in ["^1.2", "~1.2.3", ">=1.0.0, <1.5.0", "1.*", "*", "=1.2.3"] {
+            let json = serde_json::to_string(&req(input)).unwrap();
+            assert_eq!(json, alloc::format!("\"{}\"", req(input)));
+            assert_eq!(serde_json::from_str::<VersionReq<u32>>(&json).unwrap(), req(input));
+        }
+    }
+
+    #[test]
+    fn compact_test() {
+        let bytes = bincode::serialize(&ver("1.2.3")).unwrap();
+        assert_eq!(bytes.len(), 3 * 4);
+        assert_eq!(bincode::deserialize::<Version<u32>>(&bytes).unwrap(), ver("1.2.3"));
+
+        for input in ["^1.2", "~1.2.3", ">=1.0.0, <1.5.0", "1.*", "*"] {
+            let bytes = bincode::serialize(&req(input)).unwrap();
+            assert_eq!(bytes.len(), 6 * 4);
+            let back = bincode::deserialize::<VersionReq<u32>>(&bytes).unwrap();
+            assert_eq!(back, req(input), "{}", input);
         }
+        let empty = VersionReq::<u32>::from_bounds([3, 0, 0], [0; 3]);
+        assert_eq!(bincode::deserialize::<VersionReq<u32>>(&bincode::serialize(&empty).unwrap()).unwrap(), empty);
+        assert_eq!(bincode::deserialize::<VersionReq<u32>>(&bincode::serialize(&req("1.*")).unwrap()).unwrap().to_string(), "1.*");
+
+        let wide = Version::<u128>::new(1, 2, u64::MAX as u128 + 1).unwrap();
+        assert_eq!(bincode::deserialize::<Version<u128>>(&bincode::serialize(&wide).unwrap()).unwrap(), wide);
+        assert_eq!(serde_json::from_str::<Version<i128>>(&serde_json::to_string(&wide).unwrap()).unwrap().patch, u64::MAX as i128 + 1);
+
+        // Offset components are written like the primitive they wrap.
+        let offset = "1.2.3".parse::<Version<OffsetU16>>().unwrap();
+        let bytes = bincode::serialize(&offset).unwrap();
+        assert_eq!(bytes, bincode::serialize(&Version::<u16>::new(1, 2, 3).unwrap()).unwrap());
+        assert_eq!(bincode::deserialize::<Version<OffsetU16>>(&bytes).unwrap(), offset);
+        assert!(bincode::deserialize::<Version<OffsetU16>>(&bincode::serialize(&(1_u16, 2_u16, u16::MAX)).unwrap()).is_err());
+    }
+
+    #[test]
+    fn validation_test() {
+        assert!(serde_json::from_str::<Version<u8>>("\"1.2.255\"").is_err());
+        assert!(serde_json::from_str::<Version<u32>>("\"1.2\"").is_err());
+        assert!(serde_json::from_str::<Version<u32>>("[1, 2, 3]").is_err());
+        assert!(serde_json::from_str::<VersionReq<u32>>("\"^1.x.3\"").is_err());
+
+        let bytes = bincode::serialize(&(1u8, 2u8, 255u8)).unwrap();
+        assert!(bincode::deserialize::<Version<u8>>(&bytes).is_err());
+        let bytes = bincode::serialize(&(-1i8, 2i8, 3i8)).unwrap();
+        assert!(bincode::deserialize::<Version<i8>>(&bytes).is_err());
+        let bytes = bincode::serialize(&([1u8, 0, 0], [1u8, 255, 255])).unwrap();
+        assert!(bincode::deserialize::<VersionReq<u8>>(&bytes).is_err());
     }
 }
@@ -1,61 +1,184 @@
-#[cfg(nightly)]
 use core::ops::BitAnd;
 
+use crate::offset::{OffsetI128, OffsetI16, OffsetI32, OffsetI64, OffsetI8, OffsetIsize};
+use crate::offset::{OffsetU128, OffsetU16, OffsetU32, OffsetU64, OffsetU8, OffsetUsize};
+
 
 cfg_if::cfg_if! {
     if #[cfg(nightly)] {
-        use core::simd::SimdElement;
+        use core::simd::{Simd, cmp::{SimdPartialEq, SimdPartialOrd}};
         use crate::version::VersionNumber;
-        pub trait PortableSimdElement: SimdElement + PartialEq {}
-    } else {
-        pub trait PortableSimdElement: PartialEq {}
-    }
-}
 
-impl PortableSimdElement for u8 {}
+        pub trait PortableSimdElement: PartialEq {
+            /// Bitmask of the lanes where `a != b`.
+            fn simd_ne_mask(a: [Self; 4], b: [Self; 4]) -> u64 where Self: Sized;
+            /// Bitmask of the lanes where `a < b`.
+            fn simd_lt_mask(a: [Self; 4], b: [Self; 4]) -> u64 where Self: Sized;
+            /// Bitmask of the lanes where `a > b`.
+            fn simd_gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 where Self: Sized;
+        }
 
-impl PortableSimdElement for u16 {}
+        macro_rules! impl_portable_simd_element {
+            ($($t:ty),*) => {
+                $(
+                    impl PortableSimdElement for $t {
+                        #[inline]
+                        fn simd_ne_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            Simd::from_array(a).simd_ne(Simd::from_array(b)).to_bitmask()
+                        }
+
+                        #[inline]
+                        fn simd_lt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            Simd::from_array(a).simd_lt(Simd::from_array(b)).to_bitmask()
+                        }
+
+                        #[inline]
+                        fn simd_gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            Simd::from_array(a).simd_gt(Simd::from_array(b)).to_bitmask()
+                        }
+                    }
+                )*
+            };
+        }
 
-impl PortableSimdElement for u32 {}
+        impl_portable_simd_element!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+        // `core::simd` has no 128 bit lanes, these compare lane by lane.
+        macro_rules! impl_serial_simd_element {
+            ($($t:ty),*) => {
+                $(
+                    impl PortableSimdElement for $t {
+                        #[inline]
+                        fn simd_ne_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            serial_mask(a, b, |a, b| a != b)
+                        }
+
+                        #[inline]
+                        fn simd_lt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            serial_mask(a, b, |a, b| a < b)
+                        }
+
+                        #[inline]
+                        fn simd_gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            serial_mask(a, b, |a, b| a > b)
+                        }
+                    }
+                )*
+            };
+        }
 
-impl PortableSimdElement for u64 {}
+        #[inline]
+        fn serial_mask<T: Copy>(a: [T; 4], b: [T; 4], f: impl Fn(T, T) -> bool) -> u64 {
+            (0..4).fold(0, |mask, i| mask | ((f(a[i], b[i]) as u64) << i))
+        }
 
-impl PortableSimdElement for usize {}
+        impl_serial_simd_element!(u128, i128);
+    } else {
+        use crate::arch::ArchLanes;
+        use crate::version::VersionNumber;
 
-impl PortableSimdElement for i8 {}
+        pub trait PortableSimdElement: PartialEq {
+            /// Whether the masks below run on vector instructions on this machine, see
+            /// [`crate::arch`].
+            fn simd_available() -> bool;
+            /// Bitmask of the lanes where `a != b`.
+            fn simd_ne_mask(a: [Self; 4], b: [Self; 4]) -> u64 where Self: Sized;
+            /// Bitmask of the lanes where `a < b`.
+            fn simd_lt_mask(a: [Self; 4], b: [Self; 4]) -> u64 where Self: Sized;
+            /// Bitmask of the lanes where `a > b`.
+            fn simd_gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 where Self: Sized;
+        }
 
-impl PortableSimdElement for i16 {}
+        macro_rules! impl_portable_simd_element {
+            ($($t:ty),*) => {
+                $(
+                    impl PortableSimdElement for $t {
+                        #[inline]
+                        fn simd_available() -> bool {
+                            <$t as ArchLanes>::available()
+                        }
+
+                        #[inline]
+                        fn simd_ne_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            !<$t as ArchLanes>::eq_mask(a, b) & 0b1111
+                        }
+
+                        #[inline]
+                        fn simd_lt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            <$t as ArchLanes>::gt_mask(b, a)
+                        }
+
+                        #[inline]
+                        fn simd_gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                            <$t as ArchLanes>::gt_mask(a, b)
+                        }
+                    }
+                )*
+            };
+        }
 
-impl PortableSimdElement for i32 {}
+        impl_portable_simd_element!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, u128, i128);
+    }
+}
 
-impl PortableSimdElement for i64 {}
+// The stored values of offset components are ordered like the components, so they compare
+// with the lanes of the wrapped primitive.
+macro_rules! impl_offset_simd_element {
+    ($($t:ty => $prim:ty),*) => {
+        $(
+            impl PortableSimdElement for $t {
+                #[cfg(not(nightly))]
+                #[inline]
+                fn simd_available() -> bool {
+                    <$prim as PortableSimdElement>::simd_available()
+                }
+
+                #[inline]
+                fn simd_ne_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                    <$prim as PortableSimdElement>::simd_ne_mask(a.map(|x| x.raw().get()), b.map(|x| x.raw().get()))
+                }
+
+                #[inline]
+                fn simd_lt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                    <$prim as PortableSimdElement>::simd_lt_mask(a.map(|x| x.raw().get()), b.map(|x| x.raw().get()))
+                }
+
+                #[inline]
+                fn simd_gt_mask(a: [Self; 4], b: [Self; 4]) -> u64 {
+                    <$prim as PortableSimdElement>::simd_gt_mask(a.map(|x| x.raw().get()), b.map(|x| x.raw().get()))
+                }
+            }
+        )*
+    };
+}
 
-impl PortableSimdElement for isize {}
+impl_offset_simd_element!(
+    OffsetU8 => u8, OffsetU16 => u16, OffsetU32 => u32, OffsetU64 => u64, OffsetUsize => usize, OffsetU128 => u128,
+    OffsetI8 => i8, OffsetI16 => i16, OffsetI32 => i32, OffsetI64 => i64, OffsetIsize => isize, OffsetI128 => i128
+);
 
-#[cfg(nightly)]
 #[inline]
 pub fn fast_compare_simd<N: VersionNumber>(major: N, minor: N, patch: N) -> bool {
     let max = N::max();
-    let min = N::min();
-    let one = N::one();
+    let zero = N::zero();
     let max_arr = [ max, max, max, max ];
-    let min_arr = [ min, min, min, min ];
-    let simd_arr = [ major, minor, patch, max - one];
+    let zero_arr = [ zero, zero, zero, zero ];
+    let simd_arr = [ major, minor, patch, zero ];
 
+    let max_mask = N::simd_ne_mask(simd_arr, max_arr);
+    let negative_mask = N::simd_lt_mask(simd_arr, zero_arr);
 
-    let max_simd = core::simd::Simd::from_array(max_arr);
-    let min_simd = core::simd::Simd::from_array(min_arr);
-    let version_simd = core::simd::Simd::from_array(simd_arr);
-
-    let max_mask = version_simd.lanes_ne(max_simd);
-    let min_mask = version_simd.lanes_ne(min_simd);
-
-    let and_mask = max_mask.bitand(min_mask);
+    max_mask == 0b1111 && negative_mask == 0
+}
 
-    and_mask.all()
+/// Lexicographic `>=` from the lane masks of `a > b` and `a < b`: the first differing lane decides.
+#[inline]
+fn lexicographic_ge(gt_mask: u64, lt_mask: u64) -> bool {
+    let ne_mask = gt_mask | lt_mask;
+    let first_ne = ne_mask & ne_mask.wrapping_neg();
+    gt_mask.bitand(first_ne) == first_ne
 }
 
-#[cfg(nightly)]
 #[inline]
 pub fn simd_version_req<N: VersionNumber>(ver: [N; 3], lower: [N; 3], upper: [N; 3]) -> bool {
     let one = N::one();
@@ -63,16 +186,41 @@ pub fn simd_version_req<N: VersionNumber>(ver: [N; 3], lower: [N; 3], upper: [N;
     let lower_arr = [ lower[0], lower[1], lower[2], one ];
     let upper_arr = [ upper[0], upper[1], upper[2], one ];
 
-    let ver_simd = core::simd::Simd::from_array(ver_arr);
-    let lower_simd = core::simd::Simd::from_array(lower_arr);
-    let upper_simd = core::simd::Simd::from_array(upper_arr);
+    let lower_ok = lexicographic_ge(N::simd_gt_mask(ver_arr, lower_arr), N::simd_lt_mask(ver_arr, lower_arr));
+    let upper_ok = lexicographic_ge(N::simd_lt_mask(ver_arr, upper_arr), N::simd_gt_mask(ver_arr, upper_arr));
 
-    let ge_mask = ver_simd.lanes_ge(lower_simd);
-    let le_mask = ver_simd.lanes_le(upper_simd);
+    lower_ok && upper_ok
+}
 
-    let and_mask = ge_mask.bitand(le_mask);
+/// Evaluates four versions against the bounds at once, bit `i` of the result is set if
+/// `vers[i]` fits.
+///
+/// The versions are transposed into one vector per component, so every comparison covers all
+/// four versions.
+#[inline]
+pub fn simd_version_req_batch<N: VersionNumber>(vers: [[N; 3]; 4], lower: [N; 3], upper: [N; 3]) -> u64 {
+    const ALL: u64 = 0b1111;
+
+    let columns: [[N; 4]; 3] = core::array::from_fn(|c| core::array::from_fn(|lane| vers[lane][c]));
+
+    let mut lower_ok = ALL;
+    let mut upper_ok = ALL;
+    // Walks from the patch to the major component, the more significant component decides
+    // wherever it differs.
+    for c in (0..3).rev() {
+        let lower_arr = [lower[c]; 4];
+        let upper_arr = [upper[c]; 4];
+
+        let lower_gt = N::simd_gt_mask(columns[c], lower_arr);
+        let lower_lt = N::simd_lt_mask(columns[c], lower_arr);
+        lower_ok = lower_gt | (!(lower_gt | lower_lt) & ALL & lower_ok);
+
+        let upper_lt = N::simd_lt_mask(columns[c], upper_arr);
+        let upper_gt = N::simd_gt_mask(columns[c], upper_arr);
+        upper_ok = upper_lt | (!(upper_lt | upper_gt) & ALL & upper_ok);
+    }
 
-    and_mask.all()
+    lower_ok & upper_ok
 }
 
 #[cfg(test)]
@@ -80,9 +228,12 @@ mod compare_tests {
     use rand::{thread_rng, distributions::uniform::SampleUniform, Rng};
 
     use crate::{version::{VersionNumber, serial_compare}, Version};
+    use crate::offset::{OffsetI128, OffsetI16, OffsetI32, OffsetI64, OffsetI8, OffsetIsize};
+    use crate::offset::{OffsetU128, OffsetU16, OffsetU32, OffsetU64, OffsetU8, OffsetUsize};
+
+    use super::{fast_compare_simd, simd_version_req, simd_version_req_batch};
 
-    #[cfg(nightly)]
-    use super::fast_compare_simd;
+    use crate::version_req::{serial_version_req, serial_version_req_batch};
 
     const SAMPLE_SIZE: usize = 1_000;
 
@@ -90,12 +241,13 @@ mod compare_tests {
     fn generate_version_part<N: VersionNumber + SampleUniform>() -> (N, bool) {
         let min = N::min();
         let max = N::max();
+        let zero = N::zero();
 
         let mut rng = thread_rng();
 
         let ret_num: N = rng.gen_range(min..=max); 
 
-        (ret_num, ret_num == max || ret_num == min)
+        (ret_num, ret_num == max || ret_num < zero)
     }
 
     fn validity_test<N: VersionNumber + SampleUniform + core::fmt::Display + core::fmt::Debug>() {
@@ -106,13 +258,11 @@ mod compare_tests {
         let expected_result = (!major_valid) && (!minor_valid) && (!patch_valid); 
 
         
-        #[cfg(nightly)]
         let simd_result = fast_compare_simd(major, minor, patch);
 
 
         let serial_result = crate::version::serial_compare(major, minor, patch);
 
-        #[cfg(nightly)]
         assert_eq!(expected_result, simd_result, "SIMD doesn't perform as expected");
         
         assert_eq!(expected_result, serial_result, "Serial doesn't perform as expected; Major: {}, Minor: {}, Patch: {}", major, minor, patch);
@@ -130,16 +280,34 @@ mod compare_tests {
 
     }
 
-    #[cfg(nightly)]
-    fn simd_test<N: VersionNumber>(min: N, max: N) {
-        let mut simd_result = fast_compare_simd(min, min, min);
+    fn simd_test<N: VersionNumber>(zero: N, max: N) {
+        let mut simd_result = fast_compare_simd(zero, zero, zero);
 
-        assert!(!simd_result);
+        assert!(simd_result);
 
         simd_result = fast_compare_simd(max, max, max);
 
         assert!(!simd_result);
 
+        simd_result = fast_compare_simd(zero, zero, max);
+
+        assert!(!simd_result);
+    }
+
+    fn req_agreement_test<N: VersionNumber + SampleUniform + core::fmt::Debug>() {
+        // Small components make equal lanes likely, which is where the lexicographic order matters.
+        let mut rng = thread_rng();
+        let zero = N::zero();
+        let upper = zero + N::one() + N::one() + N::one();
+        let mut triple = || -> [N; 3] { [rng.gen_range(zero..=upper), rng.gen_range(zero..=upper), rng.gen_range(zero..=upper)] };
+
+        (0..SAMPLE_SIZE).for_each(|_| {
+            let (ver, lower, upper) = (triple(), triple(), triple());
+            assert_eq!(simd_version_req(ver, lower, upper), serial_version_req(ver, lower, upper), "Version: {:?}, Lower: {:?}, Upper: {:?}", ver, lower, upper);
+
+            let vers = [triple(), triple(), triple(), triple()];
+            assert_eq!(simd_version_req_batch(vers, lower, upper), serial_version_req_batch(vers, lower, upper), "Versions: {:?}, Lower: {:?}, Upper: {:?}", vers, lower, upper);
+        });
     }
 
     fn whole_test<N: VersionNumber + SampleUniform + core::fmt::Display + core::fmt::Debug>() {
@@ -147,20 +315,28 @@ mod compare_tests {
             validity_test::<N>();
         });
 
-        let min = N::min();
+        let zero = N::zero();
         let max = N::max();
 
 
-        #[cfg(nightly)]
-        simd_test(min, max);
+        simd_test(zero, max);
 
-        let mut serial_result = serial_compare(min, min, min);
+        req_agreement_test::<N>();
 
-        assert!(!serial_result);
+        let mut serial_result = serial_compare(zero, zero, zero);
+
+        assert!(serial_result);
 
         serial_result = serial_compare(max, max, max);
 
         assert!(!serial_result);
+
+        serial_result = serial_compare(zero, zero, max);
+
+        assert!(!serial_result);
+
+        assert!(Version::new(zero, zero, zero).is_ok());
+        assert!(Version::try_new(zero, zero, zero).is_some());
     }
 
     #[test]
@@ -212,4 +388,74 @@ mod compare_tests {
     fn validity_isize_test() {
         whole_test::<isize>();
     }
+
+    #[test]
+    fn validity_u128_test() {
+        whole_test::<u128>();
+    }
+
+    #[test]
+    fn validity_i128_test() {
+        whole_test::<i128>();
+    }
+
+    #[test]
+    fn validity_offset_u8_test() {
+        whole_test::<OffsetU8>();
+    }
+
+    #[test]
+    fn validity_offset_u16_test() {
+        whole_test::<OffsetU16>();
+    }
+
+    #[test]
+    fn validity_offset_u32_test() {
+        whole_test::<OffsetU32>();
+    }
+
+    #[test]
+    fn validity_offset_u64_test() {
+        whole_test::<OffsetU64>();
+    }
+
+    #[test]
+    fn validity_offset_usize_test() {
+        whole_test::<OffsetUsize>();
+    }
+
+    #[test]
+    fn validity_offset_u128_test() {
+        whole_test::<OffsetU128>();
+    }
+
+    #[test]
+    fn validity_offset_i8_test() {
+        whole_test::<OffsetI8>();
+    }
+
+    #[test]
+    fn validity_offset_i16_test() {
+        whole_test::<OffsetI16>();
+    }
+
+    #[test]
+    fn validity_offset_i32_test() {
+        whole_test::<OffsetI32>();
+    }
+
+    #[test]
+    fn validity_offset_i64_test() {
+        whole_test::<OffsetI64>();
+    }
+
+    #[test]
+    fn validity_offset_isize_test() {
+        whole_test::<OffsetIsize>();
+    }
+
+    #[test]
+    fn validity_offset_i128_test() {
+        whole_test::<OffsetI128>();
+    }
 }
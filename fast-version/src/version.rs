@@ -1,12 +1,17 @@
+use core::fmt;
 use core::ops::{Shl, ShlAssign, Shr, ShrAssign, Add, Sub};
-use alloc::string::ToString;
+use core::str::FromStr;
 
 
-use crate::{simd::PortableSimdElement, serde::SerdeNumber};
+use crate::{simd::PortableSimdElement, serde::SerdeNumber, wire::WireNumber, VersionReq};
+use crate::parse::{Cursor, ParseVersionError, ParseVersionErrorKind, parse_triple, version_error_position};
 
-pub trait VersionNumber: PartialOrd + PortableSimdElement + core::hash::Hash + Add<Output = Self> + Sub<Output = Self> + Shl + Shr + ShlAssign + ShrAssign + SerdeNumber + ToString + Copy + Sized {
+/// Integer type of the version components, implemented for all primitive integers and the
+/// offset components in [`crate::offset`].
+pub trait VersionNumber: PartialOrd + Eq + PortableSimdElement + core::hash::Hash + Add<Output = Self> + Sub<Output = Self> + Shl + Shr + ShlAssign + ShrAssign + SerdeNumber + WireNumber + fmt::Display + FromStr + Copy + Sized {
     fn max() -> Self;
     fn min() -> Self;
+    fn zero() -> Self;
     fn one() -> Self;
 }
 
@@ -19,7 +24,12 @@ impl VersionNumber for u8 {
 
    #[inline]
    fn min() -> Self {
-       u8::MAX
+       u8::MIN
+   }
+
+   #[inline]
+   fn zero() -> Self {
+       0_u8
    }
 
    #[inline]
@@ -40,6 +50,11 @@ impl VersionNumber for u16 {
         u16::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_u16
+    }
+
     #[inline]
     fn one() -> Self {
         1_u16
@@ -58,6 +73,11 @@ impl VersionNumber for u32 {
         u32::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_u32
+    }
+
     #[inline]
     fn one() -> Self {
         1_u32
@@ -76,6 +96,11 @@ impl VersionNumber for u64 {
         u64::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_u64
+    }
+
     #[inline]
     fn one() -> Self {
         1_u64
@@ -94,6 +119,11 @@ impl VersionNumber for usize {
         usize::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_usize
+    }
+
     fn one() -> Self {
         1_usize
     }
@@ -111,6 +141,11 @@ impl VersionNumber for i8 {
         i8::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_i8
+    }
+
     #[inline]
     fn one() -> Self {
         1_i8
@@ -129,6 +164,11 @@ impl VersionNumber for i16 {
         i16::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_i16
+    }
+
     #[inline]
     fn one() -> Self {
         1_i16
@@ -147,6 +187,11 @@ impl VersionNumber for i32 {
         i32::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_i32
+    }
+
     #[inline]
     fn one() -> Self {
         1_i32
@@ -165,6 +210,11 @@ impl VersionNumber for i64 {
         i64::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_i64
+    }
+
     #[inline]
     fn one() -> Self {
         1_i64
@@ -182,17 +232,70 @@ impl VersionNumber for isize {
         isize::MIN
     }
 
+    #[inline]
+    fn zero() -> Self {
+        0_isize
+    }
+
     #[inline]
     fn one() -> Self {
         1_isize
     }
 }
 
+impl VersionNumber for u128 {
+    #[inline]
+    fn max() -> Self {
+        u128::MAX
+    }
+
+    #[inline]
+    fn min() -> Self {
+        u128::MIN
+    }
+
+    #[inline]
+    fn zero() -> Self {
+        0_u128
+    }
+
+    #[inline]
+    fn one() -> Self {
+        1_u128
+    }
+}
+
+impl VersionNumber for i128 {
+    #[inline]
+    fn max() -> Self {
+        i128::MAX
+    }
+
+    #[inline]
+    fn min() -> Self {
+        i128::MIN
+    }
+
+    #[inline]
+    fn zero() -> Self {
+        0_i128
+    }
+
+    #[inline]
+    fn one() -> Self {
+        1_i128
+    }
+}
+
+/// Checks that every component is a valid version number, i.e. lies in `[N::zero(), N::max())`.
+///
+/// `N::max()` is reserved as the open end of [`crate::VersionReq`] bounds, negative numbers don't
+/// exist in semver.
 #[inline]
 pub fn serial_compare<N: VersionNumber>(major: N, minor: N, patch: N) -> bool {
     let max = N::max();
-    let min = N::min();
-    major != max && major != min && minor != max && minor != min && patch != max && patch != min
+    let zero = N::zero();
+    major < max && major >= zero && minor < max && minor >= zero && patch < max && patch >= zero
 }
 
 fn fast_compare<N: VersionNumber>(major: N, minor: N, patch: N) -> bool {
@@ -200,12 +303,19 @@ fn fast_compare<N: VersionNumber>(major: N, minor: N, patch: N) -> bool {
         if #[cfg(nightly)] {
             crate::simd::fast_compare_simd(major, minor, patch)
         } else {
-            return serial_compare(major,minor,patch);
+            if N::simd_available() {
+                crate::simd::fast_compare_simd(major, minor, patch)
+            } else {
+                serial_compare(major, minor, patch)
+            }
         }
     }
 }
 
-#[derive(Debug)]
+/// Reason a version component was rejected.
+///
+/// `*IsMin` is returned for negative components, `*IsMax` for `N::max()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NewVersionError {
     MajorIsMax,
     MajorIsMin,
@@ -215,6 +325,30 @@ pub enum NewVersionError {
     PatchIsMin
 }
 
+impl fmt::Display for NewVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NewVersionError::MajorIsMax => "major component is the reserved maximum value",
+            NewVersionError::MajorIsMin => "major component is negative",
+            NewVersionError::MinorIsMax => "minor component is the reserved maximum value",
+            NewVersionError::MinorIsMin => "minor component is negative",
+            NewVersionError::PatchIsMax => "patch component is the reserved maximum value",
+            NewVersionError::PatchIsMin => "patch component is negative",
+        })
+    }
+}
+
+impl core::error::Error for NewVersionError {}
+
+/// The most significant component two versions differ in, see [`Version::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VersionDelta {
+    Major,
+    Minor,
+    Patch,
+    Equal,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Version<N: VersionNumber> {
     pub major: N,
@@ -225,22 +359,22 @@ pub struct Version<N: VersionNumber> {
 impl<N: VersionNumber> Version<N> {
     pub fn new(major: N, minor: N, patch: N) -> Result<Self, NewVersionError> {
         let max = N::max();
-        let min = N::min();
+        let zero = N::zero();
         if major == max {
             return Err(NewVersionError::MajorIsMax)
-        } else if major == min {
+        } else if major < zero {
             return Err(NewVersionError::MajorIsMin)
         }
 
         if minor == max {
             return Err(NewVersionError::MinorIsMax)
-        } else if minor == min {
+        } else if minor < zero {
             return Err(NewVersionError::MinorIsMin)
         }
 
         if patch == max {
             return Err(NewVersionError::PatchIsMax)
-        } else if patch == min {
+        } else if patch < zero {
             return Err(NewVersionError::PatchIsMin)
         }
         
@@ -255,29 +389,71 @@ impl<N: VersionNumber> Version<N> {
 
         Some(Self { major, minor, patch })
     }
+
+    /// The next major version, `1.2.3` becomes `2.0.0`. Fails with `MajorIsMax` if the major
+    /// version would reach `N::max()`.
+    pub fn bump_major(&self) -> Result<Self, NewVersionError> {
+        let zero = N::zero();
+        Self::new(self.major + N::one(), zero, zero)
+    }
+
+    /// The next minor version, `1.2.3` becomes `1.3.0`.
+    pub fn bump_minor(&self) -> Result<Self, NewVersionError> {
+        Self::new(self.major, self.minor + N::one(), N::zero())
+    }
+
+    /// The next patch version, `1.2.3` becomes `1.2.4`.
+    pub fn bump_patch(&self) -> Result<Self, NewVersionError> {
+        Self::new(self.major, self.minor, self.patch + N::one())
+    }
+
+    /// The most significant component `self` and `other` differ in, in either direction.
+    pub fn diff(&self, other: &Self) -> VersionDelta {
+        if self.major != other.major {
+            VersionDelta::Major
+        } else if self.minor != other.minor {
+            VersionDelta::Minor
+        } else if self.patch != other.patch {
+            VersionDelta::Patch
+        } else {
+            VersionDelta::Equal
+        }
+    }
+
+    /// Whether going from `self` to `other` changes the left-most non-zero component of `self`,
+    /// which is what caret requirements treat as breaking: `1.2.3 -> 2.0.0`, `0.2.3 -> 0.3.0` and
+    /// `0.0.3 -> 0.0.4` are breaking, `1.2.3 -> 1.3.0` is not.
+    pub fn is_breaking(&self, other: &Self) -> bool {
+        let zero = N::zero();
+        match self.diff(other) {
+            VersionDelta::Major => true,
+            VersionDelta::Minor => self.major == zero,
+            VersionDelta::Patch => self.major == zero && self.minor == zero,
+            VersionDelta::Equal => false,
+        }
+    }
+
+    /// Whether bumping `self` to `other` is allowed as far as `req` is concerned: only breaking
+    /// bumps may stop satisfying a requirement that `self` satisfies.
+    pub fn bump_allowed(&self, other: &Self, req: &VersionReq<N>) -> bool {
+        !req.fits(*self) || req.fits(*other) || self.is_breaking(other)
+    }
 }
 
 impl Version<u32> {
     pub const fn const_try_new(major: u32, minor: u32, patch: u32) -> Result<Self, NewVersionError> {
         const MAX: u32 = u32::MAX;
-        const MIN: u32 = u32::MIN;
 
         if major == MAX {
             return Err(NewVersionError::MajorIsMax);
-        } else if major == MIN {
-            return Err(NewVersionError::MajorIsMin);
         }
 
         if minor == MAX {
             return Err(NewVersionError::MinorIsMax);
-        } else if minor == MIN {
-            return Err(NewVersionError::MinorIsMin);
         }
 
         if patch == MAX {
             return Err(NewVersionError::PatchIsMax);
-        } else if patch == MIN {
-            return Err(NewVersionError::PatchIsMin);
         }
 
         let ret = Version { major, minor, patch };
@@ -288,24 +464,71 @@ impl Version<u32> {
 impl Version<u64> {
     pub const fn const_try_new(major: u64, minor: u64, patch: u64) -> Result<Self, NewVersionError> {
         const MAX: u64 = u64::MAX;
-        const MIN: u64 = u64::MIN;
 
         if major == MAX {
             return Err(NewVersionError::MajorIsMax);
-        } else if major == MIN {
+        }
+
+        if minor == MAX {
+            return Err(NewVersionError::MinorIsMax);
+        }
+
+        if patch == MAX {
+            return Err(NewVersionError::PatchIsMax);
+        }
+
+        let ret = Version { major, minor, patch };
+        Ok(ret)
+    }
+
+    /// Parses a strict semver `major.minor.patch` string in a `const` context, see
+    /// [`crate::version!`].
+    pub const fn const_parse(input: &str) -> Result<Self, ParseVersionError> {
+        crate::const_parse::const_parse_version(input)
+    }
+}
+
+impl Version<u128> {
+    pub const fn const_try_new(major: u128, minor: u128, patch: u128) -> Result<Self, NewVersionError> {
+        const MAX: u128 = u128::MAX;
+
+        if major == MAX {
+            return Err(NewVersionError::MajorIsMax);
+        }
+
+        if minor == MAX {
+            return Err(NewVersionError::MinorIsMax);
+        }
+
+        if patch == MAX {
+            return Err(NewVersionError::PatchIsMax);
+        }
+
+        let ret = Version { major, minor, patch };
+        Ok(ret)
+    }
+}
+
+impl Version<i128> {
+    pub const fn const_try_new(major: i128, minor: i128, patch: i128) -> Result<Self, NewVersionError> {
+        const MAX: i128 = i128::MAX;
+
+        if major == MAX {
+            return Err(NewVersionError::MajorIsMax);
+        } else if major < 0 {
             return Err(NewVersionError::MajorIsMin);
         }
 
         if minor == MAX {
             return Err(NewVersionError::MinorIsMax);
-        } else if minor == MIN {
+        } else if minor < 0 {
             return Err(NewVersionError::MinorIsMin);
         }
 
         if patch == MAX {
             return Err(NewVersionError::PatchIsMax);
-        } else if patch == MIN {
-            return Err(NewVersionError::PatchIsMax);
+        } else if patch < 0 {
+            return Err(NewVersionError::PatchIsMin);
         }
 
         let ret = Version { major, minor, patch };
@@ -320,22 +543,25 @@ impl<N: VersionNumber> PartialEq for Version<N> {
 
 }
 
+impl<N: VersionNumber> Eq for Version<N> {}
+
+impl<N: VersionNumber> Ord for Version<N> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // Version numbers are integers, which are always comparable.
+        [self.major, self.minor, self.patch]
+            .partial_cmp(&[other.major, other.minor, other.patch])
+            .unwrap()
+    }
+}
+
 impl<N: VersionNumber> PartialOrd for Version<N> {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        let mut ordering = self.major.partial_cmp(&other.major).unwrap();
-        if ordering.is_ne() {
-            return Some(ordering);
-        }
-        ordering = self.minor.partial_cmp(&other.minor).unwrap();
-        if ordering.is_ne() {
-            return Some(ordering);
-        }
-        Some(self.patch.partial_cmp(&other.patch).unwrap())
+        Some(self.cmp(other))
     }
 }
 
 impl<N: VersionNumber> core::hash::Hash for Version<N> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         let major = self.major;
         major.hash(state);
         let minor = self.minor;
@@ -344,3 +570,142 @@ impl<N: VersionNumber> core::hash::Hash for Version<N> {
         patch.hash(state);
     }
 }
+
+impl<N: VersionNumber> fmt::Display for Version<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl<N: VersionNumber> FromStr for Version<N> {
+    type Err = ParseVersionError;
+
+    /// Parses a strict semver `major.minor.patch` string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        if cursor.is_empty() {
+            return Err(cursor.error(ParseVersionErrorKind::Empty));
+        }
+
+        let ([major, minor, patch], positions) = parse_triple(&mut cursor)?;
+        if !cursor.is_empty() {
+            return Err(cursor.unexpected());
+        }
+
+        Version::new(major, minor, patch).map_err(|e| version_error_position(e, positions))
+    }
+}
+
+#[cfg(test)]
+mod version_tests {
+    extern crate std;
+
+    use core::cmp::Ordering;
+    use core::hash::{Hash, Hasher};
+
+    use rand::{thread_rng, Rng};
+
+    use super::{NewVersionError, Version, VersionDelta};
+    use crate::VersionReq;
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn ord_test() {
+        let v = |major, minor, patch| Version::<u32>::new(major, minor, patch).unwrap();
+        assert!(v(1, 2, 3) < v(1, 2, 4));
+        assert!(v(1, 2, 9) < v(1, 3, 0));
+        assert!(v(1, 9, 9) < v(2, 0, 0));
+        assert_eq!(v(1, 2, 3).cmp(&v(1, 2, 3)), Ordering::Equal);
+        assert_eq!(v(0, 0, 0).max(v(0, 0, 1)), v(0, 0, 1));
+    }
+
+    /// `cmp`, `==` and `hash` must agree with each other.
+    #[test]
+    fn ord_eq_hash_property_test() {
+        let mut rng = thread_rng();
+        for _ in 0..10000 {
+            let mut random = || Version::<u16>::new(rng.gen_range(0..3), rng.gen_range(0..3), rng.gen_range(0..3)).unwrap();
+            let (a, b) = (random(), random());
+            assert_eq!(a.cmp(&b).is_eq(), a == b);
+            assert_eq!(a.partial_cmp(&b), Some(a.cmp(&b)));
+            assert_eq!(b.cmp(&a), a.cmp(&b).reverse());
+            if a == b {
+                assert_eq!(hash_of(&a), hash_of(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn bump_test() {
+        let v = |major, minor, patch| Version::<u8>::new(major, minor, patch).unwrap();
+        assert_eq!(v(1, 2, 3).bump_major(), Ok(v(2, 0, 0)));
+        assert_eq!(v(1, 2, 3).bump_minor(), Ok(v(1, 3, 0)));
+        assert_eq!(v(1, 2, 3).bump_patch(), Ok(v(1, 2, 4)));
+        assert_eq!(v(253, 2, 3).bump_major(), Ok(v(254, 0, 0)));
+        assert_eq!(v(254, 2, 3).bump_major(), Err(NewVersionError::MajorIsMax));
+        assert_eq!(v(1, 254, 3).bump_minor(), Err(NewVersionError::MinorIsMax));
+        assert_eq!(v(1, 2, 254).bump_patch(), Err(NewVersionError::PatchIsMax));
+        assert_eq!(Version::<i8>::new(126, 0, 0).unwrap().bump_major(), Err(NewVersionError::MajorIsMax));
+    }
+
+    #[test]
+    fn diff_test() {
+        let v = |major, minor, patch| Version::<u32>::new(major, minor, patch).unwrap();
+        assert_eq!(v(1, 2, 3).diff(&v(2, 2, 3)), VersionDelta::Major);
+        assert_eq!(v(1, 2, 3).diff(&v(1, 0, 9)), VersionDelta::Minor);
+        assert_eq!(v(1, 2, 3).diff(&v(1, 2, 0)), VersionDelta::Patch);
+        assert_eq!(v(1, 2, 3).diff(&v(1, 2, 3)), VersionDelta::Equal);
+        assert_eq!(v(1, 2, 3).diff(&v(0, 2, 3)), v(0, 2, 3).diff(&v(1, 2, 3)));
+
+        assert!(v(1, 2, 3).is_breaking(&v(2, 0, 0)));
+        assert!(!v(1, 2, 3).is_breaking(&v(1, 3, 0)));
+        assert!(v(0, 2, 3).is_breaking(&v(0, 3, 0)));
+        assert!(!v(0, 2, 3).is_breaking(&v(0, 2, 4)));
+        assert!(v(0, 0, 3).is_breaking(&v(0, 0, 4)));
+    }
+
+    #[test]
+    fn bump_allowed_test() {
+        let v = |major, minor, patch| Version::<u32>::new(major, minor, patch).unwrap();
+        let req = |input: &str| VersionReq::<u32>::parse(input).unwrap();
+        assert!(v(1, 2, 3).bump_allowed(&v(2, 0, 0), &req("^1.2")));
+        assert!(v(1, 2, 3).bump_allowed(&v(1, 3, 0), &req("^1.2")));
+        assert!(!v(1, 2, 3).bump_allowed(&v(1, 3, 0), &req("~1.2")));
+        assert!(!v(0, 2, 3).bump_allowed(&v(0, 2, 4), &req("=0.2.3")));
+        assert!(v(0, 2, 3).bump_allowed(&v(0, 3, 0), &req("=0.2.3")));
+        assert!(v(1, 2, 3).bump_allowed(&v(1, 3, 0), &req("^2")));
+
+        // Caret requirements are only ever broken by breaking bumps.
+        let mut rng = thread_rng();
+        for _ in 0..10000 {
+            let from = Version::<u32>::new(rng.gen_range(0..3), rng.gen_range(0..3), rng.gen_range(0..3)).unwrap();
+            let to = match rng.gen_range(0..3) {
+                0 => from.bump_major(),
+                1 => from.bump_minor(),
+                _ => from.bump_patch(),
+            }
+            .unwrap();
+            let caret = req(&alloc::format!("^{}", from));
+            assert!(from.bump_allowed(&to, &caret), "{} -> {}", from, to);
+            assert_eq!(caret.fits(to), !from.is_breaking(&to), "{} -> {}", from, to);
+        }
+    }
+
+    #[test]
+    fn const_try_new_wide_test() {
+        const TIMESTAMPED: Version<u128> = match Version::<u128>::const_try_new(1, 2, 1_700_000_000_000_000_000_000) {
+            Ok(version) => version,
+            Err(_) => panic!(),
+        };
+        assert_eq!(TIMESTAMPED, Version::new(1, 2, 1_700_000_000_000_000_000_000).unwrap());
+        assert_eq!(Version::<u128>::const_try_new(1, u128::MAX, 0), Err(NewVersionError::MinorIsMax));
+        assert_eq!(Version::<i128>::const_try_new(1, 2, -1), Err(NewVersionError::PatchIsMin));
+        assert_eq!(Version::<i128>::const_try_new(i128::MAX, 2, 3), Err(NewVersionError::MajorIsMax));
+        assert_eq!(Version::<i128>::const_try_new(1, 2, 3), Version::new(1, 2, 3));
+    }
+}
@@ -1,24 +1,52 @@
-use crate::{version::VersionNumber, Version};
+use core::fmt;
+use core::ops::Bound;
+use core::str::FromStr;
 
-#[cfg(nightly)]
-use crate::simd::simd_version_req;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
+use crate::{version::{VersionDelta, VersionNumber}, Version};
+use crate::parse::{parse_version_req, ParseVersionError};
+
+use crate::simd::{simd_version_req, simd_version_req_batch};
+
+/// Checks `lower <= ver <= upper`, comparing the triples lexicographically.
 #[inline]
 pub fn serial_version_req<N: VersionNumber>(ver: [N; 3], lower: [N; 3], upper: [N; 3]) -> bool {
-    for i in 0..3 {
-        let v = ver[i];
-        let l = lower[i];
-        let u = upper[i];
-        let res = l <= v && v <= u;
-        
-        if !res {
-            return false
-        }
-    }
+    lower <= ver && ver <= upper
+}
 
-    true
+/// Serial counterpart of `simd_version_req_batch`, bit `i` of the result is set if `vers[i]`
+/// fits.
+#[inline]
+pub fn serial_version_req_batch<N: VersionNumber>(vers: [[N; 3]; 4], lower: [N; 3], upper: [N; 3]) -> u64 {
+    vers.iter()
+        .enumerate()
+        .fold(0, |mask, (i, ver)| mask | ((serial_version_req(*ver, lower, upper) as u64) << i))
 }
 
+/// A single comparator. Each variant compares the first one, two or all three components of a
+/// version `a.b.c` with its own, lexicographically:
+///
+/// | Variant                     | Cargo syntax | Accepts `a.b.c` if           |
+/// |-----------------------------|--------------|------------------------------|
+/// | `Strict(M.m.p)`             | `=M.m.p`     | `(a, b, c) == (M, m, p)`     |
+/// | `GreaterMajor { M }`        | `>M`         | `a > M`                      |
+/// | `GreaterMinor { M, m }`     | `>M.m`       | `(a, b) > (M, m)`            |
+/// | `GreaterPatch { M, m, p }`  | `>M.m.p`     | `(a, b, c) > (M, m, p)`      |
+/// | `GreaterEqMajor { M }`      | `>=M`        | `a >= M`                     |
+/// | `GreaterEqMinor { M, m }`   | `>=M.m`      | `(a, b) >= (M, m)`           |
+/// | `GreaterEqPatch { M, m, p }`| `>=M.m.p`    | `(a, b, c) >= (M, m, p)`     |
+/// | `LesserMajor { M }`         | `<M`         | `a < M`                      |
+/// | `LesserMinor { M, m }`      | `<M.m`       | `(a, b) < (M, m)`            |
+/// | `LesserPatch { M, m, p }`   | `<M.m.p`     | `(a, b, c) < (M, m, p)`      |
+/// | `LesserEqMajor { M }`       | `<=M`        | `a <= M`                     |
+/// | `LesserEqMinor { M, m }`    | `<=M.m`      | `(a, b) <= (M, m)`           |
+/// | `LesserEqPatch { M, m, p }` | `<=M.m.p`    | `(a, b, c) <= (M, m, p)`     |
+///
+/// Components have to be in `[0, N::max())`. A comparator no version can satisfy, `<0`, `<0.0`,
+/// `<0.0.0` or `>` on components that are all `N::max() - 1`, is rejected with
+/// [`VersionRegError::Unsatisfiable`].
 #[derive(Debug, Copy, Clone)]
 pub enum VersionRegType<N: VersionNumber> {
     Strict(Version<N>),
@@ -74,37 +102,39 @@ pub enum VersionRegType<N: VersionNumber> {
 
 impl<N: VersionNumber> VersionRegType<N> {
     fn is_greater(&self) -> bool {
-        match self {
-            VersionRegType::GreaterMajor { .. } => true,
-            VersionRegType::GreaterMinor { .. } => true,
-            VersionRegType::GreaterPatch { .. } => true,
-            VersionRegType::GreaterEqMajor { .. } => true,
-            VersionRegType::GreaterEqMinor { .. } => true,
-            VersionRegType::GreaterEqPatch { .. } => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            VersionRegType::GreaterMajor { .. }
+                | VersionRegType::GreaterMinor { .. }
+                | VersionRegType::GreaterPatch { .. }
+                | VersionRegType::GreaterEqMajor { .. }
+                | VersionRegType::GreaterEqMinor { .. }
+                | VersionRegType::GreaterEqPatch { .. }
+        )
     }
 
     fn is_lower(&self) -> bool {
-        match self {
-            VersionRegType::LesserMajor { .. } => true,
-            VersionRegType::LesserMinor { .. } => true,
-            VersionRegType::LesserPatch { .. } => true,
-            VersionRegType::LesserEqMajor { .. } => true,
-            VersionRegType::LesserEqMinor { .. } => true,
-            VersionRegType::LesserEqPatch { .. } => true,
-            _ => false
-        }
+        matches!(
+            self,
+            VersionRegType::LesserMajor { .. }
+                | VersionRegType::LesserMinor { .. }
+                | VersionRegType::LesserPatch { .. }
+                | VersionRegType::LesserEqMajor { .. }
+                | VersionRegType::LesserEqMinor { .. }
+                | VersionRegType::LesserEqPatch { .. }
+        )
     }
 
     fn is_strict(&self) -> bool {
-        match self {
-            VersionRegType::Strict(_) => true,
-            _ => false,
-        }
+        matches!(self, VersionRegType::Strict(_))
     }
 }
 
+/// A requirement made of one comparator, or of a range between a lower and an upper bound.
+///
+/// A range accepts the versions both comparators accept. `lower` has to be one of the `Greater*`
+/// variants, `higher` one of the `Lesser*` ones, and the range must not be empty.
+#[derive(Debug, Copy, Clone)]
 pub enum VersionRegCompType<N: VersionNumber> {
     Pure(VersionRegType<N>),
     Composite {
@@ -113,8 +143,7 @@ pub enum VersionRegCompType<N: VersionNumber> {
     }
 }
 
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(core::hash::Hash, Debug, Copy, Clone)]
+#[derive(core::hash::Hash, Debug, Copy, Clone, Default)]
 pub(crate) enum VersionComperatorLower {
     Strict,
     GreaterMajor,
@@ -123,17 +152,11 @@ pub(crate) enum VersionComperatorLower {
     GreaterEqMajor,
     GreaterEqMinor,
     GreaterEqPatch,
+    #[default]
     None
 }
 
-impl Default for VersionComperatorLower {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(core::hash::Hash, Debug, Copy, Clone)]
+#[derive(core::hash::Hash, Debug, Copy, Clone, Default)]
 pub(crate) enum VersionComperatorUpper {
     LesserMajor,
     LesserMinor,
@@ -141,15 +164,10 @@ pub(crate) enum VersionComperatorUpper {
     LesserEqMajor,
     LesserEqMinor,
     LesserEqPatch,
+    #[default]
     None
 }
 
-impl Default for VersionComperatorUpper {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 #[derive(Debug, Copy, Clone)]
 pub struct VersionReq<N: VersionNumber> {
     pub(crate) comperator_lower: VersionComperatorLower,
@@ -163,10 +181,10 @@ pub struct VersionReq<N: VersionNumber> {
 }
 
 fn validate_num<N: VersionNumber>(input: N) -> Result<(), VersionRegError> {
-    let min = N::min();
+    let zero = N::zero();
     let max = N::max();
 
-    if input == min {
+    if input < zero {
         return Err(VersionRegError::MinValueNotAllowed)
     } else if input == max {
         return Err(VersionRegError::MaxValueNotAllowed)
@@ -185,12 +203,75 @@ impl<N: VersionNumber> VersionReq<N> {
         let ret = simd_version_req(ver, lower, upper);
 
         #[cfg(not(nightly))]
-        let ret = serial_version_req(ver, lower, upper);
+        let ret = if N::simd_available() {
+            simd_version_req(ver, lower, upper)
+        } else {
+            serial_version_req(ver, lower, upper)
+        };
         
         ret
     }
 
-    fn apply_reg_type(&mut self, reg_type: VersionRegType<N>) -> Result<(), VersionRegError> {
+    /// Calls `f` for every version in `versions` that fits, in order.
+    fn for_each_fitting(&self, versions: &[Version<N>], mut f: impl FnMut(Version<N>)) {
+        let lower = self.lower();
+        let upper = self.upper();
+
+        let chunks = versions.chunks_exact(4);
+        let remainder = chunks.remainder();
+        for chunk in chunks {
+            let vers: [[N; 3]; 4] = core::array::from_fn(|i| [chunk[i].major, chunk[i].minor, chunk[i].patch]);
+
+            #[cfg(nightly)]
+            let mut mask = simd_version_req_batch(vers, lower, upper);
+
+            #[cfg(not(nightly))]
+            let mut mask = if N::simd_available() {
+                simd_version_req_batch(vers, lower, upper)
+            } else {
+                serial_version_req_batch(vers, lower, upper)
+            };
+
+            while mask != 0 {
+                f(chunk[mask.trailing_zeros() as usize]);
+                mask &= mask - 1;
+            }
+        }
+
+        remainder.iter().filter(|version| self.fits(**version)).for_each(|version| f(*version));
+    }
+
+    /// The highest version in `versions` that fits, `None` if none does.
+    pub fn max_satisfying(&self, versions: &[Version<N>]) -> Option<Version<N>> {
+        let mut ret: Option<Version<N>> = None;
+        self.for_each_fitting(versions, |version| {
+            if ret.is_none_or(|best| version > best) {
+                ret = Some(version);
+            }
+        });
+        ret
+    }
+
+    /// The lowest version in `versions` that fits, `None` if none does.
+    pub fn min_satisfying(&self, versions: &[Version<N>]) -> Option<Version<N>> {
+        let mut ret: Option<Version<N>> = None;
+        self.for_each_fitting(versions, |version| {
+            if ret.is_none_or(|best| version < best) {
+                ret = Some(version);
+            }
+        });
+        ret
+    }
+
+    /// All versions in `versions` that fit, keeping their order.
+    #[cfg(feature = "alloc")]
+    pub fn filter(&self, versions: &[Version<N>]) -> Vec<Version<N>> {
+        let mut ret = Vec::new();
+        self.for_each_fitting(versions, |version| ret.push(version));
+        ret
+    }
+
+    pub(crate) fn apply_reg_type(&mut self, reg_type: VersionRegType<N>) -> Result<(), VersionRegError> {
         match reg_type {
             VersionRegType::Strict(version) => {
                 self.apply_strict(version)
@@ -235,67 +316,85 @@ impl<N: VersionNumber> VersionReq<N> {
 
     }
 
-    fn apply_strict(&mut self, reg_type: Version<N>) -> Result<(), VersionRegError> {
-        let major = reg_type.major;
-        let minor = reg_type.minor;
-        let patch = reg_type.patch;
-
+    pub(crate) fn set_lower(&mut self, major: N, minor: N, patch: N, comperator: VersionComperatorLower) {
         self.major_lower = major;
         self.minor_lower = minor;
         self.patch_lower = patch;
 
+        self.comperator_lower = comperator;
+    }
+
+    pub(crate) fn set_upper(&mut self, major: N, minor: N, patch: N, comperator: VersionComperatorUpper) {
         self.major_upper = major;
         self.minor_upper = minor;
         self.patch_upper = patch;
 
-        self.comperator_lower = VersionComperatorLower::Strict;
+        self.comperator_higher = comperator;
+    }
+
+    fn apply_strict(&mut self, reg_type: Version<N>) -> Result<(), VersionRegError> {
+        let major = reg_type.major;
+        let minor = reg_type.minor;
+        let patch = reg_type.patch;
+
+        validate_num(major)?;
+        validate_num(minor)?;
+        validate_num(patch)?;
+
+        // Both sides are set, so an intersection can't widen the upper bound past the version.
+        self.set_lower(major, minor, patch, VersionComperatorLower::Strict);
+        self.set_upper(major, minor, patch, VersionComperatorUpper::LesserEqPatch);
 
         Ok(())
     }
 
+    // There is nothing above the largest valid component, `>top` can't be satisfied.
+
     fn apply_greater_major(&mut self, major: N) -> Result<(), VersionRegError> {
         validate_num(major)?;
 
         let one = N::one();
+        let zero = N::zero();
 
-        let min_major = major + one;
-
-        self.major_lower = min_major;
+        if major == N::max() - one {
+            return Err(VersionRegError::Unsatisfiable);
+        }
 
-        self.comperator_lower = VersionComperatorLower::GreaterMajor;
+        self.set_lower(major + one, zero, zero, VersionComperatorLower::GreaterMajor);
 
         Ok(())
     }
 
     fn apply_greater_minor(&mut self, major: N, minor: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
         validate_num(minor)?;
-       
-        self.apply_greater_major(major)?;
-        
+
         let one = N::one();
+        let zero = N::zero();
+        let top = N::max() - one;
 
-        let min_minor = minor + one;
+        if major == top && minor == top {
+            return Err(VersionRegError::Unsatisfiable);
+        }
 
-        self.minor_lower = min_minor;
+        self.set_lower(major, minor + one, zero, VersionComperatorLower::GreaterMinor);
 
-        self.comperator_lower = VersionComperatorLower::GreaterMinor;
-        
         Ok(())
     }
 
     fn apply_greater_patch(&mut self, major: N, minor: N, patch: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
+        validate_num(minor)?;
         validate_num(patch)?;
 
-        self.apply_greater_minor(major, minor)?;
-
-
         let one = N::one();
+        let top = N::max() - one;
 
-        let min_patch = patch + one;
-
-        self.patch_lower = min_patch;
+        if major == top && minor == top && patch == top {
+            return Err(VersionRegError::Unsatisfiable);
+        }
 
-        self.comperator_lower = VersionComperatorLower::GreaterPatch;
+        self.set_lower(major, minor, patch + one, VersionComperatorLower::GreaterPatch);
 
         Ok(())
     }
@@ -303,79 +402,83 @@ impl<N: VersionNumber> VersionReq<N> {
     fn apply_greater_eq_major(&mut self, major: N) -> Result<(), VersionRegError> {
         validate_num(major)?;
 
-        self.major_lower = major;
+        let zero = N::zero();
 
-        self.comperator_lower = VersionComperatorLower::GreaterEqMajor;
+        self.set_lower(major, zero, zero, VersionComperatorLower::GreaterEqMajor);
 
         Ok(())
     }
 
     fn apply_greater_eq_minor(&mut self, major: N, minor: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
         validate_num(minor)?;
 
-        self.apply_greater_eq_major(major)?;
+        let zero = N::zero();
 
-        self.minor_lower = minor;
-
-        self.comperator_lower = VersionComperatorLower::GreaterEqMinor;
+        self.set_lower(major, minor, zero, VersionComperatorLower::GreaterEqMinor);
 
         Ok(())
     }
 
     fn apply_greater_eq_patch(&mut self, major: N, minor: N, patch: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
+        validate_num(minor)?;
         validate_num(patch)?;
 
-        self.apply_greater_eq_minor(major, minor)?;
-
-        self.patch_lower = patch;
-
-        self.comperator_lower = VersionComperatorLower::GreaterEqPatch;
+        self.set_lower(major, minor, patch, VersionComperatorLower::GreaterEqPatch);
 
         Ok(())
     }
 
+    // The exclusive upper bounds borrow from the next higher component when the bound ends in
+    // zero, `<1.0` is stored like `<1` and `<0.0.0` can't be satisfied at all.
+
     fn apply_lesser_major(&mut self, major: N) -> Result<(), VersionRegError> {
         validate_num(major)?;
 
+        let zero = N::zero();
         let one = N::one();
+        let max = N::max();
 
-        let max_major = major - one;
-
-        self.major_upper = max_major;
+        if major == zero {
+            return Err(VersionRegError::Unsatisfiable);
+        }
 
-        self.comperator_higher = VersionComperatorUpper::LesserMajor;
+        self.set_upper(major - one, max, max, VersionComperatorUpper::LesserMajor);
 
         Ok(())
     }
 
     fn apply_lesser_minor(&mut self, major: N, minor: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
         validate_num(minor)?;
 
-        self.apply_lesser_major(major)?;
-
+        let zero = N::zero();
         let one = N::one();
+        let max = N::max();
 
-        let max_minor = minor - one;
-
-        self.minor_upper = max_minor;
+        if minor == zero {
+            return self.apply_lesser_major(major);
+        }
 
-        self.comperator_higher = VersionComperatorUpper::LesserMinor;
+        self.set_upper(major, minor - one, max, VersionComperatorUpper::LesserMinor);
 
         Ok(())
     }
 
     fn apply_lesser_patch(&mut self, major: N, minor: N, patch: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
+        validate_num(minor)?;
         validate_num(patch)?;
 
-        self.apply_lesser_minor(major, minor)?;
-
+        let zero = N::zero();
         let one = N::one();
 
-        let max_patch = patch - one;
-
-        self.patch_upper = max_patch;
+        if patch == zero {
+            return self.apply_lesser_minor(major, minor);
+        }
 
-        self.comperator_higher = VersionComperatorUpper::LesserPatch;
+        self.set_upper(major, minor, patch - one, VersionComperatorUpper::LesserPatch);
 
         Ok(())
     }
@@ -383,65 +486,330 @@ impl<N: VersionNumber> VersionReq<N> {
     fn apply_lesser_eq_major(&mut self, major: N) -> Result<(), VersionRegError> {
         validate_num(major)?;
 
-        self.major_upper = major;
+        let max = N::max();
 
-        self.comperator_higher = VersionComperatorUpper::LesserEqMajor;
+        self.set_upper(major, max, max, VersionComperatorUpper::LesserEqMajor);
 
         Ok(())
     }
 
     fn apply_lesser_eq_minor(&mut self, major: N, minor: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
         validate_num(minor)?;
 
-        self.apply_lesser_eq_major(major)?;
+        let max = N::max();
 
-        self.minor_upper = minor;
-
-        self.comperator_higher = VersionComperatorUpper::LesserEqMinor;
+        self.set_upper(major, minor, max, VersionComperatorUpper::LesserEqMinor);
 
         Ok(())
     }
 
     fn apply_lesser_eq_patch(&mut self, major: N, minor: N, patch: N) -> Result<(), VersionRegError> {
+        validate_num(major)?;
+        validate_num(minor)?;
         validate_num(patch)?;
 
-        self.apply_lesser_eq_minor(major, minor)?;
+        self.set_upper(major, minor, patch, VersionComperatorUpper::LesserEqPatch);
 
-        self.patch_upper = patch;
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn lower(&self) -> [N; 3] {
+        [self.major_lower, self.minor_lower, self.patch_lower]
+    }
+
+    #[inline]
+    pub(crate) fn upper(&self) -> [N; 3] {
+        [self.major_upper, self.minor_upper, self.patch_upper]
+    }
 
-        self.comperator_higher = VersionComperatorUpper::LesserEqPatch;
+    /// The smallest valid version above the lower bound, `None` if there is none.
+    fn normalized_lower(&self) -> Option<[N; 3]> {
+        let zero = N::zero();
+        let one = N::one();
+        let max = N::max();
+
+        // Lower bounds may contain `max` after a `>` comparator, carry it into the next component.
+        let mut lower = self.lower();
+        for i in (1..3).rev() {
+            if lower[i] == max {
+                lower[i] = zero;
+                lower[i - 1] = lower[i - 1] + one;
+                lower[(i + 1)..].iter_mut().for_each(|c| *c = zero);
+            }
+        }
+        if lower[0] == max {
+            return None;
+        }
+        Some(lower)
+    }
+
+    /// The largest valid version below the upper bound.
+    fn normalized_upper(&self) -> [N; 3] {
+        let max = N::max();
+
+        // Open upper bounds use `max`, which is no valid component.
+        let mut upper = self.upper();
+        if let Some(i) = upper.iter().position(|c| *c == max) {
+            upper[i..].iter_mut().for_each(|c| *c = max - N::one());
+        }
+        upper
+    }
+
+    /// The smallest and largest valid version the requirement accepts, `None` if it accepts none.
+    pub(crate) fn normalized_bounds(&self) -> Option<([N; 3], [N; 3])> {
+        let lower = self.normalized_lower()?;
+        let upper = self.normalized_upper();
+
+        if lower > upper {
+            return None;
+        }
+        Some((lower, upper))
+    }
 
+    /// The lower bound of the requirement, `>1.2.3` is reported as `Included(1.2.4)` and `>=0.0.0`
+    /// as `Unbounded`.
+    pub fn lower_bound(&self) -> Bound<Version<N>> {
+        let zero = N::zero();
+        match self.normalized_lower() {
+            Some([major, minor, patch]) if [major, minor, patch] != [zero; 3] => Bound::Included(Version { major, minor, patch }),
+            Some(_) => Bound::Unbounded,
+            None => {
+                let top = N::max() - N::one();
+                Bound::Excluded(Version { major: top, minor: top, patch: top })
+            }
+        }
+    }
+
+    /// The upper bound of the requirement, bounds covering whole majors or minors are reported as
+    /// exclusive: `^1.2` and `<=1` have the upper bound `Excluded(2.0.0)`, `<=1.2.3` has
+    /// `Included(1.2.3)`.
+    pub fn upper_bound(&self) -> Bound<Version<N>> {
+        let zero = N::zero();
+        let one = N::one();
+        let top = N::max() - one;
+
+        match self.normalized_upper() {
+            [major, minor, patch] if minor == top && patch == top => {
+                if major == top {
+                    Bound::Unbounded
+                } else {
+                    Bound::Excluded(Version { major: major + one, minor: zero, patch: zero })
+                }
+            }
+            [major, minor, patch] if patch == top => Bound::Excluded(Version { major, minor: minor + one, patch: zero }),
+            [major, minor, patch] => Bound::Included(Version { major, minor, patch }),
+        }
+    }
+
+    /// Explains why `version` doesn't fit, `None` if it does.
+    ///
+    /// The mismatch names the violated bound and the most significant component of `version`
+    /// that violates it, e.g. `major 3 exceeds upper bound <3.0.0`.
+    pub fn explain(&self, version: Version<N>) -> Option<Mismatch<N>> {
+        let (lower, upper) = match self.normalized_bounds() {
+            Some(bounds) => bounds,
+            None => return Some(Mismatch::Unsatisfiable),
+        };
+
+        let ver = [version.major, version.minor, version.patch];
+        let [major, minor, patch] = if ver < lower {
+            lower
+        } else if ver > upper {
+            upper
+        } else {
+            return None;
+        };
+        let component = version.diff(&Version { major, minor, patch });
+
+        if ver < lower {
+            Some(Mismatch::BelowLower { version, component, bound: self.lower_bound() })
+        } else {
+            Some(Mismatch::AboveUpper { version, component, bound: self.upper_bound() })
+        }
+    }
+
+    /// Builds the requirement accepting exactly the versions in `[lower, upper]`, choosing the
+    /// comparators so that whole majors and minors print as `1.*`, `^1.2` and so on.
+    pub(crate) fn from_bounds(lower: [N; 3], upper: [N; 3]) -> Self {
+        use VersionComperatorLower as L;
+        use VersionComperatorUpper as U;
+
+        let zero = N::zero();
+        let max = N::max();
+        let top = max - N::one();
+
+        let mut ret = Self::default();
+
+        let [major, minor, patch] = lower;
+        let comperator_lower = match (major == zero, minor == zero, patch == zero) {
+            (true, true, true) => L::None,
+            (_, true, true) => L::GreaterEqMajor,
+            (_, _, true) => L::GreaterEqMinor,
+            _ => L::GreaterEqPatch,
+        };
+        ret.set_lower(major, minor, patch, comperator_lower);
+
+        let [major, minor, patch] = upper;
+        match (major == top, minor == top, patch == top) {
+            (true, true, true) => ret.set_upper(max, max, max, U::None),
+            (_, true, true) => ret.set_upper(major, max, max, U::LesserEqMajor),
+            (_, _, true) => ret.set_upper(major, minor, max, U::LesserEqMinor),
+            _ => ret.set_upper(major, minor, patch, U::LesserEqPatch),
+        }
+
+        ret
+    }
+
+    pub(crate) fn is_strict(&self) -> bool {
+        matches!(self.comperator_lower, VersionComperatorLower::Strict)
+    }
+
+    /// Narrows `self` to the intersection with `other`, keeping the tighter bound on each side.
+    /// Fails with [`VersionRegError::EmptyRange`] if the bounds cross, like in `>2, <1`.
+    pub(crate) fn intersect(&mut self, other: &Self) -> Result<(), VersionRegError> {
+        if self.is_strict() || other.is_strict() {
+            let self_any = matches!(self.comperator_lower, VersionComperatorLower::None) && matches!(self.comperator_higher, VersionComperatorUpper::None);
+            if !self_any {
+                return Err(VersionRegError::StrictNotAllowedInComposite);
+            }
+            *self = *other;
+            return Ok(());
+        }
+
+        let lower_unset = matches!(self.comperator_lower, VersionComperatorLower::None);
+        let other_lower_set = !matches!(other.comperator_lower, VersionComperatorLower::None);
+        if other_lower_set && (lower_unset || other.lower() > self.lower()) {
+            let [major, minor, patch] = other.lower();
+            self.set_lower(major, minor, patch, other.comperator_lower);
+        }
+
+        let upper_unset = matches!(self.comperator_higher, VersionComperatorUpper::None);
+        let other_upper_set = !matches!(other.comperator_higher, VersionComperatorUpper::None);
+        if other_upper_set && (upper_unset || other.upper() < self.upper()) {
+            let [major, minor, patch] = other.upper();
+            self.set_upper(major, minor, patch, other.comperator_higher);
+        }
+
+        if self.normalized_bounds().is_none() {
+            return Err(VersionRegError::EmptyRange);
+        }
         Ok(())
     }
+
+    /// Parses a cargo style requirement such as `^1.2`, `~1.2.3`, `>=1.0, <2.0`, `1.*` or `*`.
+    pub fn parse(input: &str) -> Result<Self, ParseVersionError> {
+        parse_version_req(input)
+    }
+}
+
+impl VersionReq<u64> {
+    /// Parses a requirement like [`VersionReq::parse`] in a `const` context, see
+    /// [`crate::version_req!`].
+    pub const fn const_parse(input: &str) -> Result<Self, ParseVersionError> {
+        crate::const_parse::const_parse_version_req(input)
+    }
 }
 
 impl<N: VersionNumber> Default for VersionReq<N> {
     fn default() -> Self {
-       let min = N::min(); 
+       let zero = N::zero();
        let max = N::max();
 
        Self {
            comperator_lower: Default::default(),
            comperator_higher: Default::default(),
-           major_lower: max,
-           minor_lower: max,
-           patch_lower: max,
-           major_upper: min,
-           minor_upper: min,
-           patch_upper: min
+           major_lower: zero,
+           minor_lower: zero,
+           patch_lower: zero,
+           major_upper: max,
+           minor_upper: max,
+           patch_upper: max
        }
     }
 }
 
-#[derive(Debug)]
+/// Why a version doesn't fit a [`VersionReq`], see [`VersionReq::explain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mismatch<N: VersionNumber> {
+    /// `component` of `version` is below the lower `bound`.
+    BelowLower {
+        version: Version<N>,
+        component: VersionDelta,
+        bound: Bound<Version<N>>,
+    },
+    /// `component` of `version` exceeds the upper `bound`.
+    AboveUpper {
+        version: Version<N>,
+        component: VersionDelta,
+        bound: Bound<Version<N>>,
+    },
+    /// The requirement accepts no version at all.
+    Unsatisfiable,
+}
+
+impl<N: VersionNumber> fmt::Display for Mismatch<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (version, component, bound, relation) = match self {
+            Mismatch::BelowLower { version, component, bound } => (version, component, bound, "is below lower bound"),
+            Mismatch::AboveUpper { version, component, bound } => (version, component, bound, "exceeds upper bound"),
+            Mismatch::Unsatisfiable => return f.write_str("requirement accepts no version"),
+        };
+
+        match component {
+            VersionDelta::Major => write!(f, "major {}", version.major)?,
+            VersionDelta::Minor => write!(f, "minor {}", version.minor)?,
+            VersionDelta::Patch => write!(f, "patch {}", version.patch)?,
+            VersionDelta::Equal => write!(f, "{}", version)?,
+        }
+        write!(f, " {} ", relation)?;
+
+        let is_lower = matches!(self, Mismatch::BelowLower { .. });
+        match (bound, is_lower) {
+            (Bound::Included(bound), true) => write!(f, ">={}", bound),
+            (Bound::Excluded(bound), true) => write!(f, ">{}", bound),
+            (Bound::Included(bound), false) => write!(f, "<={}", bound),
+            (Bound::Excluded(bound), false) => write!(f, "<{}", bound),
+            (Bound::Unbounded, _) => f.write_str("*"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VersionRegError {
+    /// A range has an exact version as one of its bounds.
     StrictNotAllowedInComposite,
+    /// The lower bound of a range is a `Lesser*` comparator.
     LowerOnPlaceOfGreater,
+    /// The upper bound of a range is a `Greater*` comparator.
     GreaterOnPlaceOfLower,
+    /// A component is negative.
     MinValueNotAllowed,
+    /// A component is `N::max()`.
     MaxValueNotAllowed,
+    /// The comparator accepts no version, like `<0.0.0`.
+    Unsatisfiable,
+    /// The lower bound of a range lies above its upper bound, like `>2, <1`.
+    EmptyRange,
 }
 
+impl fmt::Display for VersionRegError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VersionRegError::StrictNotAllowedInComposite => "an exact version can't be a bound of a range",
+            VersionRegError::LowerOnPlaceOfGreater => "the lower bound of a range is a `<` or `<=` comparator",
+            VersionRegError::GreaterOnPlaceOfLower => "the upper bound of a range is a `>` or `>=` comparator",
+            VersionRegError::MinValueNotAllowed => "component is negative",
+            VersionRegError::MaxValueNotAllowed => "component is the reserved maximum value",
+            VersionRegError::Unsatisfiable => "comparator accepts no version",
+            VersionRegError::EmptyRange => "lower bound of the range lies above its upper bound",
+        })
+    }
+}
+
+impl core::error::Error for VersionRegError {}
+
 impl<N: VersionNumber> TryFrom<VersionRegCompType<N>> for VersionReq<N> {
     type Error = VersionRegError;
 
@@ -453,20 +821,24 @@ impl<N: VersionNumber> TryFrom<VersionRegCompType<N>> for VersionReq<N> {
                 ret.apply_reg_type(inner)?;
             },
             VersionRegCompType::Composite { lower, higher } => {
-                if lower.is_greater() {
-                    return Err(VersionRegError::GreaterOnPlaceOfLower);
-                }
-
                 if lower.is_strict() || higher.is_strict() {
                     return Err(VersionRegError::StrictNotAllowedInComposite);
                 }
 
-                if higher.is_lower() {
+                if lower.is_lower() {
                     return Err(VersionRegError::LowerOnPlaceOfGreater);
                 }
 
+                if higher.is_greater() {
+                    return Err(VersionRegError::GreaterOnPlaceOfLower);
+                }
+
                 ret.apply_reg_type(lower)?;
                 ret.apply_reg_type(higher)?;
+
+                if ret.normalized_bounds().is_none() {
+                    return Err(VersionRegError::EmptyRange);
+                }
             }
         }
 
@@ -474,25 +846,597 @@ impl<N: VersionNumber> TryFrom<VersionRegCompType<N>> for VersionReq<N> {
     }
 }
 
+/// Two requirements are equal if they accept the same set of versions, no matter how they were
+/// written: `^1.2`, `>=1.2, <2` and `>=1.2.0, <=1` are all equal.
+impl<N: VersionNumber> PartialEq for VersionReq<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized_bounds() == other.normalized_bounds()
+    }
+}
+
+impl<N: VersionNumber> Eq for VersionReq<N> {}
+
 impl<N: VersionNumber> core::hash::Hash for VersionReq<N> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        let major_lower = self.major_lower;
-        let minor_lower = self.minor_lower;
-        let patch_lower = self.patch_lower;
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.normalized_bounds().hash(state);
+    }
+}
+
+impl<N: VersionNumber> FromStr for VersionReq<N> {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<N: VersionNumber> VersionReq<N> {
+    pub(crate) fn fmt_lower(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let one = N::one();
+        let [major, minor, patch] = self.lower();
+        match self.comperator_lower {
+            VersionComperatorLower::Strict => write!(f, "={}.{}.{}", major, minor, patch),
+            VersionComperatorLower::GreaterMajor => write!(f, ">{}", major - one),
+            VersionComperatorLower::GreaterMinor => write!(f, ">{}.{}", major, minor - one),
+            VersionComperatorLower::GreaterPatch => write!(f, ">{}.{}.{}", major, minor, patch - one),
+            VersionComperatorLower::GreaterEqMajor => write!(f, ">={}", major),
+            VersionComperatorLower::GreaterEqMinor => write!(f, ">={}.{}", major, minor),
+            VersionComperatorLower::GreaterEqPatch => write!(f, ">={}.{}.{}", major, minor, patch),
+            VersionComperatorLower::None => Ok(()),
+        }
+    }
 
-        major_lower.hash(state);
-        minor_lower.hash(state);
-        patch_lower.hash(state);
+    pub(crate) fn fmt_upper(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let one = N::one();
+        let [major, minor, patch] = self.upper();
+        match self.comperator_higher {
+            VersionComperatorUpper::LesserMajor => write!(f, "<{}", major + one),
+            VersionComperatorUpper::LesserMinor => write!(f, "<{}.{}", major, minor + one),
+            VersionComperatorUpper::LesserPatch => write!(f, "<{}.{}.{}", major, minor, patch + one),
+            VersionComperatorUpper::LesserEqMajor => write!(f, "<={}", major),
+            VersionComperatorUpper::LesserEqMinor => write!(f, "<={}.{}", major, minor),
+            VersionComperatorUpper::LesserEqPatch => write!(f, "<={}.{}.{}", major, minor, patch),
+            VersionComperatorUpper::None => Ok(()),
+        }
+    }
+}
+
+/// Prints the canonical form of the requirement.
+///
+/// Wildcard, caret and tilde shapes are printed as `1.*`, `^1.2` and `~1.2.3` (`^0.2.3` below
+/// `1.0.0`, where caret and tilde coincide), everything else as its comparators, e.g.
+/// `>1.2, <=3`. Parsing the output yields a requirement with the same bounds.
+impl<N: VersionNumber> fmt::Display for VersionReq<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VersionComperatorLower as L;
+        use VersionComperatorUpper as U;
+
+        let [major, minor, patch] = self.lower();
+        let upper = self.upper();
+        let same_major = major == upper[0];
+        let same_minor = same_major && minor == upper[1];
+        let same_patch = same_minor && patch == upper[2];
+        let zero_major = major == N::zero();
+
+        match (self.comperator_lower, self.comperator_higher) {
+            (L::None, U::None) => f.write_str("*"),
+            (L::Strict, _) => self.fmt_lower(f),
+            (L::GreaterEqMajor, U::LesserEqMajor) if same_major => write!(f, "{}.*", major),
+            (L::GreaterEqMinor, U::LesserEqMinor) if same_minor => write!(f, "{}.{}.*", major, minor),
+            (L::GreaterEqMinor, U::LesserEqMajor) if same_major && !zero_major => write!(f, "^{}.{}", major, minor),
+            (L::GreaterEqPatch, U::LesserEqMajor) if same_major && !zero_major => write!(f, "^{}.{}.{}", major, minor, patch),
+            (L::GreaterEqPatch, U::LesserEqMinor) if same_minor && zero_major && minor != N::zero() => write!(f, "^{}.{}.{}", major, minor, patch),
+            (L::GreaterEqPatch, U::LesserEqMinor) if same_minor => write!(f, "~{}.{}.{}", major, minor, patch),
+            (L::GreaterEqPatch, U::LesserEqPatch) if same_patch => write!(f, "={}.{}.{}", major, minor, patch),
+            (L::None, _) => self.fmt_upper(f),
+            (_, U::None) => self.fmt_lower(f),
+            _ => {
+                self.fmt_lower(f)?;
+                f.write_str(", ")?;
+                self.fmt_upper(f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod version_req_tests {
+    extern crate std;
+
+    use alloc::string::ToString;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::{VersionRegCompType, VersionRegType, VersionReq};
+    use crate::parse::{ParseVersionError, ParseVersionErrorKind};
+    use crate::version::VersionNumber;
+    use crate::version_req::VersionRegError;
+    use crate::Version;
+
+    fn req(input: &str) -> VersionReq<u64> {
+        VersionReq::parse(input).unwrap()
+    }
+
+    fn ver(input: &str) -> Version<u64> {
+        input.parse().unwrap()
+    }
+
+    fn assert_fits(requirement: &str, matching: &[&str], not_matching: &[&str]) {
+        let r = req(requirement);
+        for v in matching {
+            assert!(r.fits(ver(v)), "{} should match {}", requirement, v);
+        }
+        for v in not_matching {
+            assert!(!r.fits(ver(v)), "{} should not match {}", requirement, v);
+        }
+    }
+
+    #[test]
+    fn caret_test() {
+        assert_fits("^1.2", &["1.2.1", "1.2.5", "1.9.1", "1.99.99"], &["1.1.9", "2.1.1", "3.1.1"]);
+        assert_fits("^1.2.3", &["1.2.3", "1.3.1", "1.9.9"], &["1.2.2", "1.1.9", "2.1.1"]);
+        assert_fits("1.2.3", &["1.2.3", "1.3.1"], &["1.2.2", "2.1.1"]);
+        assert_fits("^1", &["1.1.1", "1.9.9"], &["2.1.1"]);
+    }
+
+    #[test]
+    fn zero_caret_test() {
+        assert_fits("^0.2.3", &["0.2.3", "0.2.9"], &["0.2.2", "0.3.0", "1.0.0"]);
+        assert_fits("^0.2", &["0.2.0", "0.2.9"], &["0.1.9", "0.3.0"]);
+        assert_fits("^0.0.3", &["0.0.3"], &["0.0.2", "0.0.4", "0.1.0"]);
+        assert_fits("^0.0", &["0.0.0", "0.0.9"], &["0.1.0"]);
+        assert_fits("^0", &["0.0.0", "0.9.9"], &["1.0.0"]);
+        assert_fits("~0.2.3", &["0.2.3", "0.2.9"], &["0.3.0"]);
+        assert_fits("^1.0.0", &["1.0.0", "1.9.0"], &["0.9.9", "2.0.0"]);
+    }
+
+    #[test]
+    fn zero_bound_test() {
+        assert_fits("<1.0", &["0.0.0", "0.99.99"], &["1.0.0"]);
+        assert_fits("<1.2.0", &["1.1.99", "0.5.0"], &["1.2.0"]);
+        assert_fits(">=0.0.0", &["0.0.0", "5.0.0"], &[]);
+        assert_fits(">0", &["1.0.0"], &["0.99.99"]);
 
-        let major_upper = self.major_upper;
-        let minor_upper = self.minor_upper;
-        let patch_upper = self.patch_upper;
+        let err = VersionReq::<u64>::parse("<0.0.0").unwrap_err();
+        assert_eq!(err, ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(VersionRegError::Unsatisfiable), position: 1 });
+    }
+
+    #[test]
+    fn tilde_test() {
+        assert_fits("~1.2.3", &["1.2.3", "1.2.9"], &["1.2.2", "1.3.1", "2.2.3"]);
+        assert_fits("~1.2", &["1.2.1", "1.2.9"], &["1.1.9", "1.3.1"]);
+        assert_fits("~1", &["1.1.1", "1.9.9"], &["2.1.1"]);
+    }
+
+    #[test]
+    fn wildcard_test() {
+        assert_fits("*", &["1.1.1", "99.99.99"], &[]);
+        assert_fits("1.*", &["1.1.1", "1.99.99"], &["2.1.1"]);
+        assert_fits("1.2.x", &["1.2.1", "1.2.99"], &["1.3.1", "1.1.9"]);
+        assert_fits("=1.2", &["1.2.1", "1.2.99"], &["1.3.1"]);
+    }
+
+    #[test]
+    fn comparator_test() {
+        assert_fits("=1.2.3", &["1.2.3"], &["1.2.4", "1.2.2"]);
+        assert_fits(">1.2.3", &["1.2.4", "1.3.1", "2.1.1"], &["1.2.3", "1.1.9"]);
+        assert_fits(">1.2", &["1.3.1", "2.1.1"], &["1.2.9", "1.1.1"]);
+        assert_fits(">1", &["2.1.1"], &["1.99.99"]);
+        assert_fits(">=1.2", &["1.2.1", "2.1.1"], &["1.1.99"]);
+        assert_fits("<1.2.3", &["1.2.2", "1.1.9"], &["1.2.3", "1.3.1"]);
+        assert_fits("<2", &["1.99.99"], &["2.1.1"]);
+        assert_fits("<=1.2", &["1.2.99", "1.1.1"], &["1.3.1"]);
+        assert_fits(">=1.1, <2.1", &["1.1.0", "1.5.1", "2.0.99"], &["1.0.99", "2.1.0"]);
+        assert_fits(" >= 1.1 ,<2 ", &["1.1.1", "1.9.1"], &["2.1.1"]);
+        assert_fits("^1.2, <1.5", &["1.2.1", "1.4.99"], &["1.5.1", "1.1.1"]);
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let err = |input: &str| VersionReq::<u64>::parse(input).unwrap_err();
+
+        assert_eq!(err("").kind, ParseVersionErrorKind::Empty);
+        assert_eq!(err(">=1.2,"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedEnd, position: 6 });
+        assert_eq!(err(">=1.2 <2"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('<'), position: 6 });
+        assert_eq!(err(">*"), ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('*'), position: 1 });
+        assert_eq!(err("=1.2.3, <2"), ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(VersionRegError::StrictNotAllowedInComposite), position: 8 });
+        assert_eq!(err("^1.01"), ParseVersionError { kind: ParseVersionErrorKind::LeadingZero, position: 3 });
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn satisfying_test() {
+        let versions: alloc::vec::Vec<Version<u64>> = ["1.0.0", "1.2.3", "2.0.0", "1.9.1", "0.9.0", "1.2.0", "3.1.0"]
+            .iter()
+            .map(|v| ver(v))
+            .collect();
+
+        assert_eq!(req("^1.2").max_satisfying(&versions), Some(ver("1.9.1")));
+        assert_eq!(req("^1.2").min_satisfying(&versions), Some(ver("1.2.0")));
+        assert_eq!(req("^1.2").filter(&versions), [ver("1.2.3"), ver("1.9.1"), ver("1.2.0")]);
+        assert_eq!(req("*").max_satisfying(&versions), Some(ver("3.1.0")));
+        assert_eq!(req("^4").max_satisfying(&versions), None);
+        assert_eq!(req("^4").min_satisfying(&[]), None);
+        assert!(req("^4").filter(&versions).is_empty());
+    }
 
-        major_upper.hash(state);
-        minor_upper.hash(state);
-        patch_upper.hash(state);
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn satisfying_agreement_test() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let r = match VersionReq::<u64>::parse(&random_req(&mut rng)) {
+                Ok(r) => r,
+                Err(_) => continue,
+            };
+            let versions: alloc::vec::Vec<Version<u64>> = (0..rng.gen_range(0..20))
+                .map(|_| Version::new(rng.gen_range(0..5), rng.gen_range(0..5), rng.gen_range(0..5)).unwrap())
+                .collect();
+
+            let expected: alloc::vec::Vec<Version<u64>> = versions.iter().copied().filter(|v| r.fits(*v)).collect();
+            assert_eq!(r.filter(&versions), expected, "{}", r);
+            assert_eq!(r.max_satisfying(&versions), expected.iter().copied().max(), "{}", r);
+            assert_eq!(r.min_satisfying(&versions), expected.iter().copied().min(), "{}", r);
+        }
+    }
+
+    #[test]
+    fn display_test() {
+        let canonical = [
+            ("*", "*"),
+            ("1.*", "1.*"),
+            ("=1", "1.*"),
+            ("~1", "1.*"),
+            ("1.2.x", "1.2.*"),
+            ("~1.2", "1.2.*"),
+            ("^1.2", "^1.2"),
+            ("1.2.3", "^1.2.3"),
+            ("~1.2.3", "~1.2.3"),
+            ("^0.2.3", "^0.2.3"),
+            ("~0.2.3", "^0.2.3"),
+            ("^0.0.3", "=0.0.3"),
+            ("^0.2", "0.2.*"),
+            ("<1.0", "<1"),
+            ("<1.2.0", "<1.2"),
+            ("=1.2.3", "=1.2.3"),
+            (">1.2", ">1.2"),
+            ("<=3", "<=3"),
+            ("<2.1, >1", ">1, <2.1"),
+            (">=1.2.3, <=1.5", ">=1.2.3, <=1.5"),
+            // Caret shapes only where `^` means the same range below 1.0.0.
+            (">=0.0.3, <1", ">=0.0.3, <1"),
+            (">=0.2, <1", ">=0.2, <1"),
+        ];
+
+        for (input, expected) in canonical {
+            let r = req(input);
+            let printed = r.to_string();
+            assert_eq!(printed, expected, "canonical form of {}", input);
+
+            let reparsed = req(&printed);
+            assert_eq!(reparsed.to_string(), printed);
+            assert_eq!(reparsed.lower(), r.lower());
+            assert_eq!(reparsed.upper(), r.upper());
+        }
+    }
+
+    fn hash_of<T: core::hash::Hash>(value: &T) -> u64 {
+        use core::hash::Hasher;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn random_comparator(rng: &mut impl rand::Rng) -> alloc::string::String {
+        const OPERATORS: [&str; 8] = ["", "=", ">", ">=", "<", "<=", "~", "^"];
+        let op = OPERATORS[rng.gen_range(0..OPERATORS.len())];
+        match rng.gen_range(1..=3) {
+            1 => alloc::format!("{}{}", op, rng.gen_range(0..=3)),
+            2 => alloc::format!("{}{}.{}", op, rng.gen_range(0..=3), rng.gen_range(0..=3)),
+            _ => alloc::format!("{}{}.{}.{}", op, rng.gen_range(0..=3), rng.gen_range(0..=3), rng.gen_range(0..=3)),
+        }
+    }
+
+    fn random_req(rng: &mut impl rand::Rng) -> alloc::string::String {
+        if rng.gen_bool(0.5) {
+            random_comparator(rng)
+        } else {
+            alloc::format!("{}, {}", random_comparator(rng), random_comparator(rng))
+        }
+    }
+
+    #[test]
+    fn semantic_eq_test() {
+        assert_eq!(req("^1.2"), req(">=1.2, <2"));
+        assert_eq!(req("^1.2"), req(">=1.2.0, <=1"));
+        assert_eq!(req(">1.2.3"), req(">=1.2.4"));
+        assert_eq!(req("<1.3"), req("<=1.2"));
+        assert_eq!(req("~1.2.3"), req("1.2.*, >=1.2.3"));
+        assert_eq!(req("=1.2.3"), req(">=1.2.3, <=1.2.3"));
+        assert_eq!(VersionReq::<u64>::from_bounds([2, 0, 0], [0; 3]), VersionReq::from_bounds([1, 3, 0], [1, 1, 0]));
+        assert_ne!(req("^1.2"), req("^1.3"));
+        assert_ne!(req("*"), req(">0.0.1"));
+        assert_eq!(hash_of(&req("^1.2")), hash_of(&req(">=1.2, <2")));
+    }
+
+    /// Equal requirements must accept the same versions and hash the same.
+    #[test]
+    fn eq_hash_property_test() {
+        let mut rng = rand::thread_rng();
+        let grid: alloc::vec::Vec<Version<u64>> = (0..=5)
+            .flat_map(|major| (0..=5).flat_map(move |minor| (0..=5).map(move |patch| Version::new(major, minor, patch).unwrap())))
+            .collect();
+        let mut checked = 0;
+        while checked < 2000 {
+            let (a, b) = match (VersionReq::<u64>::parse(&random_req(&mut rng)), VersionReq::<u64>::parse(&random_req(&mut rng))) {
+                (Ok(a), Ok(b)) => (a, b),
+                _ => continue,
+            };
+            checked += 1;
+            let same_set = grid.iter().all(|v| a.fits(*v) == b.fits(*v));
+            assert_eq!(a == b, same_set, "{} and {}", a, b);
+            if a == b {
+                assert_eq!(hash_of(&a), hash_of(&b), "{} and {}", a, b);
+            }
+            assert_eq!(a, a);
+            assert_eq!(hash_of(&a), hash_of(&a.clone()));
+        }
+    }
+
+    #[test]
+    fn bounds_test() {
+        use core::ops::Bound::{Excluded, Included, Unbounded};
+
+        let bounds = |input: &str| (req(input).lower_bound(), req(input).upper_bound());
+        assert_eq!(bounds("^1.2"), (Included(ver("1.2.0")), Excluded(ver("2.0.0"))));
+        assert_eq!(bounds("~1.2.3"), (Included(ver("1.2.3")), Excluded(ver("1.3.0"))));
+        assert_eq!(bounds(">1.2.3, <=1.5.0"), (Included(ver("1.2.4")), Included(ver("1.5.0"))));
+        assert_eq!(bounds("<1.0"), (Unbounded, Excluded(ver("1.0.0"))));
+        assert_eq!(bounds("=1.2.3"), (Included(ver("1.2.3")), Included(ver("1.2.3"))));
+        assert_eq!(bounds("*"), (Unbounded, Unbounded));
+        assert_eq!(bounds(">=2"), (Included(ver("2.0.0")), Unbounded));
+
+        let err = VersionReq::<u64>::parse(">2, <1").unwrap_err();
+        assert_eq!(err, ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(VersionRegError::EmptyRange), position: 4 });
+        let err = VersionReq::<u64>::const_parse(">=1.2, ^1.5, <1.4").unwrap_err();
+        assert_eq!(err, ParseVersionError { kind: ParseVersionErrorKind::InvalidRequirement(VersionRegError::EmptyRange), position: 13 });
+
+        let err = VersionReq::<u8>::parse(">254").unwrap_err();
+        assert_eq!(err.kind, ParseVersionErrorKind::InvalidRequirement(VersionRegError::Unsatisfiable));
+    }
+
+    #[test]
+    fn explain_test() {
+        let explain = |requirement: &str, version: &str| req(requirement).explain(ver(version)).map(|m| m.to_string());
+        assert_eq!(explain("^2.1", "3.0.0").unwrap(), "major 3 exceeds upper bound <3.0.0");
+        assert_eq!(explain("^2.1", "2.0.7").unwrap(), "minor 0 is below lower bound >=2.1.0");
+        assert_eq!(explain("~1.2.3", "1.3.0").unwrap(), "minor 3 exceeds upper bound <1.3.0");
+        assert_eq!(explain(">=1.2.3, <=1.5.2", "1.5.3").unwrap(), "patch 3 exceeds upper bound <=1.5.2");
+        let empty = VersionReq::<u64>::from_bounds([3, 0, 0], [0; 3]);
+        assert_eq!(empty.explain(ver("1.0.0")).unwrap().to_string(), "requirement accepts no version");
+        assert_eq!(explain("^2.1", "2.3.0"), None);
+
+        let mismatch = req("^1.2").explain(ver("1.1.0")).unwrap();
+        assert!(matches!(mismatch, super::Mismatch::BelowLower { component: crate::VersionDelta::Minor, .. }));
+    }
+
+    /// `explain` finds a mismatch exactly for the versions that don't fit.
+    #[test]
+    fn explain_property_test() {
+        let mut rng = rand::thread_rng();
+        let grid: alloc::vec::Vec<Version<u64>> = (0..=4)
+            .flat_map(|major| (0..=4).flat_map(move |minor| (0..=4).map(move |patch| Version::new(major, minor, patch).unwrap())))
+            .collect();
+        for _ in 0..1000 {
+            let r = match VersionReq::<u64>::parse(&random_req(&mut rng)) {
+                Ok(r) => r,
+                Err(_) => continue,
+            };
+            for version in &grid {
+                assert_eq!(r.explain(*version).is_none(), r.fits(*version), "{} on {}", r, version);
+            }
+        }
+    }
+
+    // The conformance suite checks every comparator against the truth table on `VersionRegType`.
+    // Comparators are built from the components `0`, `1`, `2` and `top`. Every bound they
+    // produce, and the smallest version above or below it, is made of `0..=3`, `top - 1` and
+    // `top`, so checking those versions covers every version.
+
+    fn grid<N: VersionNumber>() -> [N; 4] {
+        let one = N::one();
+        [N::zero(), one, one + one, N::max() - one]
+    }
+
+    fn probes<N: VersionNumber>() -> Vec<Version<N>> {
+        let one = N::one();
+        let top = N::max() - one;
+        let values = [N::zero(), one, one + one, one + one + one, top - one, top];
+        let mut ret = Vec::new();
+        for major in values {
+            for minor in values {
+                for patch in values {
+                    ret.push(Version { major, minor, patch });
+                }
+            }
+        }
+        ret
+    }
+
+    /// Every `VersionRegType` on the grid, with its cargo syntax.
+    fn reg_types<N: VersionNumber>() -> Vec<(VersionRegType<N>, String)> {
+        let mut ret = Vec::new();
+        for major in grid::<N>() {
+            for (op, reg_type) in [
+                (">", VersionRegType::GreaterMajor { major }),
+                (">=", VersionRegType::GreaterEqMajor { major }),
+                ("<", VersionRegType::LesserMajor { major }),
+                ("<=", VersionRegType::LesserEqMajor { major }),
+            ] {
+                ret.push((reg_type, format!("{}{}", op, major)));
+            }
+            for minor in grid::<N>() {
+                for (op, reg_type) in [
+                    (">", VersionRegType::GreaterMinor { major, minor }),
+                    (">=", VersionRegType::GreaterEqMinor { major, minor }),
+                    ("<", VersionRegType::LesserMinor { major, minor }),
+                    ("<=", VersionRegType::LesserEqMinor { major, minor }),
+                ] {
+                    ret.push((reg_type, format!("{}{}.{}", op, major, minor)));
+                }
+                for patch in grid::<N>() {
+                    for (op, reg_type) in [
+                        ("=", VersionRegType::Strict(Version { major, minor, patch })),
+                        (">", VersionRegType::GreaterPatch { major, minor, patch }),
+                        (">=", VersionRegType::GreaterEqPatch { major, minor, patch }),
+                        ("<", VersionRegType::LesserPatch { major, minor, patch }),
+                        ("<=", VersionRegType::LesserEqPatch { major, minor, patch }),
+                    ] {
+                        ret.push((reg_type, format!("{}{}.{}.{}", op, major, minor, patch)));
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    /// The truth table, written out as plain comparisons.
+    fn truth_table<N: VersionNumber>(reg_type: &VersionRegType<N>, v: &Version<N>) -> bool {
+        let major = [v.major];
+        let minor = [v.major, v.minor];
+        let patch = [v.major, v.minor, v.patch];
+        match *reg_type {
+            VersionRegType::Strict(s) => patch == [s.major, s.minor, s.patch],
+            VersionRegType::GreaterMajor { major: a } => major > [a],
+            VersionRegType::GreaterMinor { major: a, minor: b } => minor > [a, b],
+            VersionRegType::GreaterPatch { major: a, minor: b, patch: c } => patch > [a, b, c],
+            VersionRegType::GreaterEqMajor { major: a } => major >= [a],
+            VersionRegType::GreaterEqMinor { major: a, minor: b } => minor >= [a, b],
+            VersionRegType::GreaterEqPatch { major: a, minor: b, patch: c } => patch >= [a, b, c],
+            VersionRegType::LesserMajor { major: a } => major < [a],
+            VersionRegType::LesserMinor { major: a, minor: b } => minor < [a, b],
+            VersionRegType::LesserPatch { major: a, minor: b, patch: c } => patch < [a, b, c],
+            VersionRegType::LesserEqMajor { major: a } => major <= [a],
+            VersionRegType::LesserEqMinor { major: a, minor: b } => minor <= [a, b],
+            VersionRegType::LesserEqPatch { major: a, minor: b, patch: c } => patch <= [a, b, c],
+        }
+    }
+
+    fn assert_conforms<N: VersionNumber + core::fmt::Debug>(req: &VersionReq<N>, accepts: impl Fn(&Version<N>) -> bool, name: &str) {
+        for version in probes::<N>() {
+            assert_eq!(req.fits(version), accepts(&version), "{} on {}", name, version);
+        }
+    }
+
+    fn truth_table_test<N: VersionNumber + core::fmt::Debug>() {
+        let probes = probes::<N>();
+        for (reg_type, syntax) in reg_types::<N>() {
+            let satisfiable = probes.iter().any(|v| truth_table(&reg_type, v));
+            let converted = VersionReq::try_from(VersionRegCompType::Pure(reg_type));
+            let parsed = VersionReq::<N>::parse(&syntax);
+
+            if !satisfiable {
+                assert_eq!(converted.unwrap_err(), VersionRegError::Unsatisfiable, "{}", syntax);
+                assert_eq!(parsed.unwrap_err().kind, ParseVersionErrorKind::InvalidRequirement(VersionRegError::Unsatisfiable), "{}", syntax);
+                continue;
+            }
+
+            let converted = converted.unwrap();
+            assert_conforms(&converted, |v| truth_table(&reg_type, v), &syntax);
+            assert_eq!(parsed.unwrap(), converted, "{}", syntax);
+        }
+    }
+
+    #[test]
+    fn truth_table_u8_test() {
+        truth_table_test::<u8>();
+    }
+
+    #[test]
+    fn truth_table_i16_test() {
+        truth_table_test::<i16>();
+    }
+
+    #[test]
+    fn truth_table_u64_test() {
+        truth_table_test::<u64>();
+
+        for (reg_type, syntax) in reg_types::<u64>() {
+            match VersionReq::const_parse(&syntax) {
+                Ok(req) => assert_conforms(&req, |v| truth_table(&reg_type, v), &syntax),
+                Err(e) => assert_eq!(e.kind, ParseVersionErrorKind::InvalidRequirement(VersionRegError::Unsatisfiable), "{}", syntax),
+            }
+        }
+    }
+
+    fn composite_test<N: VersionNumber + core::fmt::Debug>() {
+        let probes = probes::<N>();
+        // Which probes each comparator accepts, computed once for all pairs.
+        let (lower, higher): (Vec<_>, Vec<_>) = reg_types::<N>()
+            .into_iter()
+            .filter(|(reg_type, _)| !reg_type.is_strict())
+            .map(|(reg_type, syntax)| {
+                let accepted: Vec<bool> = probes.iter().map(|v| truth_table(&reg_type, v)).collect();
+                (reg_type, syntax, accepted)
+            })
+            .partition(|(reg_type, _, _)| reg_type.is_greater());
+
+        for (lower, lower_syntax, lower_accepted) in &lower {
+            for (higher, higher_syntax, higher_accepted) in &higher {
+                let syntax = format!("{}, {}", lower_syntax, higher_syntax);
+                let composite = VersionRegCompType::Composite { lower: *lower, higher: *higher };
+                let swapped = VersionRegCompType::Composite { lower: *higher, higher: *lower };
+                assert_eq!(VersionReq::try_from(swapped).unwrap_err(), VersionRegError::LowerOnPlaceOfGreater, "{}", syntax);
+
+                let accepted: Vec<bool> = lower_accepted.iter().zip(higher_accepted).map(|(a, b)| *a && *b).collect();
+                let converted = VersionReq::try_from(composite);
+                if !lower_accepted.contains(&true) || !higher_accepted.contains(&true) {
+                    assert_eq!(converted.unwrap_err(), VersionRegError::Unsatisfiable, "{}", syntax);
+                } else if !accepted.contains(&true) {
+                    assert_eq!(converted.unwrap_err(), VersionRegError::EmptyRange, "{}", syntax);
+                } else {
+                    // A range accepts an interval and both of its ends are among the probes, so
+                    // comparing the bounds is enough, `fits` on them is covered by the truth table.
+                    let converted = converted.unwrap();
+                    let mut accepted = probes.iter().zip(&accepted).filter(|(_, a)| **a).map(|(v, _)| [v.major, v.minor, v.patch]);
+                    let first = accepted.next().unwrap();
+                    let last = accepted.last().unwrap_or(first);
+                    assert_eq!(converted.normalized_bounds(), Some((first, last)), "{}", syntax);
+                    assert_eq!(VersionReq::<N>::parse(&syntax).unwrap(), converted, "{}", syntax);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn composite_u8_test() {
+        composite_test::<u8>();
+    }
+
+    #[test]
+    fn composite_i64_test() {
+        composite_test::<i64>();
+    }
 
-        self.comperator_lower.hash(state);
-        self.comperator_higher.hash(state);
+    #[test]
+    fn composite_error_test() {
+        let strict = VersionRegType::Strict(ver("1.2.3"));
+        let lower = VersionRegType::GreaterEqMajor { major: 1 };
+        let higher = VersionRegType::LesserMajor { major: 2 };
+        let error = |lower, higher| VersionReq::<u64>::try_from(VersionRegCompType::Composite { lower, higher }).unwrap_err();
+
+        assert_eq!(error(strict, higher), VersionRegError::StrictNotAllowedInComposite);
+        assert_eq!(error(lower, strict), VersionRegError::StrictNotAllowedInComposite);
+        assert_eq!(error(lower, lower), VersionRegError::GreaterOnPlaceOfLower);
+        assert_eq!(error(higher, higher), VersionRegError::LowerOnPlaceOfGreater);
+        assert_eq!(error(VersionRegType::GreaterMajor { major: 2 }, higher), VersionRegError::EmptyRange);
+        assert_eq!(error(lower, VersionRegType::LesserMajor { major: 0 }), VersionRegError::Unsatisfiable);
+
+        let invalid = VersionRegType::Strict(Version { major: 1, minor: u64::MAX, patch: 0 });
+        assert_eq!(VersionReq::try_from(VersionRegCompType::Pure(invalid)).unwrap_err(), VersionRegError::MaxValueNotAllowed);
     }
 }
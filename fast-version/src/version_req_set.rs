@@ -0,0 +1,298 @@
+use core::fmt;
+use core::str::FromStr;
+
+use alloc::vec::Vec;
+
+use crate::parse::{parse_version_req_set, ParseVersionError};
+use crate::version::VersionNumber;
+use crate::{Version, VersionReq};
+
+/// A union of version requirements, e.g. `1.4.* || >=2.1, <3`.
+///
+/// The set is stored as sorted, disjoint and non adjacent inclusive intervals, so two sets
+/// accepting the same versions are always equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersionReqSet<N: VersionNumber> {
+    intervals: Vec<([N; 3], [N; 3])>,
+}
+
+/// The largest valid version, `max` is reserved for open bounds.
+fn top<N: VersionNumber>() -> [N; 3] {
+    let top = N::max() - N::one();
+    [top, top, top]
+}
+
+/// The next valid version after `version`, `None` if `version` is the largest one.
+fn successor<N: VersionNumber>(mut version: [N; 3]) -> Option<[N; 3]> {
+    let top = N::max() - N::one();
+    for i in (0..3).rev() {
+        if version[i] < top {
+            version[i] = version[i] + N::one();
+            version[(i + 1)..].iter_mut().for_each(|c| *c = N::zero());
+            return Some(version);
+        }
+    }
+    None
+}
+
+/// The valid version before `version`, `None` if `version` is `0.0.0`.
+fn predecessor<N: VersionNumber>(mut version: [N; 3]) -> Option<[N; 3]> {
+    let top = N::max() - N::one();
+    for i in (0..3).rev() {
+        if version[i] > N::zero() {
+            version[i] = version[i] - N::one();
+            version[(i + 1)..].iter_mut().for_each(|c| *c = top);
+            return Some(version);
+        }
+    }
+    None
+}
+
+impl<N: VersionNumber> VersionReqSet<N> {
+    /// The set that accepts no version.
+    pub fn empty() -> Self {
+        Self { intervals: Vec::new() }
+    }
+
+    /// The set that accepts every version, like `*`.
+    pub fn any() -> Self {
+        Self::from(VersionReq::default())
+    }
+
+    /// Parses `||` separated requirements, e.g. `1.4.* || >=2.1, <3`.
+    pub fn parse(input: &str) -> Result<Self, ParseVersionError> {
+        parse_version_req_set(input)
+    }
+
+    fn from_intervals(mut intervals: Vec<([N; 3], [N; 3])>) -> Self {
+        // The components are integers, which are always comparable.
+        intervals.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut merged: Vec<([N; 3], [N; 3])> = Vec::with_capacity(intervals.len());
+        for (lower, upper) in intervals {
+            match merged.last_mut() {
+                Some(last) if successor(last.1).is_none_or(|next| lower <= next) => {
+                    if upper > last.1 {
+                        last.1 = upper;
+                    }
+                }
+                _ => merged.push((lower, upper)),
+            }
+        }
+
+        Self { intervals: merged }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn fits(&self, version: Version<N>) -> bool {
+        let ver = [version.major, version.minor, version.patch];
+        let index = self.intervals.partition_point(|(lower, _)| *lower <= ver);
+        index > 0 && ver <= self.intervals[index - 1].1
+    }
+
+    /// Every version accepted by `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut intervals = self.intervals.clone();
+        intervals.extend_from_slice(&other.intervals);
+        Self::from_intervals(intervals)
+    }
+
+    /// Every version accepted by both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a_lower, a_upper) = self.intervals[i];
+            let (b_lower, b_upper) = other.intervals[j];
+
+            let lower = if a_lower > b_lower { a_lower } else { b_lower };
+            let upper = if a_upper < b_upper { a_upper } else { b_upper };
+            if lower <= upper {
+                intervals.push((lower, upper));
+            }
+
+            if a_upper < b_upper {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { intervals }
+    }
+
+    /// Every version not accepted by `self`.
+    pub fn complement(&self) -> Self {
+        let mut intervals = Vec::with_capacity(self.intervals.len() + 1);
+        let mut start = Some([N::zero(); 3]);
+        for (lower, upper) in &self.intervals {
+            if let (Some(from), Some(to)) = (start, predecessor(*lower)) {
+                if from <= to {
+                    intervals.push((from, to));
+                }
+            }
+            start = successor(*upper);
+        }
+        if let Some(from) = start {
+            intervals.push((from, top()));
+        }
+
+        Self { intervals }
+    }
+
+    /// Whether every version accepted by `self` is also accepted by `other`.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.intersection(other) == *self
+    }
+
+    /// The contiguous requirements making up the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = VersionReq<N>> + '_ {
+        self.intervals.iter().map(|(lower, upper)| VersionReq::from_bounds(*lower, *upper))
+    }
+}
+
+impl<N: VersionNumber> From<VersionReq<N>> for VersionReqSet<N> {
+    fn from(value: VersionReq<N>) -> Self {
+        Self { intervals: value.normalized_bounds().into_iter().collect() }
+    }
+}
+
+impl<N: VersionNumber> FromStr for VersionReqSet<N> {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Prints the intervals separated by ` || `, the empty set is printed as `<0.0.0`.
+impl<N: VersionNumber> fmt::Display for VersionReqSet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("<0.0.0");
+        }
+
+        for (i, req) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" || ")?;
+            }
+            write!(f, "{}", req)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod version_req_set_tests {
+    use alloc::string::ToString;
+
+    use rand::{thread_rng, Rng};
+
+    use super::VersionReqSet;
+    use crate::parse::{ParseVersionError, ParseVersionErrorKind};
+    use crate::Version;
+
+    fn set(input: &str) -> VersionReqSet<u64> {
+        VersionReqSet::parse(input).unwrap()
+    }
+
+    fn ver(input: &str) -> Version<u64> {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn fits_test() {
+        let s = set("1.4.* || >=2.1, <3");
+        for v in ["1.4.0", "1.4.9", "2.1.0", "2.9.9"] {
+            assert!(s.fits(ver(v)), "{}", v);
+        }
+        for v in ["1.3.9", "1.5.0", "2.0.9", "3.0.0", "0.0.0"] {
+            assert!(!s.fits(ver(v)), "{}", v);
+        }
+    }
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(set("^1.2 || ^1.5"), set("^1.2"));
+        assert_eq!(set("1.2.* || 1.3.*"), set(">=1.2, <1.4"));
+        assert_eq!(set("=1.2.3 || =1.2.4"), set(">=1.2.3, <=1.2.4"));
+        assert_eq!(set("<0.0.0 || ^1"), set("^1"));
+        assert!(set("<0.0.0").is_empty());
+        assert!(set(">2, <1").is_empty());
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(set("1.4.* || >=2.1, <3").to_string(), "1.4.* || ^2.1");
+        assert_eq!(set("^2 || 1.4.*").to_string(), "1.4.* || 2.*");
+        assert_eq!(set("*").to_string(), "*");
+        assert_eq!(set(">=1.2.3, <2.0.1").to_string(), ">=1.2.3, <=2.0.0");
+        assert_eq!(VersionReqSet::<u64>::empty().to_string(), "<0.0.0");
+        assert_eq!(set("1.4.* || ^2.1").complement().to_string(), "<=1.3 || >=1.5, <=2.0 || >=3");
+    }
+
+    #[test]
+    fn set_operations_test() {
+        let a = set("^1 || ^3");
+        let b = set(">=1.5, <3.2");
+
+        assert_eq!(a.union(&b), set(">=1, <3.2 || ^3"));
+        assert_eq!(a.intersection(&b), set(">=1.5, <2 || >=3, <3.2"));
+        assert_eq!(a.complement(), set("<1 || 2.* || >=4"));
+        assert_eq!(a.complement().complement(), a);
+        assert!(set("^1.2").is_subset_of(&a));
+        assert!(!b.is_subset_of(&a));
+        assert!(VersionReqSet::empty().is_subset_of(&a));
+        assert!(a.is_subset_of(&VersionReqSet::any()));
+        assert_eq!(VersionReqSet::<u64>::any().complement(), VersionReqSet::empty());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert_eq!(
+            VersionReqSet::<u64>::parse("^1 || ^1.x.3"),
+            Err(ParseVersionError { kind: ParseVersionErrorKind::UnexpectedCharacter('.'), position: 10 })
+        );
+        assert_eq!(
+            VersionReqSet::<u64>::parse("^1 || "),
+            Err(ParseVersionError { kind: ParseVersionErrorKind::Empty, position: 6 })
+        );
+    }
+
+    /// Checks the set operations against `fits` on a grid of small versions.
+    #[test]
+    fn set_operations_property_test() {
+        const OPERATORS: [&str; 7] = ["=", ">", ">=", "<", "<=", "~", "^"];
+        let mut rng = thread_rng();
+        let mut random_set = || {
+            let mut ret = alloc::string::String::new();
+            for i in 0..rng.gen_range(1..=3) {
+                if i > 0 {
+                    ret.push_str(" || ");
+                }
+                let op = OPERATORS[rng.gen_range(0..OPERATORS.len())];
+                ret.push_str(&alloc::format!("{}{}.{}", op, rng.gen_range(0..=3), rng.gen_range(0..=3)));
+            }
+            VersionReqSet::<u64>::parse(&ret).unwrap()
+        };
+        let grid: alloc::vec::Vec<Version<u64>> = (0..=4)
+            .flat_map(|major| (0..=4).flat_map(move |minor| (0..=4).map(move |patch| Version::new(major, minor, patch).unwrap())))
+            .collect();
+
+        for _ in 0..500 {
+            let (a, b) = (random_set(), random_set());
+            let (union, intersection, complement) = (a.union(&b), a.intersection(&b), a.complement());
+            for v in &grid {
+                assert_eq!(union.fits(*v), a.fits(*v) || b.fits(*v), "{} | {} at {}", a, b, v);
+                assert_eq!(intersection.fits(*v), a.fits(*v) && b.fits(*v), "{} & {} at {}", a, b, v);
+                assert_eq!(complement.fits(*v), !a.fits(*v), "!{} at {}", a, v);
+            }
+            assert_eq!(a.is_subset_of(&b), grid.iter().all(|v| !a.fits(*v) || b.fits(*v)), "{} <= {}", a, b);
+            assert_eq!(VersionReqSet::parse(&a.to_string()), Ok(a));
+        }
+    }
+}
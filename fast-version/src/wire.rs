@@ -0,0 +1,452 @@
+//! Compact binary encoding of [`Version`] and [`VersionReq`].
+//!
+//! Components are written as unsigned LEB128 varints, so small version numbers take a single
+//! byte no matter how wide `N` is. A version is its three components. A requirement starts with
+//! a tag byte followed by the components of its normalized bounds:
+//!
+//! * bits 0-1: number of lower bound components, trailing zeros are left out, `0` is `>=0.0.0`
+//! * bits 2-3: number of upper bound components, trailing open components are left out, `0` is
+//!   no upper bound
+//! * bit 4: the requirement accepts no version, no components follow
+//!
+//! `^1.2` is encoded as `[0b0110, 1, 2, 1]`. Encoding and decoding never allocate, and decoding
+//! only accepts the canonical encoding, so `encode(decode(bytes)) == bytes` for any input that
+//! decodes.
+
+use core::fmt;
+
+use crate::offset::{OffsetI128, OffsetI16, OffsetI32, OffsetI64, OffsetI8, OffsetIsize};
+use crate::offset::{OffsetU128, OffsetU16, OffsetU32, OffsetU64, OffsetU8, OffsetUsize};
+use crate::version::VersionNumber;
+use crate::{Version, VersionReq};
+
+/// Conversion of a component to and from the integer written as a varint.
+pub trait WireNumber: Sized {
+    fn to_wire(self) -> u128;
+    fn from_wire(value: u128) -> Option<Self>;
+}
+
+macro_rules! impl_wire_number {
+    ($($t:ty),*) => {
+        $(
+            impl WireNumber for $t {
+                #[inline]
+                fn to_wire(self) -> u128 {
+                    // Valid components are never negative.
+                    self as u128
+                }
+
+                #[inline]
+                fn from_wire(value: u128) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, u128, i128);
+
+macro_rules! impl_offset_wire_number {
+    ($($t:ty => $prim:ty),*) => {
+        $(
+            impl WireNumber for $t {
+                #[inline]
+                fn to_wire(self) -> u128 {
+                    self.get().to_wire()
+                }
+
+                #[inline]
+                fn from_wire(value: u128) -> Option<Self> {
+                    <$prim>::from_wire(value).and_then(Self::new)
+                }
+            }
+        )*
+    };
+}
+
+impl_offset_wire_number!(
+    OffsetU8 => u8, OffsetU16 => u16, OffsetU32 => u32, OffsetU64 => u64, OffsetUsize => usize, OffsetU128 => u128,
+    OffsetI8 => i8, OffsetI16 => i16, OffsetI32 => i32, OffsetI64 => i64, OffsetIsize => isize, OffsetI128 => i128
+);
+
+const LOWER_LEN_MASK: u8 = 0b0011;
+const UPPER_LEN_SHIFT: u8 = 2;
+const EMPTY: u8 = 0b1_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireErrorKind {
+    /// The output buffer is too small, see `encoded_len`.
+    BufferTooSmall,
+    UnexpectedEnd,
+    /// A varint doesn't fit into 128 bits.
+    Overflow,
+    /// A component doesn't fit into `N` or is `N::max()`.
+    InvalidComponent,
+    InvalidTag(u8),
+    /// The input decodes, but isn't how the value would be encoded: a varint with trailing zero
+    /// groups, a bound with a trailing component that should have been left out or bounds that
+    /// accept nothing without the empty tag.
+    NonCanonical,
+}
+
+/// Error returned when encoding or decoding fails.
+///
+/// `position` is the byte offset into the buffer at which the error occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireError {
+    pub kind: WireErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            WireErrorKind::BufferTooSmall => write!(f, "buffer too small, ran out at position {}", self.position),
+            WireErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at position {}", self.position),
+            WireErrorKind::Overflow => write!(f, "varint at position {} overflows", self.position),
+            WireErrorKind::InvalidComponent => write!(f, "invalid version component at position {}", self.position),
+            WireErrorKind::InvalidTag(tag) => write!(f, "invalid requirement tag {:#04x} at position {}", tag, self.position),
+            WireErrorKind::NonCanonical => write!(f, "non-canonical encoding at position {}", self.position),
+        }
+    }
+}
+
+impl core::error::Error for WireError {}
+
+#[inline]
+fn varint_len(value: u128) -> usize {
+    let bits = 128 - value.leading_zeros() as usize;
+    bits.div_ceil(7).max(1)
+}
+
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+
+    fn byte(&mut self, byte: u8) -> Result<(), WireError> {
+        match self.buf.get_mut(self.position) {
+            Some(slot) => {
+                *slot = byte;
+                self.position += 1;
+                Ok(())
+            }
+            None => Err(WireError { kind: WireErrorKind::BufferTooSmall, position: self.position }),
+        }
+    }
+
+    fn varint(&mut self, mut value: u128) -> Result<(), WireError> {
+        while value >= 0x80 {
+            self.byte(value as u8 | 0x80)?;
+            value >>= 7;
+        }
+        self.byte(value as u8)
+    }
+
+    fn component<N: VersionNumber>(&mut self, value: N) -> Result<(), WireError> {
+        self.varint(value.to_wire())
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+
+    fn error(&self, kind: WireErrorKind, position: usize) -> WireError {
+        WireError { kind, position }
+    }
+
+    fn byte(&mut self) -> Result<u8, WireError> {
+        let byte = *self.buf.get(self.position)
+            .ok_or(self.error(WireErrorKind::UnexpectedEnd, self.position))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u128, WireError> {
+        let start = self.position;
+        let mut value = 0_u128;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            let group = (byte & 0x7f) as u128;
+            if shift == 126 && group > 0b11 {
+                return Err(self.error(WireErrorKind::Overflow, start));
+            }
+            value |= group << shift;
+
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err(self.error(WireErrorKind::NonCanonical, start));
+                }
+                return Ok(value);
+            }
+
+            shift += 7;
+            if shift > 126 {
+                return Err(self.error(WireErrorKind::Overflow, start));
+            }
+        }
+    }
+
+    fn component<N: VersionNumber>(&mut self) -> Result<N, WireError> {
+        let start = self.position;
+        match N::from_wire(self.varint()?) {
+            Some(n) if n != N::max() => Ok(n),
+            _ => Err(self.error(WireErrorKind::InvalidComponent, start)),
+        }
+    }
+}
+
+impl<N: VersionNumber> Version<N> {
+    /// Number of bytes `encode_into` writes.
+    pub fn encoded_len(&self) -> usize {
+        [self.major, self.minor, self.patch]
+            .iter()
+            .map(|c| varint_len(c.to_wire()))
+            .sum()
+    }
+
+    /// Writes the compact encoding into `buf`, returning the number of bytes written.
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, WireError> {
+        let mut writer = Writer::new(buf);
+        writer.component(self.major)?;
+        writer.component(self.minor)?;
+        writer.component(self.patch)?;
+        Ok(writer.position)
+    }
+
+    /// Reads a version from the start of `buf`, returning it and the number of bytes read.
+    pub fn decode(buf: &[u8]) -> Result<(Self, usize), WireError> {
+        let mut reader = Reader::new(buf);
+        let major = reader.component()?;
+        let minor = reader.component()?;
+        let patch = reader.component()?;
+        Ok((Version { major, minor, patch }, reader.position))
+    }
+}
+
+impl<N: VersionNumber> VersionReq<N> {
+    /// The tag and the components to write after it.
+    fn wire_parts(&self) -> (u8, [N; 3], usize, [N; 3], usize) {
+        let zero = N::zero();
+        let top = N::max() - N::one();
+
+        match self.normalized_bounds() {
+            Some((lower, upper)) => {
+                let lower_len = lower.iter().rposition(|c| *c != zero).map_or(0, |i| i + 1);
+                let upper_len = upper.iter().rposition(|c| *c != top).map_or(0, |i| i + 1);
+                let tag = lower_len as u8 | (upper_len as u8) << UPPER_LEN_SHIFT;
+                (tag, lower, lower_len, upper, upper_len)
+            }
+            None => (EMPTY, [zero; 3], 0, [zero; 3], 0),
+        }
+    }
+
+    /// Number of bytes `encode_into` writes.
+    pub fn encoded_len(&self) -> usize {
+        let (_, lower, lower_len, upper, upper_len) = self.wire_parts();
+        1 + lower[..lower_len].iter().chain(&upper[..upper_len])
+            .map(|c| varint_len(c.to_wire()))
+            .sum::<usize>()
+    }
+
+    /// Writes the compact encoding into `buf`, returning the number of bytes written.
+    ///
+    /// Requirements accepting the same versions have the same encoding.
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, WireError> {
+        let (tag, lower, lower_len, upper, upper_len) = self.wire_parts();
+
+        let mut writer = Writer::new(buf);
+        writer.byte(tag)?;
+        for component in lower[..lower_len].iter().chain(&upper[..upper_len]) {
+            writer.component(*component)?;
+        }
+        Ok(writer.position)
+    }
+
+    /// Reads a requirement from the start of `buf`, returning it and the number of bytes read.
+    pub fn decode(buf: &[u8]) -> Result<(Self, usize), WireError> {
+        let zero = N::zero();
+        let one = N::one();
+        let top = N::max() - one;
+
+        let mut reader = Reader::new(buf);
+        let tag = reader.byte()?;
+        if tag == EMPTY {
+            return Ok((VersionReq::from_bounds([zero, zero, one], [zero; 3]), reader.position));
+        }
+        if tag >> (UPPER_LEN_SHIFT + 2) != 0 {
+            return Err(reader.error(WireErrorKind::InvalidTag(tag), 0));
+        }
+
+        let lower_len = (tag & LOWER_LEN_MASK) as usize;
+        let upper_len = (tag >> UPPER_LEN_SHIFT) as usize;
+
+        let mut lower = [zero; 3];
+        let mut upper = [top; 3];
+        for (bound, len, left_out) in [(&mut lower, lower_len, zero), (&mut upper, upper_len, top)] {
+            let mut last_start = reader.position;
+            for component in bound[..len].iter_mut() {
+                last_start = reader.position;
+                *component = reader.component()?;
+            }
+            // The last component would have been left out by the encoder.
+            if len > 0 && bound[len - 1] == left_out {
+                return Err(reader.error(WireErrorKind::NonCanonical, last_start));
+            }
+        }
+
+        if lower > upper {
+            return Err(reader.error(WireErrorKind::NonCanonical, reader.position));
+        }
+        Ok((VersionReq::from_bounds(lower, upper), reader.position))
+    }
+}
+
+#[cfg(test)]
+mod wire_tests {
+    use rand::{thread_rng, Rng};
+
+    use super::{WireError, WireErrorKind};
+    use crate::offset::OffsetU8;
+    use crate::{Version, VersionReq};
+
+    fn ver(input: &str) -> Version<u64> {
+        input.parse().unwrap()
+    }
+
+    fn req(input: &str) -> VersionReq<u64> {
+        input.parse().unwrap()
+    }
+
+    fn encode_req<N: crate::version::VersionNumber>(r: &VersionReq<N>) -> ([u8; 64], usize) {
+        let mut buf = [0; 64];
+        let len = r.encode_into(&mut buf).unwrap();
+        assert_eq!(len, r.encoded_len());
+        (buf, len)
+    }
+
+    #[test]
+    fn version_test() {
+        let mut buf = [0; 32];
+        assert_eq!(ver("1.2.3").encode_into(&mut buf), Ok(3));
+        assert_eq!(buf[..3], [1, 2, 3]);
+        assert_eq!(Version::<u64>::decode(&buf[..3]), Ok((ver("1.2.3"), 3)));
+
+        let len = ver("300.0.128").encode_into(&mut buf).unwrap();
+        assert_eq!(buf[..len], [0xac, 0x02, 0x00, 0x80, 0x01]);
+        assert_eq!(Version::<u64>::decode(&buf[..len]), Ok((ver("300.0.128"), 5)));
+
+        let big = Version::<u64>::new(u64::MAX - 1, 0, 0).unwrap();
+        let len = big.encode_into(&mut buf).unwrap();
+        assert_eq!(len, big.encoded_len());
+        assert_eq!(Version::<u64>::decode(&buf[..len]), Ok((big, len)));
+
+        let widest = Version::<u128>::new(1, 2, u128::MAX - 1).unwrap();
+        let len = widest.encode_into(&mut buf).unwrap();
+        assert_eq!(len, 2 + 19);
+        assert_eq!(Version::<u128>::decode(&buf[..len]), Ok((widest, len)));
+        assert!(Version::<i128>::decode(&buf[..len]).is_err());
+
+        assert_eq!(Version::<OffsetU8>::decode(&[1, 2, 3]), Ok(("1.2.3".parse().unwrap(), 3)));
+        assert!(Version::<OffsetU8>::decode(&[1, 2, 0xfe, 0x01]).is_err());
+    }
+
+    #[test]
+    fn version_req_test() {
+        let (buf, len) = encode_req(&req("^1.2"));
+        assert_eq!(buf[..len], [0b0110, 1, 2, 1]);
+
+        for (input, expected_len) in [("*", 1), (">=1.2.3", 4), ("=1.2.3", 7), ("<2", 2), ("1.2.*", 5)] {
+            let (buf, len) = encode_req(&req(input));
+            assert_eq!(len, expected_len, "{}", input);
+            let (decoded, read) = VersionReq::<u64>::decode(&buf[..len]).unwrap();
+            assert_eq!(read, len);
+            assert_eq!(decoded, req(input), "{}", input);
+        }
+
+        let empty = VersionReq::<u64>::from_bounds([3, 0, 0], [0; 3]);
+        let (buf, len) = encode_req(&empty);
+        assert_eq!(buf[..len], [0b1_0000]);
+        assert_eq!(VersionReq::<u64>::decode(&buf[..len]), Ok((empty, 1)));
+
+        assert_eq!(encode_req(&req("^1.2")).1, encode_req(&req(">=1.2, <2")).1);
+        assert_eq!(encode_req(&VersionReq::<u8>::parse("^1.2").unwrap()).0, encode_req(&req("^1.2")).0);
+    }
+
+    fn error<T>(kind: WireErrorKind, position: usize) -> Result<T, WireError> {
+        Err(WireError { kind, position })
+    }
+
+    #[test]
+    fn error_test() {
+
+        let mut small = [0; 2];
+        assert_eq!(ver("1.2.3").encode_into(&mut small), error(WireErrorKind::BufferTooSmall, 2));
+        assert_eq!(req("^1.2").encode_into(&mut small), error(WireErrorKind::BufferTooSmall, 2));
+
+        assert_eq!(Version::<u64>::decode(&[1, 2]), error(WireErrorKind::UnexpectedEnd, 2));
+        assert_eq!(Version::<u64>::decode(&[1, 0x82, 0x00, 3]), error(WireErrorKind::NonCanonical, 1));
+        assert_eq!(Version::<u8>::decode(&[1, 0xff, 0x01, 3]), error(WireErrorKind::InvalidComponent, 1));
+        assert_eq!(Version::<u8>::decode(&[1, 2, 0x80, 0x02]), error(WireErrorKind::InvalidComponent, 2));
+        assert_eq!(Version::<u64>::decode(&[0xff; 20]), error(WireErrorKind::Overflow, 0));
+
+        assert_eq!(VersionReq::<u64>::decode(&[0b10_0000]), error(WireErrorKind::InvalidTag(0b10_0000), 0));
+        assert_eq!(VersionReq::<u64>::decode(&[0b0010, 1, 0]), error(WireErrorKind::NonCanonical, 2));
+        assert_eq!(VersionReq::<u8>::decode(&[0b0100, 0xfe, 0x01]), error(WireErrorKind::NonCanonical, 1));
+        assert_eq!(VersionReq::<u64>::decode(&[0b0101, 2, 1]), error(WireErrorKind::NonCanonical, 3));
+    }
+
+    /// Every requirement survives the round trip, and any byte string that decodes re-encodes to
+    /// exactly the bytes that were read.
+    #[test]
+    fn fuzz_test() {
+        let mut rng = thread_rng();
+        for _ in 0..20000 {
+            let len = rng.gen_range(0..12);
+            let mut input = [0_u8; 12];
+            for byte in input[..len].iter_mut() {
+                *byte = match rng.gen_range(0..4) {
+                    0 => rng.gen(),
+                    1 => rng.gen_range(0..4),
+                    2 => 0b0101 + rng.gen_range(0..11),
+                    _ => 0xff,
+                };
+            }
+            let input = &input[..len];
+
+            if let Ok((decoded, read)) = VersionReq::<u8>::decode(input) {
+                let (buf, written) = encode_req(&decoded);
+                assert_eq!(buf[..written], input[..read], "{:?} decoded as {}", input, decoded);
+            }
+            if let Ok((decoded, read)) = Version::<i16>::decode(input) {
+                let mut buf = [0; 16];
+                let written = decoded.encode_into(&mut buf).unwrap();
+                assert_eq!(buf[..written], input[..read], "{:?} decoded as {}", input, decoded);
+            }
+        }
+
+        let grid = [0, 1, 2, 127, 128, 300, u64::MAX - 2];
+        for _ in 0..5000 {
+            let mut component = || grid[rng.gen_range(0..grid.len())];
+            let lower = [component(), component(), component()];
+            let upper = [component(), component(), component()];
+            let r = VersionReq::<u64>::from_bounds(lower, upper);
+            let (buf, len) = encode_req(&r);
+            assert_eq!(VersionReq::<u64>::decode(&buf[..len]), Ok((r, len)), "{}", r);
+        }
+    }
+}
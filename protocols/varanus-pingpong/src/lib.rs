@@ -7,22 +7,17 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
-use fast_version::version_req::{VersionRegCompType, VersionRegType};
+use fast_version::{version, version_req};
 use varanus_core::node::NodeState;
 use varanus_core::protocol::{DefaultVersionNumber, GenericProtocol, Version, VersionReq};
 use varanus_core::protocol::name::ProtocolName;
 use varanus_core::protocol::request::{FastRequestProtocol, RequestProtocol, RequestType, ResponseType};
 use varanus_core::transport::TransportProtocol;
 
+const PING_PONG_VERSION: Version<DefaultVersionNumber> = version!("1.1.1");
+const PING_PONG_VERSION_REQ: VersionReq<DefaultVersionNumber> = version_req!("=1.1.1");
+
 lazy_static::lazy_static! {
-	static ref PING_PONG_VERSION: Version<DefaultVersionNumber> = {
-		Version::new(1, 1, 1).unwrap()
-	};
-	static ref PING_PONG_VERSION_REQ: VersionReq<DefaultVersionNumber> = {
-		let init_version = Version::new(1, 1, 1).unwrap();
-		let type_version_req = VersionRegType::Strict(init_version);
-		VersionReq::try_from(VersionRegCompType::Pure(type_version_req)).unwrap()
-	};
 	static ref PING_PONG_PROTOCOL_NAME: ProtocolName = {
 		ProtocolName::new("PingPong".to_string()).unwrap()
 	};
@@ -119,13 +114,19 @@ impl PingPongProtocol {
 	}
 }
 
+impl Default for PingPongProtocol {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl GenericProtocol for PingPongProtocol {
 	fn version() -> Version<varanus_core::protocol::DefaultVersionNumber> {
-		*PING_PONG_VERSION
+		PING_PONG_VERSION
 	}
 
 	fn version_req() -> VersionReq<varanus_core::protocol::DefaultVersionNumber> {
-		*PING_PONG_VERSION_REQ
+		PING_PONG_VERSION_REQ
 	}
 
 	fn name() -> ProtocolName {
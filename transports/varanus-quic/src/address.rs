@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::str::FromStr;
+use varanus_core::transport::address::{GenericAddress, TransportIdentifier};
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct QuicIdentifier;
+
+impl TransportIdentifier for QuicIdentifier {
+	fn new() -> Self {
+		QuicIdentifier
+	}
+
+	fn string() -> String {
+		"quic".to_string()
+	}
+
+	fn from_string(_: &str) -> Self {
+		QuicIdentifier
+	}
+}
+
+/// The UDP socket address of a QUIC endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuicAddress {
+	internal: SocketAddr,
+}
+
+impl QuicAddress {
+	pub fn new(internal: SocketAddr) -> Self {
+		Self {
+			internal
+		}
+	}
+
+	pub fn v4(ip: Ipv4Addr, port: u16) -> Self {
+		Self::new(SocketAddr::V4(SocketAddrV4::new(ip, port)))
+	}
+
+	pub fn v6(ip: Ipv6Addr, port: u16) -> Self {
+		Self::new(SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0)))
+	}
+
+	pub fn socket_address(&self) -> SocketAddr {
+		self.internal
+	}
+
+	pub fn port(&self) -> u16 {
+		self.internal.port()
+	}
+
+	pub fn is_ipv6(&self) -> bool {
+		self.internal.is_ipv6()
+	}
+}
+
+impl From<SocketAddr> for QuicAddress {
+	fn from(internal: SocketAddr) -> Self {
+		Self::new(internal)
+	}
+}
+
+impl From<QuicAddress> for SocketAddr {
+	fn from(address: QuicAddress) -> Self {
+		address.internal
+	}
+}
+
+impl Display for QuicAddress {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		Display::fmt(&self.internal, f)
+	}
+}
+
+impl FromStr for QuicAddress {
+	type Err = std::net::AddrParseError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.parse().map(Self::new)
+	}
+}
+
+impl GenericAddress for QuicAddress {
+	type Associated = QuicIdentifier;
+
+	fn transport_identifier() -> Self::Associated {
+		QuicIdentifier
+	}
+}
@@ -0,0 +1,81 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use quinn::{RecvStream, SendStream, StreamId};
+use quinn::rustls::pki_types::CertificateDer;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use varanus_core::encryption::transport::SecureTransportConnection;
+use varanus_core::transport::connection::TransportConnection;
+use crate::address::QuicAddress;
+
+/// A bidirectional stream of a QUIC connection.
+pub struct QuicConnection {
+	connection: quinn::Connection,
+	send: SendStream,
+	recv: RecvStream,
+	local: Option<QuicAddress>,
+}
+
+impl QuicConnection {
+	pub(crate) fn new(connection: quinn::Connection, send: SendStream, recv: RecvStream, local: Option<QuicAddress>) -> Self {
+		Self {
+			connection,
+			send,
+			recv,
+			local
+		}
+	}
+
+	/// The QUIC connection this stream belongs to, shared with the other streams to the same node.
+	pub fn connection(&self) -> &quinn::Connection {
+		&self.connection
+	}
+
+	pub fn stream_id(&self) -> StreamId {
+		self.send.id()
+	}
+}
+
+impl AsyncRead for QuicConnection {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+		AsyncRead::poll_read(Pin::new(&mut self.recv), cx, buf)
+	}
+}
+
+impl AsyncWrite for QuicConnection {
+	fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		AsyncWrite::poll_write(Pin::new(&mut self.send), cx, buf)
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		AsyncWrite::poll_flush(Pin::new(&mut self.send), cx)
+	}
+
+	fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		AsyncWrite::poll_shutdown(Pin::new(&mut self.send), cx)
+	}
+}
+
+impl TransportConnection for QuicConnection {
+	type TransportAddress = QuicAddress;
+
+	/// For dialed streams this is the dialing endpoint, which is bound to the unspecified address:
+	/// the port is the one the peer sees, the IP is `0.0.0.0` or `::`.
+	fn local_address(&mut self) -> Option<Self::TransportAddress> {
+		self.local
+	}
+
+	fn remote_address(&mut self) -> Option<Self::TransportAddress> {
+		Some(QuicAddress::new(self.connection.remote_address()))
+	}
+}
+
+impl SecureTransportConnection for QuicConnection {
+	/// The certificate chain the peer presented, leaf first.
+	type Identity = Vec<CertificateDer<'static>>;
+
+	fn peer_identity(&self) -> Option<Self::Identity> {
+		let identity = self.connection.peer_identity()?;
+		identity.downcast::<Vec<CertificateDer<'static>>>().ok().map(|d| *d)
+	}
+}
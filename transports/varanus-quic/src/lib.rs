@@ -0,0 +1,462 @@
+//! QUIC over UDP, on quinn.
+//!
+//! Every bidirectional QUIC stream is a connection of its own: dials to the same address share one
+//! QUIC connection and only open a new stream on it, so requests don't block each other the way
+//! they do on a single TCP connection. The TLS handshake of QUIC authenticates both nodes, which
+//! is why [`QuicConnection`] is a [`SecureTransportConnection`] and takes
+//! [`TransportEncryption`] in place of a separate
+//! [`EncryptionProtocol`](varanus_core::encryption::EncryptionProtocol).
+//!
+//! [`SecureTransportConnection`]: varanus_core::encryption::transport::SecureTransportConnection
+//! [`TransportEncryption`]: varanus_core::encryption::transport::TransportEncryption
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use fast_version::{version, version_req};
+use quinn::{ClientConfig, ConnectError, ConnectionError, Endpoint, ServerConfig};
+use quinn::crypto::rustls::{NoInitialCipherSuite, QuicClientConfig, QuicServerConfig};
+use quinn::rustls;
+use quinn::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use quinn::rustls::server::{VerifierBuilderError, WebPkiClientVerifier};
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use varanus_core::protocol::{DefaultVersionNumber, GenericProtocol, Version, VersionReq};
+use varanus_core::protocol::name::ProtocolName;
+use varanus_core::transport::{BoxedDial, TransportProtocol};
+
+pub use self::address::{QuicAddress, QuicIdentifier};
+pub use self::connection::QuicConnection;
+pub use self::listener::QuicListener;
+
+pub mod address;
+pub mod connection;
+pub mod listener;
+
+const QUIC_VERSION: Version<DefaultVersionNumber> = version!("0.1.0");
+const QUIC_VERSION_REQ: VersionReq<DefaultVersionNumber> = version_req!("^0.1");
+
+/// The ALPN protocol both sides have to agree on.
+pub const ALPN_PROTOCOL: &[u8] = b"varanus";
+
+/// Written by the dialing side first: quinn only tells the peer about a stream once data was
+/// sent on it, and listeners shouldn't have to wait for the first request.
+pub(crate) const STREAM_OPEN: u8 = 0;
+
+lazy_static::lazy_static! {
+	static ref QUIC_PROTOCOL_NAME: ProtocolName = {
+		ProtocolName::new("QuicTransport".to_string()).unwrap()
+	};
+}
+
+#[derive(Error, Debug)]
+pub enum QuicError {
+	#[error("invalid TLS configuration")]
+	Tls(#[from] rustls::Error),
+	#[error("invalid trusted certificates")]
+	Verifier(#[from] VerifierBuilderError),
+	#[error("TLS configuration has no cipher suite usable by QUIC")]
+	CipherSuite(#[from] NoInitialCipherSuite),
+	#[error("failed to bind a UDP socket on `{0}`")]
+	Bind(QuicAddress, #[source] io::Error),
+	#[error("failed to connect to `{0}`")]
+	Connect(QuicAddress, #[source] ConnectError),
+	#[error("connection with `{0}` failed")]
+	Connection(QuicAddress, #[source] ConnectionError),
+	#[error("failed to open a stream with `{0}`")]
+	Stream(QuicAddress, #[source] io::Error),
+}
+
+/// The certificate chain and private key a node proves its identity with.
+pub struct QuicIdentity {
+	certificate_chain: Vec<CertificateDer<'static>>,
+	private_key: PrivateKeyDer<'static>,
+}
+
+impl QuicIdentity {
+	pub fn new(certificate_chain: Vec<CertificateDer<'static>>, private_key: PrivateKeyDer<'static>) -> Self {
+		Self {
+			certificate_chain,
+			private_key
+		}
+	}
+}
+
+pub struct QuicConfig {
+	identity: QuicIdentity,
+	trusted: Vec<CertificateDer<'static>>,
+	server_name: String,
+}
+
+impl QuicConfig {
+	/// Connections are mutually authenticated: both sides present `identity` and only accept
+	/// identities issued by one of the `trusted` certificates.
+	pub fn new(identity: QuicIdentity, trusted: Vec<CertificateDer<'static>>) -> Self {
+		Self {
+			identity,
+			trusted,
+			server_name: "varanus".to_string(),
+		}
+	}
+
+	/// The name the certificates of dialed nodes have to be issued for, `varanus` by default.
+	pub fn server_name<S: Into<String>>(mut self, server_name: S) -> Self {
+		self.server_name = server_name.into();
+		self
+	}
+}
+
+/// A connection to another node and the local address it was dialed from.
+type Dialed = (quinn::Connection, Option<QuicAddress>);
+
+/// Connections by remote address, set once the handshake is done.
+type Connections = Mutex<HashMap<SocketAddr, Arc<OnceCell<Dialed>>>>;
+
+struct QuicState {
+	server: ServerConfig,
+	client: ClientConfig,
+	server_name: String,
+	/// Dialing endpoints for IPv4 and IPv6, bound to the unspecified address on first use.
+	endpoints: Mutex<[Option<Endpoint>; 2]>,
+	/// Shared with the tasks that drop entries once their connection is closed.
+	connections: Arc<Connections>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+	mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Removes the entry of `address` if it is still `cell`, and not a newer connection.
+fn forget(connections: &Connections, address: SocketAddr, cell: &Arc<OnceCell<Dialed>>) {
+	let mut connections = lock(connections);
+	if connections.get(&address).is_some_and(|d| Arc::ptr_eq(d, cell)) {
+		connections.remove(&address);
+	}
+}
+
+impl QuicState {
+	fn endpoint(&self, remote: QuicAddress) -> Result<Endpoint, QuicError> {
+		let mut endpoints = lock(&self.endpoints);
+		let slot = &mut endpoints[remote.is_ipv6() as usize];
+		if let Some(endpoint) = slot {
+			return Ok(endpoint.clone());
+		}
+		let local = match remote.is_ipv6() {
+			true => QuicAddress::v6(Ipv6Addr::UNSPECIFIED, 0),
+			false => QuicAddress::v4(Ipv4Addr::UNSPECIFIED, 0),
+		};
+		let mut endpoint = Endpoint::client(local.socket_address()).map_err(|e| QuicError::Bind(local, e))?;
+		endpoint.set_default_client_config(self.client.clone());
+		*slot = Some(endpoint.clone());
+		Ok(endpoint)
+	}
+
+	/// An open connection to `address`, or a new one.
+	async fn connection(&self, address: QuicAddress) -> Result<Dialed, QuicError> {
+		let cell = {
+			let mut connections = lock(&self.connections);
+			let cell = connections.entry(address.socket_address()).or_default();
+			if cell.get().is_some_and(|(connection, _)| connection.close_reason().is_some()) {
+				*cell = Arc::default();
+			}
+			cell.clone()
+		};
+		// Concurrent dials wait for the same handshake.
+		let dialed = cell.get_or_try_init(|| async {
+			let dialed = self.connect(address).await?;
+			let connections = self.connections.clone();
+			let (connection, cell) = (dialed.0.clone(), cell.clone());
+			tokio::spawn(async move {
+				connection.closed().await;
+				forget(&connections, address.socket_address(), &cell);
+			});
+			Ok(dialed)
+		}).await;
+		if dialed.is_err() {
+			forget(&self.connections, address.socket_address(), &cell);
+		}
+		dialed.cloned()
+	}
+
+	async fn connect(&self, address: QuicAddress) -> Result<Dialed, QuicError> {
+		let endpoint = self.endpoint(address)?;
+		let local = endpoint.local_addr().ok().map(QuicAddress::new);
+		let connecting = endpoint.connect(address.socket_address(), &self.server_name).map_err(|e| QuicError::Connect(address, e))?;
+		let connection = connecting.await.map_err(|e| QuicError::Connection(address, e))?;
+		Ok((connection, local))
+	}
+
+	async fn open(&self, address: QuicAddress) -> Result<QuicConnection, QuicError> {
+		let (connection, local) = self.connection(address).await?;
+		let (mut send, recv) = connection.open_bi().await.map_err(|e| QuicError::Connection(address, e))?;
+		send.write_all(&[STREAM_OPEN]).await.map_err(|e| QuicError::Stream(address, e.into()))?;
+		Ok(QuicConnection::new(connection, send, recv, local))
+	}
+}
+
+/// QUIC over IPv4 and IPv6 UDP.
+///
+/// All dials go out through one UDP endpoint per IP version, while every listener binds an
+/// endpoint of its own and accepts in a background task that ends when the listener is dropped.
+pub struct QuicTransport {
+	state: Arc<QuicState>,
+}
+
+impl QuicTransport {
+	pub fn new(config: QuicConfig) -> Result<Self, QuicError> {
+		let provider = Arc::new(rustls::crypto::ring::default_provider());
+		let mut roots = rustls::RootCertStore::empty();
+		for certificate in config.trusted {
+			roots.add(certificate)?;
+		}
+		let roots = Arc::new(roots);
+		let QuicIdentity { certificate_chain, private_key } = config.identity;
+
+		let client_verifier = WebPkiClientVerifier::builder_with_provider(roots.clone(), provider.clone()).build()?;
+		let mut server = rustls::ServerConfig::builder_with_provider(provider.clone())
+			.with_protocol_versions(&[&rustls::version::TLS13])?
+			.with_client_cert_verifier(client_verifier)
+			.with_single_cert(certificate_chain.clone(), private_key.clone_key())?;
+		server.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+		let mut client = rustls::ClientConfig::builder_with_provider(provider)
+			.with_protocol_versions(&[&rustls::version::TLS13])?
+			.with_root_certificates(roots)
+			.with_client_auth_cert(certificate_chain, private_key)?;
+		client.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+		let state = QuicState {
+			server: ServerConfig::with_crypto(Arc::new(QuicServerConfig::try_from(server)?)),
+			client: ClientConfig::new(Arc::new(QuicClientConfig::try_from(client)?)),
+			server_name: config.server_name,
+			endpoints: Mutex::new([None, None]),
+			connections: Arc::new(Mutex::new(HashMap::new())),
+		};
+		Ok(Self {
+			state: Arc::new(state)
+		})
+	}
+}
+
+impl GenericProtocol for QuicTransport {
+	fn version() -> Version<DefaultVersionNumber> {
+		QUIC_VERSION
+	}
+
+	fn version_req() -> VersionReq<DefaultVersionNumber> {
+		QUIC_VERSION_REQ
+	}
+
+	fn name() -> ProtocolName {
+		QUIC_PROTOCOL_NAME.clone()
+	}
+}
+
+impl TransportProtocol for QuicTransport {
+	type Connection = QuicConnection;
+	type TransportIdentifier = QuicIdentifier;
+	type TransportAddress = QuicAddress;
+	type TransportError = QuicError;
+	type TransportFuture = BoxedDial<QuicConnection, QuicError>;
+	type Listener = QuicListener;
+
+	/// Opens a new stream, on the existing connection to `address` if there is one.
+	fn dial(&self, address: &Self::TransportAddress) -> Self::TransportFuture {
+		let state = self.state.clone();
+		let address = *address;
+		Box::pin(async move { state.open(address).await })
+	}
+
+	fn listen(&self, address: &Self::TransportAddress) -> Result<Self::Listener, Self::TransportError> {
+		let endpoint = Endpoint::server(self.state.server.clone(), address.socket_address()).map_err(|e| QuicError::Bind(*address, e))?;
+		Ok(QuicListener::new(endpoint))
+	}
+}
+
+#[cfg(test)]
+mod quic_tests {
+	use std::net::{Ipv4Addr, Ipv6Addr};
+	use std::time::Duration;
+	use quinn::rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+	use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa, KeyPair};
+	use tokio::time::timeout;
+	use varanus_core::encryption::{EncryptionConnection, EncryptionProtocol};
+	use varanus_core::encryption::transport::{SecureTransportConnection, TransportEncryption};
+	use varanus_core::node::NodeState;
+	use varanus_core::transport::connection::TransportConnection;
+	use varanus_core::transport::listener::TransportListener;
+	use varanus_core::transport::testing::ping_pong;
+	use varanus_core::transport::TransportProtocol;
+	use crate::{QuicAddress, QuicConfig, QuicConnection, QuicError, QuicIdentity, QuicTransport};
+
+	struct Authority {
+		certificate: Certificate,
+		key: KeyPair,
+	}
+
+	impl Authority {
+		fn new() -> Self {
+			let mut params = CertificateParams::new(Vec::new()).unwrap();
+			params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+			let key = KeyPair::generate().unwrap();
+			Self {
+				certificate: params.self_signed(&key).unwrap(),
+				key,
+			}
+		}
+
+		/// A node identity for `name` and its certificate.
+		fn issue(&self, name: &str) -> (QuicIdentity, CertificateDer<'static>) {
+			let key = KeyPair::generate().unwrap();
+			let certificate = CertificateParams::new(vec![name.to_string()]).unwrap()
+				.signed_by(&key, &self.certificate, &self.key)
+				.unwrap();
+			let private_key = PrivatePkcs8KeyDer::from(key.serialize_der()).into();
+			(QuicIdentity::new(vec![certificate.der().clone()], private_key), certificate.der().clone())
+		}
+
+		fn transport(&self) -> (QuicTransport, CertificateDer<'static>) {
+			let (identity, certificate) = self.issue("varanus");
+			let config = QuicConfig::new(identity, vec![self.certificate.der().clone()]);
+			(QuicTransport::new(config).unwrap(), certificate)
+		}
+	}
+
+	async fn loopback_test(address: QuicAddress) {
+		let authority = Authority::new();
+		let (listening, listening_certificate) = authority.transport();
+		let (dialing, dialing_certificate) = authority.transport();
+		let listening = NodeState::builder().add_default_transport(listening).build();
+		let dialing = NodeState::builder().add_default_transport(dialing).build();
+
+		let mut listener = listening.listen_default(&address).unwrap();
+		let listen_address = listener.local_address().unwrap();
+		assert_ne!(listen_address.port(), 0);
+
+		let mut first = dialing.dial_default(&listen_address).await.unwrap();
+		let mut second = dialing.dial_default(&listen_address).await.unwrap();
+		// Both streams share one connection.
+		assert_eq!(first.connection().stable_id(), second.connection().stable_id());
+		assert_ne!(first.stream_id(), second.stream_id());
+		assert_eq!(first.remote_address(), Some(listen_address));
+
+		let (mut accepted_first, remote) = listener.accept().await.unwrap().unwrap();
+		let (mut accepted_second, _) = listener.accept().await.unwrap().unwrap();
+		assert_eq!(accepted_first.remote_address(), Some(remote));
+		assert_eq!(accepted_first.local_address(), Some(listen_address));
+		// Dialing endpoints are bound to the unspecified address, so only the port matches.
+		let unspecified = match address.is_ipv6() {
+			true => QuicAddress::v6(Ipv6Addr::UNSPECIFIED, remote.port()),
+			false => QuicAddress::v4(Ipv4Addr::UNSPECIFIED, remote.port()),
+		};
+		assert_eq!(first.local_address(), Some(unspecified));
+		assert_eq!(remote, QuicAddress::new((address.socket_address().ip(), remote.port()).into()));
+
+		// Streams are independent, answer the second one first.
+		ping_pong(&mut second, &mut accepted_second).await;
+		ping_pong(&mut first, &mut accepted_first).await;
+
+		assert_eq!(first.peer_identity(), Some(vec![listening_certificate]));
+		assert_eq!(accepted_first.peer_identity(), Some(vec![dialing_certificate]));
+	}
+
+	#[tokio::test]
+	async fn loopback_v4_test() {
+		loopback_test(QuicAddress::v4(Ipv4Addr::LOCALHOST, 0)).await;
+	}
+
+	#[tokio::test]
+	async fn loopback_v6_test() {
+		loopback_test(QuicAddress::v6(Ipv6Addr::LOCALHOST, 0)).await;
+	}
+
+	#[tokio::test]
+	async fn concurrent_dial_test() {
+		let authority = Authority::new();
+		let (listening, _) = authority.transport();
+		let (dialing, _) = authority.transport();
+		let listener = listening.listen(&QuicAddress::v4(Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let address = listener.local_address().unwrap();
+
+		// Dials racing for the first connection end up on the same one.
+		let (first, second) = tokio::join!(dialing.dial(&address), dialing.dial(&address));
+		let (first, second) = (first.unwrap(), second.unwrap());
+		assert_eq!(first.connection().stable_id(), second.connection().stable_id());
+		assert_eq!(dialing.state.connections.lock().unwrap().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn cleanup_test() {
+		let authority = Authority::new();
+		let (listening, _) = authority.transport();
+		let (dialing, _) = authority.transport();
+		let mut listener = listening.listen(&QuicAddress::v4(Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let address = listener.local_address().unwrap();
+
+		// Failed handshakes aren't kept.
+		let (untrusted, _) = Authority::new().transport();
+		assert!(untrusted.dial(&address).await.is_err());
+		assert!(untrusted.state.connections.lock().unwrap().is_empty());
+		assert!(listener.accept().await.unwrap().is_err());
+
+		// A dropped listener stops waiting for streams, so the connection closes with the last
+		// accepted one and is forgotten by the dialing side.
+		let dialer = dialing.dial(&address).await.unwrap();
+		let (accepted, _) = listener.accept().await.unwrap().unwrap();
+		drop(accepted);
+		drop(listener);
+		timeout(Duration::from_secs(5), dialer.connection().closed()).await.unwrap();
+		timeout(Duration::from_secs(5), async {
+			while !dialing.state.connections.lock().unwrap().is_empty() {
+				tokio::task::yield_now().await;
+			}
+		}).await.unwrap();
+	}
+
+	fn secured<E: EncryptionProtocol<QuicConnection>>(_: &E, connection: QuicConnection) -> E::Connection {
+		E::Connection::new(connection)
+	}
+
+	#[tokio::test]
+	async fn transport_encryption_test() {
+		let authority = Authority::new();
+		let (listening, _) = authority.transport();
+		let (dialing, _) = authority.transport();
+		let state = NodeState::builder().add_default_transport(listening).add_generic_transport(dialing).build();
+
+		let mut listener = state.listen_default(&QuicAddress::v4(Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let address = listener.local_address().unwrap();
+		let dialer = state.dial_generic::<QuicTransport>(&address).unwrap().await.unwrap();
+		let (accepted, _) = listener.accept().await.unwrap().unwrap();
+
+		let mut dialer = secured(&TransportEncryption, dialer);
+		let mut accepted = secured(&TransportEncryption, accepted);
+		ping_pong(&mut dialer, &mut accepted).await;
+	}
+
+	#[tokio::test]
+	async fn untrusted_test() {
+		let (listening, _) = Authority::new().transport();
+		let (dialing, _) = Authority::new().transport();
+		let listening = NodeState::builder().add_default_transport(listening).build();
+		let dialing = NodeState::builder().add_default_transport(dialing).build();
+
+		let mut listener = listening.listen_default(&QuicAddress::v4(Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let address = listener.local_address().unwrap();
+		let error = dialing.dial_default(&address).await.err().unwrap();
+		assert!(matches!(error, QuicError::Connection(a, _) if a == address));
+		assert!(matches!(listener.accept().await, Some(Err(QuicError::Connection(..)))));
+
+		// The certificate has to be issued for the dialed name.
+		let authority = Authority::new();
+		let (listening, _) = authority.transport();
+		let (identity, _) = authority.issue("varanus");
+		let dialing = QuicTransport::new(QuicConfig::new(identity, vec![authority.certificate.der().clone()]).server_name("other")).unwrap();
+		let state = NodeState::builder().add_default_transport(listening).build();
+		let listener = state.listen_default(&QuicAddress::v4(Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let address = listener.local_address().unwrap();
+		let dialing = NodeState::builder().add_default_transport(dialing).build();
+		assert!(matches!(dialing.dial_default(&address).await, Err(QuicError::Connection(..))));
+	}
+}
@@ -0,0 +1,100 @@
+use std::io;
+use std::task::{Context, Poll};
+use quinn::{Endpoint, Incoming};
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use varanus_core::transport::listener::{AcceptResult, TransportListener};
+use crate::{QuicError, STREAM_OPEN};
+use crate::address::QuicAddress;
+use crate::connection::QuicConnection;
+
+type AcceptSender = UnboundedSender<Result<QuicConnection, QuicError>>;
+
+/// Yields the streams that dialing nodes open, across all of their connections.
+///
+/// Dropping the listener refuses new connections, streams that were already accepted stay open.
+pub struct QuicListener {
+	endpoint: Endpoint,
+	receiver: UnboundedReceiver<Result<QuicConnection, QuicError>>,
+	task: JoinHandle<()>,
+}
+
+impl QuicListener {
+	pub(crate) fn new(endpoint: Endpoint) -> Self {
+		let (sender, receiver) = unbounded_channel();
+		let task = tokio::spawn(accept_connections(endpoint.clone(), sender));
+		Self {
+			endpoint,
+			receiver,
+			task
+		}
+	}
+}
+
+async fn accept_connections(endpoint: Endpoint, sender: AcceptSender) {
+	let local = endpoint.local_addr().ok().map(QuicAddress::new);
+	while let Some(incoming) = endpoint.accept().await {
+		tokio::spawn(accept_streams(incoming, local, sender.clone()));
+	}
+}
+
+async fn accept_streams(incoming: Incoming, local: Option<QuicAddress>, sender: AcceptSender) {
+	let remote = QuicAddress::new(incoming.remote_address());
+	let connection = match incoming.await {
+		Ok(d) => d,
+		Err(e) => {
+			let _ = sender.send(Err(QuicError::Connection(remote, e)));
+			return;
+		}
+	};
+	loop {
+		// Stop taking streams of the peer once the listener is dropped, the connection closes with
+		// the last stream that was accepted.
+		let (send, mut recv) = tokio::select! {
+			accepted = connection.accept_bi() => match accepted {
+				Ok(d) => d,
+				Err(_) => return,
+			},
+			_ = sender.closed() => return,
+		};
+		let connection = connection.clone();
+		let sender = sender.clone();
+		// Read the marker in a task of its own, so a peer that stalls doesn't hold up other streams.
+		tokio::spawn(async move {
+			let accepted = match recv.read_u8().await {
+				Ok(STREAM_OPEN) => Ok(QuicConnection::new(connection, send, recv, local)),
+				Ok(_) => Err(QuicError::Stream(remote, io::Error::new(io::ErrorKind::InvalidData, "stream doesn't start with the open marker"))),
+				Err(e) => Err(QuicError::Stream(remote, e)),
+			};
+			let _ = sender.send(accepted);
+		});
+	}
+}
+
+impl TransportListener for QuicListener {
+	type Connection = QuicConnection;
+	type TransportError = QuicError;
+
+	fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<AcceptResult<Self>> {
+		self.receiver.poll_recv(cx).map(|accepted| {
+			accepted.map(|result| {
+				result.map(|connection| {
+					let remote = QuicAddress::new(connection.connection().remote_address());
+					(connection, remote)
+				})
+			})
+		})
+	}
+
+	fn local_address(&self) -> Option<QuicAddress> {
+		self.endpoint.local_addr().ok().map(QuicAddress::new)
+	}
+}
+
+impl Drop for QuicListener {
+	fn drop(&mut self) {
+		self.task.abort();
+		self.endpoint.set_server_config(None);
+	}
+}
@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::str::FromStr;
+use varanus_core::transport::address::{GenericAddress, TransportIdentifier};
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct TcpIdentifier;
+
+impl TransportIdentifier for TcpIdentifier {
+	fn new() -> Self {
+		TcpIdentifier
+	}
+
+	fn string() -> String {
+		"tcp".to_string()
+	}
+
+	fn from_string(_: &str) -> Self {
+		TcpIdentifier
+	}
+}
+
+/// An IPv4 or IPv6 socket address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TcpAddress {
+	internal: SocketAddr,
+}
+
+impl TcpAddress {
+	pub fn new(internal: SocketAddr) -> Self {
+		Self {
+			internal
+		}
+	}
+
+	pub fn v4(ip: Ipv4Addr, port: u16) -> Self {
+		Self::new(SocketAddr::V4(SocketAddrV4::new(ip, port)))
+	}
+
+	pub fn v6(ip: Ipv6Addr, port: u16) -> Self {
+		Self::new(SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0)))
+	}
+
+	pub fn socket_address(&self) -> SocketAddr {
+		self.internal
+	}
+
+	pub fn port(&self) -> u16 {
+		self.internal.port()
+	}
+
+	pub fn is_ipv6(&self) -> bool {
+		self.internal.is_ipv6()
+	}
+}
+
+impl From<SocketAddr> for TcpAddress {
+	fn from(internal: SocketAddr) -> Self {
+		Self::new(internal)
+	}
+}
+
+impl From<TcpAddress> for SocketAddr {
+	fn from(address: TcpAddress) -> Self {
+		address.internal
+	}
+}
+
+impl Display for TcpAddress {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		Display::fmt(&self.internal, f)
+	}
+}
+
+impl FromStr for TcpAddress {
+	type Err = std::net::AddrParseError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.parse().map(Self::new)
+	}
+}
+
+impl GenericAddress for TcpAddress {
+	type Associated = TcpIdentifier;
+
+	fn transport_identifier() -> Self::Associated {
+		TcpIdentifier
+	}
+}
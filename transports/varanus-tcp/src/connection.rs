@@ -0,0 +1,67 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use varanus_core::transport::connection::TransportConnection;
+use crate::address::TcpAddress;
+
+pub struct TcpConnection {
+	stream: TcpStream,
+}
+
+impl TcpConnection {
+	pub(crate) fn new(stream: TcpStream) -> Self {
+		Self {
+			stream
+		}
+	}
+
+	pub fn stream(&self) -> &TcpStream {
+		&self.stream
+	}
+
+	pub fn into_stream(self) -> TcpStream {
+		self.stream
+	}
+}
+
+impl AsyncRead for TcpConnection {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_read(cx, buf)
+	}
+}
+
+impl AsyncWrite for TcpConnection {
+	fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.stream).poll_write(cx, buf)
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_flush(cx)
+	}
+
+	fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_shutdown(cx)
+	}
+
+	fn poll_write_vectored(mut self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[io::IoSlice<'_>]) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+	}
+
+	fn is_write_vectored(&self) -> bool {
+		self.stream.is_write_vectored()
+	}
+}
+
+impl TransportConnection for TcpConnection {
+	type TransportAddress = TcpAddress;
+
+	fn local_address(&mut self) -> Option<Self::TransportAddress> {
+		self.stream.local_addr().ok().map(TcpAddress::new)
+	}
+
+	fn remote_address(&mut self) -> Option<Self::TransportAddress> {
+		self.stream.peer_addr().ok().map(TcpAddress::new)
+	}
+}
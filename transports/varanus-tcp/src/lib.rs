@@ -0,0 +1,231 @@
+use std::io;
+use std::time::Duration;
+use fast_version::{version, version_req};
+use socket2::{Domain, Protocol, SockRef, Socket, TcpKeepalive, Type};
+use thiserror::Error;
+use tokio::net::TcpStream;
+use varanus_core::protocol::{DefaultVersionNumber, GenericProtocol, Version, VersionReq};
+use varanus_core::protocol::name::ProtocolName;
+use varanus_core::transport::{BoxedDial, TransportProtocol};
+
+pub use self::address::{TcpAddress, TcpIdentifier};
+pub use self::connection::TcpConnection;
+pub use self::listener::TcpListener;
+
+pub mod address;
+pub mod connection;
+pub mod listener;
+
+const TCP_VERSION: Version<DefaultVersionNumber> = version!("0.1.0");
+const TCP_VERSION_REQ: VersionReq<DefaultVersionNumber> = version_req!("^0.1");
+
+lazy_static::lazy_static! {
+	static ref TCP_PROTOCOL_NAME: ProtocolName = {
+		ProtocolName::new("TcpTransport".to_string()).unwrap()
+	};
+}
+
+#[derive(Error, Debug)]
+pub enum TcpError {
+	#[error("failed to connect to `{0}`")]
+	Dial(TcpAddress, #[source] io::Error),
+	#[error("failed to listen on `{0}`")]
+	Listen(TcpAddress, #[source] io::Error),
+	#[error("failed to accept an inbound connection")]
+	Accept(#[source] io::Error),
+	#[error("failed to configure the socket of a connection")]
+	Configure(#[source] io::Error),
+}
+
+/// Socket options applied to every dialed and accepted connection.
+#[derive(Clone, Copy, Debug)]
+pub struct TcpConfig {
+	nodelay: bool,
+	keepalive: Option<Duration>,
+	backlog: u32,
+}
+
+impl TcpConfig {
+	pub fn new() -> Self {
+		Self {
+			nodelay: true,
+			keepalive: None,
+			backlog: 1024,
+		}
+	}
+
+	/// Sets `TCP_NODELAY`, enabled by default since requests are usually small.
+	pub fn nodelay(mut self, nodelay: bool) -> Self {
+		self.nodelay = nodelay;
+		self
+	}
+
+	/// Enables `SO_KEEPALIVE` with the given idle time before the first probe, disabled by default.
+	pub fn keepalive(mut self, keepalive: Option<Duration>) -> Self {
+		self.keepalive = keepalive;
+		self
+	}
+
+	/// The length of the queue of pending inbound connections.
+	pub fn backlog(mut self, backlog: u32) -> Self {
+		self.backlog = backlog;
+		self
+	}
+
+	pub(crate) fn apply(&self, stream: &TcpStream) -> Result<(), TcpError> {
+		stream.set_nodelay(self.nodelay).map_err(TcpError::Configure)?;
+		if let Some(time) = self.keepalive {
+			let keepalive = TcpKeepalive::new().with_time(time);
+			SockRef::from(stream).set_tcp_keepalive(&keepalive).map_err(TcpError::Configure)?;
+		}
+		Ok(())
+	}
+}
+
+impl Default for TcpConfig {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// TCP over IPv4 and IPv6. Dialed and accepted sockets both get the options of the [`TcpConfig`]
+/// of the transport they went through.
+pub struct TcpTransport {
+	config: TcpConfig,
+}
+
+impl TcpTransport {
+	pub fn new(config: TcpConfig) -> Self {
+		Self {
+			config
+		}
+	}
+
+	pub fn config(&self) -> &TcpConfig {
+		&self.config
+	}
+}
+
+impl Default for TcpTransport {
+	fn default() -> Self {
+		Self::new(TcpConfig::default())
+	}
+}
+
+impl GenericProtocol for TcpTransport {
+	fn version() -> Version<DefaultVersionNumber> {
+		TCP_VERSION
+	}
+
+	fn version_req() -> VersionReq<DefaultVersionNumber> {
+		TCP_VERSION_REQ
+	}
+
+	fn name() -> ProtocolName {
+		TCP_PROTOCOL_NAME.clone()
+	}
+}
+
+impl TransportProtocol for TcpTransport {
+	type Connection = TcpConnection;
+	type TransportIdentifier = TcpIdentifier;
+	type TransportAddress = TcpAddress;
+	type TransportError = TcpError;
+	type TransportFuture = BoxedDial<TcpConnection, TcpError>;
+	type Listener = TcpListener;
+
+	fn dial(&self, address: &Self::TransportAddress) -> Self::TransportFuture {
+		let address = *address;
+		let config = self.config;
+		Box::pin(async move {
+			let stream = TcpStream::connect(address.socket_address()).await.map_err(|e| TcpError::Dial(address, e))?;
+			config.apply(&stream)?;
+			Ok(TcpConnection::new(stream))
+		})
+	}
+
+	fn listen(&self, address: &Self::TransportAddress) -> Result<Self::Listener, Self::TransportError> {
+		let listen_error = |e| TcpError::Listen(*address, e);
+		let socket_address = address.socket_address();
+		let socket = Socket::new(Domain::for_address(socket_address), Type::STREAM, Some(Protocol::TCP)).map_err(listen_error)?;
+		// Lets a restarted node bind again while old connections are in `TIME_WAIT`.
+		#[cfg(unix)]
+		socket.set_reuse_address(true).map_err(listen_error)?;
+		socket.set_nonblocking(true).map_err(listen_error)?;
+		socket.bind(&socket_address.into()).map_err(listen_error)?;
+		let backlog = self.config.backlog.try_into().unwrap_or(i32::MAX);
+		socket.listen(backlog).map_err(listen_error)?;
+		let listener = tokio::net::TcpListener::from_std(socket.into()).map_err(listen_error)?;
+		Ok(TcpListener::new(listener, self.config))
+	}
+}
+
+#[cfg(test)]
+mod tcp_tests {
+	use std::net::{Ipv4Addr, Ipv6Addr};
+	use std::time::Duration;
+	use socket2::SockRef;
+	use varanus_core::node::NodeState;
+	use varanus_core::transport::connection::TransportConnection;
+	use varanus_core::transport::listener::TransportListener;
+	use varanus_core::transport::testing::{connect, ping_pong};
+	use varanus_core::transport::TransportProtocol;
+	use crate::{TcpAddress, TcpConfig, TcpError, TcpTransport};
+
+	async fn loopback_test(address: TcpAddress) {
+		let state = NodeState::builder().add_default_transport(TcpTransport::default()).build();
+		let (listener, mut dialer, mut accepted) = connect(&state, &address).await;
+		let listen_address = listener.local_address().unwrap();
+		assert_eq!(listen_address.socket_address().ip(), address.socket_address().ip());
+		assert_ne!(listen_address.port(), 0);
+
+		let remote = accepted.remote_address().unwrap();
+		assert_eq!(dialer.remote_address(), Some(listen_address));
+		assert_eq!(accepted.local_address(), Some(listen_address));
+		assert_eq!(dialer.local_address(), Some(remote));
+		ping_pong(&mut dialer, &mut accepted).await;
+	}
+
+	#[tokio::test]
+	async fn loopback_v4_test() {
+		loopback_test(TcpAddress::v4(Ipv4Addr::LOCALHOST, 0)).await;
+	}
+
+	#[tokio::test]
+	async fn loopback_v6_test() {
+		loopback_test(TcpAddress::v6(Ipv6Addr::LOCALHOST, 0)).await;
+	}
+
+	#[tokio::test]
+	async fn options_test() {
+		let default = TcpTransport::default();
+		let config = TcpConfig::new().nodelay(false).keepalive(Some(Duration::from_secs(30)));
+		let state = NodeState::builder().add_default_transport(TcpTransport::new(config)).build();
+		let (mut listener, dialer, accepted) = connect(&state, &"127.0.0.1:0".parse().unwrap()).await;
+		let address = listener.local_address().unwrap();
+		for connection in [&dialer, &accepted] {
+			assert!(!connection.stream().nodelay().unwrap());
+			assert!(SockRef::from(connection.stream()).keepalive().unwrap());
+		}
+
+		// Accepted connections are configured by the listening side.
+		let dialer = default.dial(&address).await.unwrap();
+		let (accepted, _) = listener.accept().await.unwrap().unwrap();
+		assert!(dialer.stream().nodelay().unwrap());
+		assert!(!SockRef::from(dialer.stream()).keepalive().unwrap());
+		assert!(!accepted.stream().nodelay().unwrap());
+	}
+
+	#[tokio::test]
+	async fn error_test() {
+		let state = NodeState::builder().add_default_transport(TcpTransport::default()).build();
+		let listener = state.listen_default(&TcpAddress::v4(Ipv4Addr::LOCALHOST, 0)).unwrap();
+		let address = listener.local_address().unwrap();
+		assert!(matches!(state.listen_default(&address), Err(TcpError::Listen(a, _)) if a == address));
+
+		drop(listener);
+		let error = state.dial_default(&address).await.err().unwrap();
+		assert!(matches!(error, TcpError::Dial(a, _) if a == address));
+		assert_eq!(error.to_string(), format!("failed to connect to `{}`", address));
+	}
+}
@@ -0,0 +1,42 @@
+use std::task::{Context, Poll};
+use varanus_core::transport::listener::{AcceptResult, TransportListener};
+use crate::{TcpConfig, TcpError};
+use crate::address::TcpAddress;
+use crate::connection::TcpConnection;
+
+pub struct TcpListener {
+	listener: tokio::net::TcpListener,
+	config: TcpConfig,
+}
+
+impl TcpListener {
+	pub(crate) fn new(listener: tokio::net::TcpListener, config: TcpConfig) -> Self {
+		Self {
+			listener,
+			config
+		}
+	}
+}
+
+impl TransportListener for TcpListener {
+	type Connection = TcpConnection;
+	type TransportError = TcpError;
+
+	/// An accepted socket that can't be given the listener's [`TcpConfig`] is closed and yields an
+	/// `Err` in its place.
+	fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<AcceptResult<Self>> {
+		let (stream, remote) = match self.listener.poll_accept(cx) {
+			Poll::Ready(Ok(d)) => d,
+			Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(TcpError::Accept(e)))),
+			Poll::Pending => return Poll::Pending,
+		};
+		let accepted = self.config
+			.apply(&stream)
+			.map(|_| (TcpConnection::new(stream), TcpAddress::new(remote)));
+		Poll::Ready(Some(accepted))
+	}
+
+	fn local_address(&self) -> Option<TcpAddress> {
+		self.listener.local_addr().ok().map(TcpAddress::new)
+	}
+}
@@ -0,0 +1,105 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use socket2::SockAddr;
+use varanus_core::transport::address::{GenericAddress, TransportIdentifier};
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct UnixIdentifier;
+
+impl TransportIdentifier for UnixIdentifier {
+	fn new() -> Self {
+		UnixIdentifier
+	}
+
+	fn string() -> String {
+		"unix".to_string()
+	}
+
+	fn from_string(_: &str) -> Self {
+		UnixIdentifier
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnixAddress {
+	/// A socket without a name, usually the remote side of an accepted connection.
+	Unnamed,
+	/// A socket file in the filesystem.
+	Path(PathBuf),
+	/// A name in the Linux abstract namespace. It doesn't show up in the filesystem and
+	/// disappears with the last socket using it.
+	#[cfg(target_os = "linux")]
+	Abstract(Vec<u8>),
+}
+
+impl UnixAddress {
+	pub fn path<P: Into<PathBuf>>(path: P) -> Self {
+		UnixAddress::Path(path.into())
+	}
+
+	#[cfg(target_os = "linux")]
+	pub fn abstract_name<N: Into<Vec<u8>>>(name: N) -> Self {
+		UnixAddress::Abstract(name.into())
+	}
+
+	pub fn as_path(&self) -> Option<&Path> {
+		match self {
+			UnixAddress::Path(path) => Some(path),
+			_ => None,
+		}
+	}
+
+	pub(crate) fn to_sock_addr(&self) -> io::Result<SockAddr> {
+		match self {
+			UnixAddress::Unnamed => Err(io::Error::new(io::ErrorKind::InvalidInput, "unnamed unix sockets can't be addressed")),
+			UnixAddress::Path(path) => SockAddr::unix(path),
+			#[cfg(target_os = "linux")]
+			UnixAddress::Abstract(name) => {
+				use std::ffi::OsStr;
+				use std::os::unix::ffi::OsStrExt;
+
+				// Abstract names are told apart from paths by a leading null byte.
+				let mut bytes = Vec::with_capacity(name.len() + 1);
+				bytes.push(0);
+				bytes.extend_from_slice(name);
+				SockAddr::unix(OsStr::from_bytes(&bytes))
+			}
+		}
+	}
+}
+
+impl From<&std::os::unix::net::SocketAddr> for UnixAddress {
+	fn from(address: &std::os::unix::net::SocketAddr) -> Self {
+		if let Some(path) = address.as_pathname() {
+			return UnixAddress::Path(path.to_path_buf());
+		}
+		#[cfg(target_os = "linux")]
+		{
+			use std::os::linux::net::SocketAddrExt;
+			if let Some(name) = address.as_abstract_name() {
+				return UnixAddress::Abstract(name.to_vec());
+			}
+		}
+		UnixAddress::Unnamed
+	}
+}
+
+impl Display for UnixAddress {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			UnixAddress::Unnamed => f.write_str("(unnamed)"),
+			UnixAddress::Path(path) => Display::fmt(&path.display(), f),
+			#[cfg(target_os = "linux")]
+			UnixAddress::Abstract(name) => write!(f, "@{}", name.escape_ascii()),
+		}
+	}
+}
+
+impl GenericAddress for UnixAddress {
+	type Associated = UnixIdentifier;
+
+	fn transport_identifier() -> Self::Associated {
+		UnixIdentifier
+	}
+}
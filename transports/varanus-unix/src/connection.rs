@@ -0,0 +1,75 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use socket2::SockRef;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::UnixStream;
+use tokio::net::unix::UCred;
+use varanus_core::transport::connection::TransportConnection;
+use crate::address::UnixAddress;
+
+pub struct UnixConnection {
+	stream: UnixStream,
+	local: UnixAddress,
+	remote: UnixAddress,
+}
+
+impl UnixConnection {
+	pub(crate) fn new(stream: UnixStream) -> io::Result<Self> {
+		// tokio's addresses can't represent abstract names, std's can.
+		let std_stream = std::os::unix::net::UnixStream::from(SockRef::from(&stream).try_clone()?);
+		let local = UnixAddress::from(&std_stream.local_addr()?);
+		let remote = UnixAddress::from(&std_stream.peer_addr()?);
+		Ok(Self {
+			stream,
+			local,
+			remote
+		})
+	}
+
+	/// The credentials of the process on the other side (`SO_PEERCRED` on Linux), taken when
+	/// the connection was established.
+	pub fn peer_credentials(&self) -> io::Result<UCred> {
+		self.stream.peer_cred()
+	}
+
+	pub fn stream(&self) -> &UnixStream {
+		&self.stream
+	}
+
+	pub fn into_stream(self) -> UnixStream {
+		self.stream
+	}
+}
+
+impl AsyncRead for UnixConnection {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_read(cx, buf)
+	}
+}
+
+impl AsyncWrite for UnixConnection {
+	fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.stream).poll_write(cx, buf)
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_flush(cx)
+	}
+
+	fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_shutdown(cx)
+	}
+}
+
+impl TransportConnection for UnixConnection {
+	type TransportAddress = UnixAddress;
+
+	fn local_address(&mut self) -> Option<Self::TransportAddress> {
+		Some(self.local.clone())
+	}
+
+	fn remote_address(&mut self) -> Option<Self::TransportAddress> {
+		Some(self.remote.clone())
+	}
+}
@@ -0,0 +1,283 @@
+//! Unix domain sockets, for agents talking to a node on the same host.
+#![cfg(unix)]
+
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use fast_version::{version, version_req};
+use socket2::{Domain, Socket, Type};
+use thiserror::Error;
+use varanus_core::protocol::{DefaultVersionNumber, GenericProtocol, Version, VersionReq};
+use varanus_core::protocol::name::ProtocolName;
+use varanus_core::transport::{BoxedDial, TransportProtocol};
+
+pub use tokio::net::unix::UCred;
+
+pub use self::address::{UnixAddress, UnixIdentifier};
+pub use self::connection::UnixConnection;
+pub use self::listener::UnixListener;
+
+pub mod address;
+pub mod connection;
+pub mod listener;
+
+const UNIX_VERSION: Version<DefaultVersionNumber> = version!("0.1.0");
+const UNIX_VERSION_REQ: VersionReq<DefaultVersionNumber> = version_req!("^0.1");
+
+lazy_static::lazy_static! {
+	static ref UNIX_PROTOCOL_NAME: ProtocolName = {
+		ProtocolName::new("UnixTransport".to_string()).unwrap()
+	};
+}
+
+#[derive(Error, Debug)]
+pub enum UnixError {
+	#[error("failed to connect to `{0}`")]
+	Dial(UnixAddress, #[source] io::Error),
+	#[error("failed to listen on `{0}`")]
+	Listen(UnixAddress, #[source] io::Error),
+	#[error("failed to accept an inbound connection")]
+	Accept(#[source] io::Error),
+}
+
+/// Removes the socket file at `path` if nothing listens on it anymore, which is what a process
+/// that didn't shut down cleanly leaves behind. Anything else is left for `bind` to report.
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+	let metadata = match fs::symlink_metadata(path) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+		Err(e) => return Err(e),
+	};
+	if !metadata.file_type().is_socket() {
+		return Ok(());
+	}
+	match std::os::unix::net::UnixStream::connect(path) {
+		Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path),
+		_ => Ok(()),
+	}
+}
+
+/// Options of the listening sockets.
+#[derive(Clone, Copy, Debug)]
+pub struct UnixConfig {
+	backlog: u32,
+}
+
+impl UnixConfig {
+	pub fn new() -> Self {
+		Self {
+			backlog: 1024,
+		}
+	}
+
+	/// The length of the queue of pending inbound connections. Dials to a listener whose queue is
+	/// full wait until it accepts.
+	pub fn backlog(mut self, backlog: u32) -> Self {
+		self.backlog = backlog;
+		self
+	}
+}
+
+impl Default for UnixConfig {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Unix domain stream sockets, addressed by path or, on Linux, by abstract name.
+///
+/// Dialing sockets aren't bound, so listeners see them as [`UnixAddress::Unnamed`]. Listening on a
+/// path takes over a socket file nothing listens on anymore.
+pub struct UnixTransport {
+	config: UnixConfig,
+}
+
+impl UnixTransport {
+	pub fn new(config: UnixConfig) -> Self {
+		Self {
+			config
+		}
+	}
+
+	pub fn config(&self) -> &UnixConfig {
+		&self.config
+	}
+}
+
+impl Default for UnixTransport {
+	fn default() -> Self {
+		Self::new(UnixConfig::default())
+	}
+}
+
+impl GenericProtocol for UnixTransport {
+	fn version() -> Version<DefaultVersionNumber> {
+		UNIX_VERSION
+	}
+
+	fn version_req() -> VersionReq<DefaultVersionNumber> {
+		UNIX_VERSION_REQ
+	}
+
+	fn name() -> ProtocolName {
+		UNIX_PROTOCOL_NAME.clone()
+	}
+}
+
+impl TransportProtocol for UnixTransport {
+	type Connection = UnixConnection;
+	type TransportIdentifier = UnixIdentifier;
+	type TransportAddress = UnixAddress;
+	type TransportError = UnixError;
+	type TransportFuture = BoxedDial<UnixConnection, UnixError>;
+	type Listener = UnixListener;
+
+	fn dial(&self, address: &Self::TransportAddress) -> Self::TransportFuture {
+		let address = address.clone();
+		Box::pin(async move {
+			let dial_error = |e| UnixError::Dial(address.clone(), e);
+			let sock_addr = address.to_sock_addr().map_err(dial_error)?;
+			// A non-blocking connect fails with `WouldBlock` while the backlog of the listener is
+			// full, a blocking one waits for room in it.
+			let connect = tokio::task::spawn_blocking(move || {
+				let socket = Socket::new(Domain::UNIX, Type::STREAM, None)?;
+				socket.connect(&sock_addr)?;
+				socket.set_nonblocking(true)?;
+				Ok::<_, io::Error>(socket)
+			});
+			let socket = connect.await.map_err(|e| dial_error(e.into()))?.map_err(dial_error)?;
+			let stream = tokio::net::UnixStream::from_std(socket.into()).map_err(dial_error)?;
+			UnixConnection::new(stream).map_err(dial_error)
+		})
+	}
+
+	fn listen(&self, address: &Self::TransportAddress) -> Result<Self::Listener, Self::TransportError> {
+		let listen_error = |e| UnixError::Listen(address.clone(), e);
+		if let Some(path) = address.as_path() {
+			remove_stale_socket(path).map_err(listen_error)?;
+		}
+		let socket = Socket::new(Domain::UNIX, Type::STREAM, None).map_err(listen_error)?;
+		socket.set_nonblocking(true).map_err(listen_error)?;
+		socket.bind(&address.to_sock_addr().map_err(listen_error)?).map_err(listen_error)?;
+		let backlog = self.config.backlog.try_into().unwrap_or(i32::MAX);
+		socket.listen(backlog).map_err(listen_error)?;
+		let listener = tokio::net::UnixListener::from_std(socket.into()).map_err(listen_error)?;
+		Ok(UnixListener::new(listener, address.clone()))
+	}
+}
+
+#[cfg(test)]
+mod unix_tests {
+	use std::fs;
+	use std::path::PathBuf;
+	use varanus_core::node::NodeState;
+	use varanus_core::transport::connection::TransportConnection;
+	use varanus_core::transport::listener::TransportListener;
+	use varanus_core::transport::testing::{connect, ping_pong};
+	use varanus_core::transport::TransportProtocol;
+	use crate::{UnixAddress, UnixConfig, UnixError, UnixTransport};
+
+	fn socket_path(name: &str) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("varanus-unix-{}-{}.sock", std::process::id(), name));
+		let _ = fs::remove_file(&path);
+		path
+	}
+
+	#[tokio::test]
+	async fn path_test() {
+		let path = socket_path("path");
+		let address = UnixAddress::path(&path);
+		let state = NodeState::builder().add_default_transport(UnixTransport::default()).build();
+		let mut listener = state.listen_default(&address).unwrap();
+		assert_eq!(listener.local_address(), Some(address.clone()));
+
+		let mut dialer = state.dial_default(&address).await.unwrap();
+		let (mut accepted, remote) = listener.accept().await.unwrap().unwrap();
+		assert_eq!(remote, UnixAddress::Unnamed);
+		assert_eq!(dialer.remote_address(), Some(address.clone()));
+		assert_eq!(dialer.local_address(), Some(UnixAddress::Unnamed));
+		assert_eq!(accepted.local_address(), Some(address.clone()));
+		ping_pong(&mut dialer, &mut accepted).await;
+
+		// A live socket is never taken over.
+		assert!(matches!(state.listen_default(&address), Err(UnixError::Listen(..))));
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[tokio::test]
+	async fn stale_socket_test() {
+		let path = socket_path("stale");
+		let address = UnixAddress::path(&path);
+		drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+		assert!(path.exists());
+
+		let state = NodeState::builder().add_default_transport(UnixTransport::default()).build();
+		let (listener, mut dialer, mut accepted) = connect(&state, &address).await;
+		ping_pong(&mut dialer, &mut accepted).await;
+		drop(listener);
+		fs::remove_file(&path).unwrap();
+
+		// Files that aren't sockets are left alone.
+		fs::write(&path, b"data").unwrap();
+		assert!(matches!(state.listen_default(&address), Err(UnixError::Listen(..))));
+		assert_eq!(fs::read(&path).unwrap(), b"data");
+		fs::remove_file(&path).unwrap();
+
+		let error = state.dial_default(&address).await.err().unwrap();
+		assert_eq!(error.to_string(), format!("failed to connect to `{}`", path.display()));
+	}
+
+	#[cfg(target_os = "linux")]
+	#[tokio::test]
+	async fn abstract_test() {
+		let address = UnixAddress::abstract_name(format!("varanus-unix-{}", std::process::id()));
+		let state = NodeState::builder().add_default_transport(UnixTransport::default()).build();
+		let (listener, mut dialer, mut accepted) = connect(&state, &address).await;
+		assert_eq!(dialer.remote_address(), Some(address.clone()));
+		assert_eq!(accepted.local_address(), Some(address.clone()));
+		ping_pong(&mut dialer, &mut accepted).await;
+
+		// The name is released with the listener.
+		drop(listener);
+		assert!(state.dial_default(&address).await.is_err());
+		assert!(state.listen_default(&address).is_ok());
+	}
+
+	#[tokio::test]
+	async fn peer_credentials_test() {
+		let path = socket_path("credentials");
+		let state = NodeState::builder().add_default_transport(UnixTransport::default()).build();
+		let (_listener, dialer, accepted) = connect(&state, &UnixAddress::path(&path)).await;
+
+		// Both ends are this process.
+		let credentials = accepted.peer_credentials().unwrap();
+		let own = dialer.peer_credentials().unwrap();
+		assert_eq!(credentials.uid(), own.uid());
+		assert_eq!(credentials.gid(), own.gid());
+		#[cfg(target_os = "linux")]
+		assert_eq!(credentials.pid(), Some(std::process::id() as i32));
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[tokio::test]
+	async fn backlog_test() {
+		let path = socket_path("backlog");
+		let address = UnixAddress::path(&path);
+		let transport = UnixTransport::new(UnixConfig::new().backlog(1));
+		let mut listener = transport.listen(&address).unwrap();
+
+		// Dials beyond a full backlog wait for the listener instead of failing.
+		let dials: Vec<_> = (0..8).map(|_| tokio::spawn(transport.dial(&address))).collect();
+		let accepting = tokio::spawn(async move {
+			for _ in 0..8 {
+				listener.accept().await.unwrap().unwrap();
+			}
+		});
+		for dial in dials {
+			dial.await.unwrap().unwrap();
+		}
+		accepting.await.unwrap();
+		fs::remove_file(&path).unwrap();
+	}
+}
@@ -0,0 +1,45 @@
+use std::task::{Context, Poll};
+use varanus_core::transport::connection::TransportConnection;
+use varanus_core::transport::listener::{AcceptResult, TransportListener};
+use crate::UnixError;
+use crate::address::UnixAddress;
+use crate::connection::UnixConnection;
+
+pub struct UnixListener {
+	listener: tokio::net::UnixListener,
+	address: UnixAddress,
+}
+
+impl UnixListener {
+	pub(crate) fn new(listener: tokio::net::UnixListener, address: UnixAddress) -> Self {
+		Self {
+			listener,
+			address
+		}
+	}
+}
+
+impl TransportListener for UnixListener {
+	type Connection = UnixConnection;
+	type TransportError = UnixError;
+
+	/// The remote address is the name the dialer bound, or [`UnixAddress::Unnamed`].
+	fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<AcceptResult<Self>> {
+		let stream = match self.listener.poll_accept(cx) {
+			Poll::Ready(Ok((stream, _))) => stream,
+			Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(UnixError::Accept(e)))),
+			Poll::Pending => return Poll::Pending,
+		};
+		let accepted = UnixConnection::new(stream)
+			.map(|mut connection| {
+				let remote = connection.remote_address().unwrap_or(UnixAddress::Unnamed);
+				(connection, remote)
+			})
+			.map_err(UnixError::Accept);
+		Poll::Ready(Some(accepted))
+	}
+
+	fn local_address(&self) -> Option<UnixAddress> {
+		Some(self.address.clone())
+	}
+}
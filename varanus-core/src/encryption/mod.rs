@@ -1,4 +1,5 @@
 pub mod plaintext;
+pub mod transport;
 
 use std::io::{Error, IoSlice};
 use std::pin::Pin;
@@ -13,8 +14,9 @@ pub trait EncryptionProtocol<T: AsyncWrite + AsyncRead + 'static>: GenericProtoc
 	type Connection: EncryptionConnection<T>;
 }
 
+#[allow(dead_code)]
 pub(crate) trait InnerEncryptionProtocol: GenericProtocol {
-	fn new_connection(&self, inner: Box<(dyn AsyncReadWrite + 'static)>);
+	fn new_connection(&self, inner: Box<dyn AsyncReadWrite + 'static>);
 }
 
 pub trait EncryptionConnection<T: AsyncWrite + AsyncRead + 'static>: AsyncWrite + AsyncRead + Sync + Send + Unpin {
@@ -29,7 +31,7 @@ impl<T: AsyncRead + AsyncWrite + Unpin> AsyncReadWrite for T {}
 #[pin_project]
 pub(crate) struct InnerEncryptionConnection {
 	#[pin]
-	inner: Box<(dyn AsyncReadWrite + 'static)>,
+	inner: Box<dyn AsyncReadWrite + 'static>,
 }
 
 impl AsyncWrite for InnerEncryptionConnection {
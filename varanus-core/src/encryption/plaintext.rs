@@ -14,7 +14,7 @@ pub struct PlainTextConnection<T: AsyncRead + AsyncWrite + 'static> {
 
 impl<T: AsyncRead + AsyncWrite + 'static> PlainTextConnection<T> {
 	#[inline]
-	fn get_pin<'a>(self: Pin<&mut Self>) -> Pin<&mut T> {
+	fn get_pin(self: Pin<&mut Self>) -> Pin<&mut T> {
 		let this = self.project();
 		this.inner
 	}
@@ -61,7 +61,6 @@ impl<T: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static> EncryptionConnec
 	}
 
 	fn inner_ref(&self) -> Rc<&T> {
-		let ret = Rc::new(&self.inner);
-		ret
+		Rc::new(&self.inner)
 	}
 }
\ No newline at end of file
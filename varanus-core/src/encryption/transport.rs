@@ -0,0 +1,49 @@
+use std::fmt::Debug;
+use fast_version::{version, version_req};
+use crate::encryption::EncryptionProtocol;
+use crate::encryption::plaintext::PlainTextConnection;
+use crate::protocol::{DefaultVersionNumber, GenericProtocol, Version, VersionReq};
+use crate::protocol::name::ProtocolName;
+use crate::transport::connection::TransportConnection;
+
+const TRANSPORT_ENCRYPTION_VERSION: Version<DefaultVersionNumber> = version!("0.1.0");
+const TRANSPORT_ENCRYPTION_VERSION_REQ: VersionReq<DefaultVersionNumber> = version_req!("^0.1");
+
+lazy_static::lazy_static! {
+	static ref TRANSPORT_ENCRYPTION_PROTOCOL_NAME: ProtocolName = {
+		ProtocolName::new("TransportEncryption".to_string()).unwrap()
+	};
+}
+
+/// A connection the transport itself already encrypts and authenticates, like a QUIC stream
+/// protected by the TLS handshake of its connection.
+pub trait SecureTransportConnection: TransportConnection {
+	type Identity: Clone + Send + Sync + Debug + 'static;
+
+	/// The identity the remote side proved during the handshake.
+	fn peer_identity(&self) -> Option<Self::Identity>;
+}
+
+/// Takes the place of an [`EncryptionProtocol`] on a [`SecureTransportConnection`]: the data
+/// passes through unchanged, since the transport already protects it. It can't be used over
+/// any other connection.
+#[derive(Default)]
+pub struct TransportEncryption;
+
+impl GenericProtocol for TransportEncryption {
+	fn version() -> Version<DefaultVersionNumber> {
+		TRANSPORT_ENCRYPTION_VERSION
+	}
+
+	fn version_req() -> VersionReq<DefaultVersionNumber> {
+		TRANSPORT_ENCRYPTION_VERSION_REQ
+	}
+
+	fn name() -> ProtocolName {
+		TRANSPORT_ENCRYPTION_PROTOCOL_NAME.clone()
+	}
+}
+
+impl<T: SecureTransportConnection + Send + Sync> EncryptionProtocol<T> for TransportEncryption {
+	type Connection = PlainTextConnection<T>;
+}
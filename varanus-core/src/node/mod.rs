@@ -6,6 +6,7 @@ use crate::node::builder::NodeStateBuilder;
 use crate::transport::address::{InternalTransportIdentifier, TransportIdentifier};
 use crate::transport::{InternalTransportProtocol, TransportProtocol};
 
+#[derive(Debug)]
 pub enum GenericResolutionError {
     TransportNotFound,
     RwLockError
@@ -22,6 +23,20 @@ impl<Dt: TransportProtocol> NodeState<Dt> {
     }
 
     pub fn dial_generic<T: TransportProtocol>(&self, address: &T::TransportAddress) -> Result<T::TransportFuture, GenericResolutionError> {
+        let transport_protocol = self.generic_transport::<T>()?;
+        Ok(transport_protocol.dial(address))
+    }
+
+    pub fn listen_default(&self, address: &Dt::TransportAddress) -> Result<Dt::Listener, Dt::TransportError> {
+        self.default_transport.listen(address)
+    }
+
+    pub fn listen_generic<T: TransportProtocol>(&self, address: &T::TransportAddress) -> Result<Result<T::Listener, T::TransportError>, GenericResolutionError> {
+        let transport_protocol = self.generic_transport::<T>()?;
+        Ok(transport_protocol.listen(address))
+    }
+
+    fn generic_transport<T: TransportProtocol>(&self) -> Result<Arc<T>, GenericResolutionError> {
         let transport_identifier = T::TransportIdentifier::new();
         let generic_transport_identifier: Box<dyn InternalTransportIdentifier> = Box::new(transport_identifier);
         let generic_transport_protocol = {
@@ -40,17 +55,86 @@ impl<Dt: TransportProtocol> NodeState<Dt> {
                 }
             }
         };
-        let transport_protocol: Arc<T> = match generic_transport_protocol.downcast::<T>() {
-            Ok(d) => d,
-            Err(_) => {
-                return Err(GenericResolutionError::TransportNotFound)
-            }
-        };
-
-        Ok(transport_protocol.dial(address))
+        match generic_transport_protocol.downcast::<T>() {
+            Ok(d) => Ok(d),
+            Err(_) => Err(GenericResolutionError::TransportNotFound)
+        }
     }
 
     pub fn builder() -> NodeStateBuilder<Dt> {
         NodeStateBuilder::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod node_tests {
+    use std::any::Any;
+    use std::future::poll_fn;
+    use std::pin::Pin;
+
+    use super::{GenericResolutionError, NodeState};
+    use crate::transport::address::{InternalTransportIdentifier, TransportIdentifier};
+    use crate::transport::connection::TransportConnection;
+    use crate::transport::listener::TransportListener;
+    use crate::transport::memory::{MemoryAddress, MemoryError, MemoryHub, MemoryIdentifier, MemoryTransport};
+    use crate::transport::testing::ping_pong;
+
+    /// The default and the generic transport sit on separate hubs, so a port can be taken on both.
+    fn node() -> NodeState<MemoryTransport> {
+        NodeState::builder()
+            .add_default_transport(MemoryTransport::with_hub(MemoryHub::new()))
+            .add_generic_transport(MemoryTransport::with_hub(MemoryHub::new()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn listen_default_test() {
+        let state = node();
+        let mut listener = state.listen_default(&MemoryAddress::new(1)).unwrap();
+        assert_eq!(listener.local_address(), Some(MemoryAddress::new(1)));
+        assert_eq!(state.listen_default(&MemoryAddress::new(1)).err(), Some(MemoryError::AddressInUse(1)));
+
+        let mut dialer = state.dial_default(&MemoryAddress::new(1)).await.unwrap();
+        let (mut accepted, remote) = listener.accept().await.unwrap().unwrap();
+        assert_eq!(dialer.local_address(), Some(remote));
+        ping_pong(&mut dialer, &mut accepted).await;
+    }
+
+    #[tokio::test]
+    async fn listen_generic_test() {
+        let state = node();
+        let mut listener = state.listen_generic::<MemoryTransport>(&MemoryAddress::new(1)).unwrap().unwrap();
+        assert!(state.listen_default(&MemoryAddress::new(1)).is_ok());
+
+        let mut dialer = state.dial_generic::<MemoryTransport>(&MemoryAddress::new(1)).unwrap().await.unwrap();
+        let (mut accepted, remote) = listener.accept().await.unwrap().unwrap();
+        assert_eq!(dialer.local_address(), Some(remote));
+        ping_pong(&mut accepted, &mut dialer).await;
+
+        let state = NodeState::builder().add_default_transport(MemoryTransport::with_hub(MemoryHub::new())).build();
+        assert!(matches!(state.listen_generic::<MemoryTransport>(&MemoryAddress::new(1)), Err(GenericResolutionError::TransportNotFound)));
+    }
+
+    #[tokio::test]
+    async fn listen_internal_test() {
+        let state = node();
+        let identifier: Box<dyn InternalTransportIdentifier> = Box::new(MemoryIdentifier::new());
+        let transport = state.alternate_transports.read().unwrap().get(&identifier).unwrap().clone();
+
+        let address: Box<dyn Any> = Box::new(MemoryAddress::new(1));
+        let mut listener = transport.listen(address).unwrap();
+        assert_eq!(listener.local_address().unwrap().get_identifier().to_self_string(), "memory");
+
+        let address: Box<dyn Any> = Box::new(MemoryAddress::new(1));
+        assert_eq!(transport.listen(address).err().unwrap().to_string(), "port `1` is already in use");
+
+        let address: Box<dyn Any> = Box::new(MemoryAddress::new(1));
+        let mut dialer = Pin::from(transport.dial(address)).await.unwrap();
+        let (mut accepted, remote) = poll_fn(|cx| listener.poll_accept(cx)).await.unwrap().unwrap();
+        let remote = (remote as Box<dyn Any>).downcast::<MemoryAddress>().unwrap();
+        let local = (dialer.local_address().unwrap() as Box<dyn Any>).downcast::<MemoryAddress>().unwrap();
+        assert_eq!(local, remote);
+        assert_eq!(accepted.remote_address().unwrap().get_identifier().to_self_string(), "memory");
+        ping_pong(&mut dialer, &mut accepted).await;
+    }
+}
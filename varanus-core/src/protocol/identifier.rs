@@ -1,5 +1,5 @@
-use fast_version::serde::{VersionSerde, VersionReqSerde, VersionSerdeError};
-use fast_version::{Version, VersionReq};
+use fast_version::{Version, VersionError, VersionReq};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::DefaultVersionNumber;
@@ -21,29 +21,37 @@ impl ProtocolIdentifier {
             version_req
         }
     }
+
+    /// Builds an identifier from its textual form, e.g. `("Ping", "1.1.0", "^1.1")`.
+    pub fn parse(name: &str, version: &str, version_req: &str) -> Result<Self, ProtocolIdentifierSerdeError> {
+        let name = ProtocolName::new(name.to_string())?;
+        let version = version.parse::<Version<DefaultVersionNumber>>().map_err(VersionError::from)?;
+        let version_req = version_req.parse::<VersionReq<DefaultVersionNumber>>().map_err(VersionError::from)?;
+
+        Ok(Self::new(name, version, version_req))
+    }
 }
 
 #[derive(Error, Debug)]
 pub enum ProtocolIdentifierSerdeError {
-    #[error("Error in version conversion")]
-    VersionSerde(#[from] VersionSerdeError),
     #[error("Error in protocl name conversion")]
     ProtocolNameError(#[from] ProtocolNameError),
-    #[error("Error in version requirenment conversion")]
-    VersionReqSerde,
+    #[error("Error in version conversion")]
+    VersionError(#[from] VersionError),
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct ProtocolIdentifierSerde {
     name: String,
-    version: VersionSerde,
-    version_req: VersionReqSerde
+    version: Version<DefaultVersionNumber>,
+    version_req: VersionReq<DefaultVersionNumber>
 }
 
 impl From<ProtocolIdentifier> for ProtocolIdentifierSerde {
     fn from(pi: ProtocolIdentifier) -> Self {
         let name = pi.name.to_string();
-        let version = VersionSerde::from(pi.version);
-        let version_req = VersionReqSerde::from(pi.version_req);
+        let version = pi.version;
+        let version_req = pi.version_req;
         Self {
             name,
             version,
@@ -56,9 +64,9 @@ impl TryFrom<ProtocolIdentifierSerde> for ProtocolIdentifier {
     type Error = ProtocolIdentifierSerdeError;
 
     fn try_from(value: ProtocolIdentifierSerde) -> Result<Self, Self::Error> {
-       let name = ProtocolName::new(value.name).map_err(|e| ProtocolIdentifierSerdeError::ProtocolNameError(e))?;
-       let version = Version::<DefaultVersionNumber>::try_from(value.version).map_err(ProtocolIdentifierSerdeError::VersionSerde)?;
-       let version_req = VersionReq::try_from(value.version_req).map_err(|_| ProtocolIdentifierSerdeError::VersionReqSerde)?;
+       let name = ProtocolName::new(value.name).map_err(ProtocolIdentifierSerdeError::ProtocolNameError)?;
+       let version = value.version;
+       let version_req = value.version_req;
 
        let ret = Self {
            name,
@@ -69,3 +77,24 @@ impl TryFrom<ProtocolIdentifierSerde> for ProtocolIdentifier {
        Ok(ret)
     }
 }
+
+#[cfg(test)]
+mod identifier_tests {
+    use std::error::Error;
+
+    use super::{ProtocolIdentifier, ProtocolIdentifierSerdeError};
+
+    #[test]
+    fn parse_test() {
+        let identifier = ProtocolIdentifier::parse("Ping", "1.1.0", "^1.1").unwrap();
+        assert_eq!(identifier.name.to_string(), "Ping");
+        assert_eq!(identifier.version.to_string(), "1.1.0");
+        assert_eq!(identifier.version_req.to_string(), "^1.1");
+
+        let error = ProtocolIdentifier::parse("Ping", "1.1.0", "<0.0.0").unwrap_err();
+        assert!(matches!(error, ProtocolIdentifierSerdeError::VersionError(_)));
+        let source = error.source().unwrap();
+        assert_eq!(source.to_string(), "failed to parse version");
+        assert_eq!(source.source().unwrap().to_string(), "invalid version requirement at position 1");
+    }
+}
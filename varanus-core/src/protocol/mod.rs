@@ -7,6 +7,7 @@ use self::{name::ProtocolName, identifier::ProtocolIdentifier};
 pub mod name;
 pub mod identifier;
 pub mod request;
+pub mod solver;
 
 pub type DefaultVersionNumber = u64;
 
@@ -22,6 +23,7 @@ pub trait GenericProtocol: Any + Send + Sync {
     }
 }
 
+#[allow(dead_code)]
 pub(crate) trait InternalGenericProtocol: Send + Sync {
     fn version(&self) -> Version<DefaultVersionNumber>;
     fn version_req(&self) -> VersionReq<DefaultVersionNumber>;
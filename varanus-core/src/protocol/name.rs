@@ -33,12 +33,15 @@ pub enum ProtocolNameError {
     NameToShort(usize),
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct ProtocolName {
     internal: String
 }
 
 impl ProtocolName {
+    /// # Safety
+    ///
+    /// `input` must be a valid protocol name, see [`ProtocolName::new`].
     pub unsafe fn unchecked_new(input: String) -> ProtocolName {
         ProtocolName {
             internal: input
@@ -107,8 +110,8 @@ impl std::fmt::Debug for ProtocolName {
     }
 }
 
-impl ToString for ProtocolName {
-    fn to_string(&self) -> String {
-        self.internal.clone()
+impl std::fmt::Display for ProtocolName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.internal)
     }
 }
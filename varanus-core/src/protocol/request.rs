@@ -13,12 +13,14 @@ pub trait RequestType<'a>: Serialize + Deserialize<'a> {
 pub trait ResponseType<'a>: Serialize + Deserialize<'a> {
 }
 
+pub type RequestHandleFuture<R, E> = Pin<Box<dyn Future<Output = Result<R, E>>>>;
+
 pub trait RequestProtocol<'a, Dt: TransportProtocol>: GenericProtocol {
 	type Request: RequestType<'a>;
 	type Response: ResponseType<'a>;
 	type Error: Error + Send + Sync;
 
-	fn handle_request(self: Arc<Self>, state: Arc<NodeState<Dt>>, request: Self::Request) -> Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+	fn handle_request(self: Arc<Self>, state: Arc<NodeState<Dt>>, request: Self::Request) -> RequestHandleFuture<Self::Response, Self::Error>;
 }
 
 pub trait FastRequestProtocol<'a, Dt: TransportProtocol>: GenericProtocol {
@@ -0,0 +1,819 @@
+//! PubGrub style resolution of protocol dependencies.
+//!
+//! Given the available protocol implementations and the protocols each of them depends on,
+//! [`solve`] picks one implementation per required protocol such that every dependency is
+//! satisfied. The solver keeps a list of incompatibilities, sets of terms that may not all hold
+//! at once, and learns a new one from every conflict it runs into. If the node's own requirements
+//! turn out to be incompatible, the chain of learned incompatibilities is rendered into an
+//! explanation like:
+//!
+//! ```text
+//! Because Rpc >=2 requires Ping 2.* and no implementation of Ping matches 2.*, Rpc >=2 is forbidden.
+//! And because the node requires Rpc >=2, version solving failed.
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use fast_version::version_req::{VersionRegCompType, VersionRegType};
+use fast_version::VersionReqSet;
+use thiserror::Error;
+
+use super::identifier::ProtocolIdentifier;
+use super::name::ProtocolName;
+use super::{DefaultVersionNumber, Version, VersionReq};
+
+type VersionSet = VersionReqSet<DefaultVersionNumber>;
+
+/// A protocol another one depends on, and the versions of it that work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolDependency {
+    pub name: ProtocolName,
+    pub version_req: VersionReq<DefaultVersionNumber>,
+}
+
+impl ProtocolDependency {
+    pub fn new(name: ProtocolName, version_req: VersionReq<DefaultVersionNumber>) -> Self {
+        Self {
+            name,
+            version_req
+        }
+    }
+}
+
+/// An available protocol implementation and the protocols it depends on.
+#[derive(Debug)]
+pub struct ProtocolCandidate {
+    pub identifier: ProtocolIdentifier,
+    pub dependencies: Vec<ProtocolDependency>,
+}
+
+impl ProtocolCandidate {
+    pub fn new(identifier: ProtocolIdentifier, dependencies: Vec<ProtocolDependency>) -> Self {
+        Self {
+            identifier,
+            dependencies
+        }
+    }
+}
+
+/// Returned by [`solve`] if no selection satisfies the requirements.
+#[derive(Error, Debug)]
+#[error("{explanation}")]
+pub struct SolveError {
+    explanation: String,
+}
+
+impl SolveError {
+    /// The human readable derivation of the failure, one step per line.
+    pub fn explanation(&self) -> &str {
+        &self.explanation
+    }
+}
+
+/// Picks the newest implementations that satisfy `requirements` and, transitively, the
+/// dependencies of every picked implementation.
+///
+/// The selection contains one candidate per protocol that ends up required, in the order they
+/// were decided on.
+pub fn solve<'a>(requirements: &[ProtocolDependency], candidates: &'a [ProtocolCandidate]) -> Result<Vec<&'a ProtocolCandidate>, SolveError> {
+    Solver::new(requirements, candidates).run()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Package {
+    /// The node itself, its dependencies are the requirements passed to [`solve`].
+    Root,
+    Protocol(ProtocolName),
+}
+
+fn exactly(version: Version<DefaultVersionNumber>) -> VersionSet {
+    let req = VersionReq::try_from(VersionRegCompType::Pure(VersionRegType::Strict(version)))
+        .expect("a strict requirement on a valid version is valid");
+    VersionSet::from(req)
+}
+
+/// The versions from `lower` up to but excluding `upper`, `None` leaves that side open.
+fn range(lower: Option<Version<DefaultVersionNumber>>, upper: Option<Version<DefaultVersionNumber>>) -> VersionSet {
+    let bound = |ty| VersionSet::from(VersionReq::try_from(VersionRegCompType::Pure(ty)).expect("a bound on a valid version is valid"));
+    let lower = lower.map_or_else(VersionSet::any, |v| bound(VersionRegType::GreaterEqPatch { major: v.major, minor: v.minor, patch: v.patch }));
+    let upper = upper.map_or_else(VersionSet::any, |v| bound(VersionRegType::LesserPatch { major: v.major, minor: v.minor, patch: v.patch }));
+    lower.intersection(&upper)
+}
+
+/// The single version in `versions`, if there is exactly one.
+fn single_version(versions: &VersionSet) -> Option<Version<DefaultVersionNumber>> {
+    let mut iter = versions.iter();
+    match (iter.next(), iter.next()) {
+        (Some(req), None) => match (req.lower_bound(), req.upper_bound()) {
+            (std::ops::Bound::Included(lower), std::ops::Bound::Included(upper)) if lower == upper => Some(lower),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A statement about a package: selected with a version in `versions`, or, if negative, not
+/// selected with any of them (which includes not being selected at all).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    package: Package,
+    positive: bool,
+    versions: VersionSet,
+}
+
+impl Term {
+    fn positive(package: Package, versions: VersionSet) -> Self {
+        Self { package, positive: true, versions }
+    }
+
+    fn negative(package: Package, versions: VersionSet) -> Self {
+        Self { package, positive: false, versions }
+    }
+
+    /// The term that holds for a package nothing is known about yet.
+    fn unknown(package: Package) -> Self {
+        Self::negative(package, VersionSet::empty())
+    }
+
+    fn negate(&self) -> Self {
+        Self { package: self.package.clone(), positive: !self.positive, versions: self.versions.clone() }
+    }
+
+    /// Both terms at once, they have to be about the same package.
+    fn intersect(&self, other: &Self) -> Self {
+        let package = self.package.clone();
+        match (self.positive, other.positive) {
+            (true, true) => Self::positive(package, self.versions.intersection(&other.versions)),
+            (true, false) => Self::positive(package, self.versions.intersection(&other.versions.complement())),
+            (false, true) => Self::positive(package, other.versions.intersection(&self.versions.complement())),
+            (false, false) => Self::negative(package, self.versions.union(&other.versions)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.positive && self.versions.is_empty()
+    }
+
+    fn satisfies(&self, other: &Self) -> bool {
+        self.intersect(&other.negate()).is_empty()
+    }
+
+    fn contradicts(&self, other: &Self) -> bool {
+        self.intersect(other).is_empty()
+    }
+}
+
+impl fmt::Display for Package {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Package::Root => f.write_str("the node"),
+            Package::Protocol(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Prints the package with its versions, `Ping 1.1.0`, `Ping ^1.1` or just `Ping` for any version.
+struct Versioned<'a>(&'a Package, &'a VersionSet);
+
+impl fmt::Display for Versioned<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Versioned(package, versions) = *self;
+        if *package == Package::Root || *versions == VersionSet::any() {
+            return write!(f, "{}", package);
+        }
+        match single_version(versions) {
+            Some(version) => write!(f, "{} {}", package, version),
+            None => write!(f, "{} {}", package, versions),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.positive {
+            f.write_str("not ")?;
+        }
+        write!(f, "{}", Versioned(&self.package, &self.versions))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cause {
+    /// The node has to be selected.
+    Root,
+    /// No implementation matches the versions of the only term.
+    NoVersions,
+    /// A package version depends on another package.
+    Dependency,
+    /// Learned from the two incompatibilities at these indices.
+    Derived(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+struct Incompatibility {
+    terms: Vec<Term>,
+    cause: Cause,
+}
+
+impl Incompatibility {
+    /// Terms about the same package are merged into one.
+    fn new(terms: Vec<Term>, cause: Cause) -> Self {
+        let mut merged: Vec<Term> = Vec::with_capacity(terms.len());
+        for term in terms {
+            match merged.iter_mut().find(|t| t.package == term.package) {
+                Some(existing) => *existing = existing.intersect(&term),
+                None => merged.push(term),
+            }
+        }
+        Self { terms: merged, cause }
+    }
+
+    fn term(&self, package: &Package) -> Option<&Term> {
+        self.terms.iter().find(|t| t.package == *package)
+    }
+
+    /// Whether the incompatibility rules out the node itself, which ends the search.
+    fn is_terminal(&self) -> bool {
+        match self.terms.as_slice() {
+            [] => true,
+            [term] => term.positive && term.package == Package::Root,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_terminal() {
+            return f.write_str("version solving failed");
+        }
+
+        if let (Cause::NoVersions, [term]) = (self.cause, self.terms.as_slice()) {
+            return match term.versions == VersionSet::any() {
+                true => write!(f, "no implementation of {} is available", term.package),
+                false => write!(f, "no implementation of {} matches {}", term.package, term.versions),
+            };
+        }
+
+        // The node is always selected, so it only adds noise next to other terms.
+        let terms: Vec<&Term> = match self.cause {
+            Cause::Dependency => self.terms.iter().collect(),
+            _ => self.terms.iter().filter(|t| !(t.positive && t.package == Package::Root)).collect(),
+        };
+        let versioned = |t: &Term| Versioned(&t.package, &t.versions).to_string();
+
+        match terms.as_slice() {
+            [t] if t.package == Package::Root => f.write_str("the node is required"),
+            [t] if t.positive => write!(f, "{} is forbidden", versioned(t)),
+            [t] => write!(f, "{} is required", versioned(t)),
+            [a, b] if a.positive && !b.positive => write!(f, "{} requires {}", versioned(a), versioned(b)),
+            [a, b] if !a.positive && b.positive => write!(f, "{} requires {}", versioned(b), versioned(a)),
+            [a, b] if a.positive && b.positive => write!(f, "{} is incompatible with {}", versioned(a), versioned(b)),
+            _ => {
+                let listed: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+                write!(f, "{} can't all hold", listed.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Assignment {
+    term: Term,
+    decision_level: usize,
+    /// The incompatibility the term was derived from, `None` for decisions.
+    cause: Option<usize>,
+}
+
+enum Relation {
+    Satisfied,
+    Contradicted,
+    /// Every term but the one at this index is satisfied, and that one is inconclusive.
+    AlmostSatisfied(usize),
+    Inconclusive,
+}
+
+#[derive(Default)]
+struct PartialSolution {
+    assignments: Vec<Assignment>,
+    decision_level: usize,
+}
+
+impl PartialSolution {
+    /// What the first `len` assignments say about `package`.
+    fn accumulated(&self, package: &Package, len: usize) -> Term {
+        self.assignments[..len]
+            .iter()
+            .filter(|a| a.term.package == *package)
+            .fold(Term::unknown(package.clone()), |acc, a| acc.intersect(&a.term))
+    }
+
+    fn relation(&self, incompatibility: &Incompatibility) -> Relation {
+        let mut relation = Relation::Satisfied;
+        for (i, term) in incompatibility.terms.iter().enumerate() {
+            let accumulated = self.accumulated(&term.package, self.assignments.len());
+            if accumulated.contradicts(term) {
+                return Relation::Contradicted;
+            }
+            if !accumulated.satisfies(term) {
+                relation = match relation {
+                    Relation::Satisfied => Relation::AlmostSatisfied(i),
+                    _ => Relation::Inconclusive,
+                };
+            }
+        }
+        relation
+    }
+
+    /// Index of the earliest assignment after which `term` is satisfied, `None` if it already
+    /// holds without any.
+    fn earliest_satisfying(&self, term: &Term) -> Option<usize> {
+        let mut accumulated = Term::unknown(term.package.clone());
+        if accumulated.satisfies(term) {
+            return None;
+        }
+        for (i, assignment) in self.assignments.iter().enumerate() {
+            if assignment.term.package == term.package {
+                accumulated = accumulated.intersect(&assignment.term);
+                if accumulated.satisfies(term) {
+                    return Some(i);
+                }
+            }
+        }
+        unreachable!("the incompatibility is satisfied by the partial solution")
+    }
+
+    /// The assignment that made `incompatibility` satisfied, and the decision level at which it
+    /// was already satisfied without it.
+    fn satisfier(&self, incompatibility: &Incompatibility) -> (usize, usize) {
+        let satisfier = incompatibility.terms
+            .iter()
+            .filter_map(|t| self.earliest_satisfying(t))
+            .max()
+            .expect("a satisfied incompatibility has a satisfier");
+        let satisfier_term = &self.assignments[satisfier].term;
+
+        let mut previous_level = 1;
+        for term in &incompatibility.terms {
+            let level = if term.package == satisfier_term.package {
+                // The earliest assignment that satisfies the term together with the satisfier.
+                let mut accumulated = satisfier_term.clone();
+                let mut level = None;
+                if !accumulated.satisfies(term) {
+                    for assignment in &self.assignments[..satisfier] {
+                        if assignment.term.package == term.package {
+                            accumulated = accumulated.intersect(&assignment.term);
+                            if accumulated.satisfies(term) {
+                                level = Some(assignment.decision_level);
+                                break;
+                            }
+                        }
+                    }
+                }
+                level
+            } else {
+                self.earliest_satisfying(term).map(|i| self.assignments[i].decision_level)
+            };
+            previous_level = previous_level.max(level.unwrap_or(0));
+        }
+
+        (satisfier, previous_level)
+    }
+
+    fn decide(&mut self, term: Term) {
+        self.decision_level += 1;
+        self.assignments.push(Assignment { term, decision_level: self.decision_level, cause: None });
+    }
+
+    fn derive(&mut self, term: Term, cause: usize) {
+        self.assignments.push(Assignment { term, decision_level: self.decision_level, cause: Some(cause) });
+    }
+
+    fn backtrack(&mut self, decision_level: usize) {
+        self.assignments.retain(|a| a.decision_level <= decision_level);
+        self.decision_level = decision_level;
+    }
+}
+
+struct Solver<'r, 'a> {
+    requirements: &'r [ProtocolDependency],
+    candidates: &'a [ProtocolCandidate],
+    /// Every incompatibility ever created, including the intermediate ones of conflict
+    /// resolution, so that explanations can refer to them.
+    incompatibilities: Vec<Incompatibility>,
+    /// The incompatibilities taking part in propagation, by the packages they mention.
+    by_package: HashMap<Package, Vec<usize>>,
+    solution: PartialSolution,
+}
+
+impl<'r, 'a> Solver<'r, 'a> {
+    fn new(requirements: &'r [ProtocolDependency], candidates: &'a [ProtocolCandidate]) -> Self {
+        Self {
+            requirements,
+            candidates,
+            incompatibilities: Vec::new(),
+            by_package: HashMap::new(),
+            solution: PartialSolution::default(),
+        }
+    }
+
+    fn run(mut self) -> Result<Vec<&'a ProtocolCandidate>, SolveError> {
+        let root = Incompatibility::new(vec![Term::negative(Package::Root, VersionSet::any())], Cause::Root);
+        self.add(root);
+
+        let mut next = Package::Root;
+        loop {
+            if let Err(terminal) = self.propagate(next) {
+                return Err(SolveError { explanation: self.explain(terminal) });
+            }
+            match self.next_package() {
+                Some(package) => next = self.choose(package),
+                None => return Ok(self.selection()),
+            }
+        }
+    }
+
+    fn add(&mut self, incompatibility: Incompatibility) -> usize {
+        let index = self.incompatibilities.len();
+        self.incompatibilities.push(incompatibility);
+        self.register(index);
+        index
+    }
+
+    fn register(&mut self, index: usize) {
+        for term in &self.incompatibilities[index].terms {
+            self.by_package.entry(term.package.clone()).or_default().push(index);
+        }
+    }
+
+    fn candidates_of<'s>(&'s self, name: &'s ProtocolName) -> impl Iterator<Item = &'a ProtocolCandidate> + 's {
+        self.candidates.iter().filter(move |c| c.identifier.name == *name)
+    }
+
+    /// Unit propagation: derives every term that follows from the incompatibilities, starting
+    /// with the ones mentioning `package`. Returns the terminal incompatibility on failure.
+    fn propagate(&mut self, package: Package) -> Result<(), usize> {
+        let mut changed = vec![package];
+        while let Some(package) = changed.pop() {
+            let indices = self.by_package.get(&package).cloned().unwrap_or_default();
+            for &index in indices.iter().rev() {
+                match self.solution.relation(&self.incompatibilities[index]) {
+                    Relation::Satisfied => {
+                        let root_cause = self.resolve_conflict(index)?;
+                        let term = match self.solution.relation(&self.incompatibilities[root_cause]) {
+                            Relation::AlmostSatisfied(i) => self.incompatibilities[root_cause].terms[i].negate(),
+                            _ => unreachable!("conflict resolution backtracks until one term is undecided"),
+                        };
+                        changed.clear();
+                        changed.push(term.package.clone());
+                        self.solution.derive(term, root_cause);
+                        break;
+                    }
+                    Relation::AlmostSatisfied(i) => {
+                        let term = self.incompatibilities[index].terms[i].negate();
+                        changed.push(term.package.clone());
+                        self.solution.derive(term, index);
+                    }
+                    Relation::Contradicted | Relation::Inconclusive => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Learns the root cause of the satisfied incompatibility at `index` and backtracks until it
+    /// is almost satisfied, or returns the terminal incompatibility if there is no way back.
+    fn resolve_conflict(&mut self, index: usize) -> Result<usize, usize> {
+        let mut current = index;
+        loop {
+            let incompatibility = &self.incompatibilities[current];
+            if incompatibility.is_terminal() {
+                return Err(current);
+            }
+
+            let (satisfier_index, previous_level) = self.solution.satisfier(incompatibility);
+            let satisfier = self.solution.assignments[satisfier_index].clone();
+            let term = incompatibility.term(&satisfier.term.package)
+                .expect("the satisfier is about a package of the incompatibility")
+                .clone();
+
+            let cause = match satisfier.cause {
+                Some(cause) if previous_level >= satisfier.decision_level => cause,
+                _ => {
+                    if current != index {
+                        self.register(current);
+                    }
+                    self.solution.backtrack(previous_level);
+                    return Ok(current);
+                }
+            };
+
+            let mut terms: Vec<Term> = incompatibility.terms
+                .iter()
+                .chain(&self.incompatibilities[cause].terms)
+                .filter(|t| t.package != satisfier.term.package)
+                .cloned()
+                .collect();
+            if !satisfier.term.satisfies(&term) {
+                terms.push(satisfier.term.intersect(&term.negate()).negate());
+            }
+
+            self.incompatibilities.push(Incompatibility::new(terms, Cause::Derived(current, cause)));
+            current = self.incompatibilities.len() - 1;
+        }
+    }
+
+    /// The undecided package with a positive derivation and the fewest matching candidates.
+    fn next_package(&self) -> Option<Package> {
+        let mut packages: Vec<&Package> = Vec::new();
+        for assignment in &self.solution.assignments {
+            let package = &assignment.term.package;
+            if !packages.contains(&package) {
+                packages.push(package);
+            }
+        }
+
+        let len = self.solution.assignments.len();
+        packages
+            .into_iter()
+            .filter(|p| self.solution.assignments.iter().all(|a| a.cause.is_some() || a.term.package != **p))
+            .filter_map(|p| {
+                let term = self.solution.accumulated(p, len);
+                if !term.positive {
+                    return None;
+                }
+                let count = match p {
+                    Package::Root => 1,
+                    Package::Protocol(name) => self.candidates_of(name).filter(|c| term.versions.fits(c.identifier.version)).count(),
+                };
+                Some((count, p))
+            })
+            .min_by_key(|(count, _)| *count)
+            .map(|(_, p)| p.clone())
+    }
+
+    /// Decides on the newest version of `package` that's still allowed and adds its dependencies,
+    /// or records that there is none. Returns the package to propagate from.
+    fn choose(&mut self, package: Package) -> Package {
+        let allowed = self.solution.accumulated(&package, self.solution.assignments.len());
+
+        match &package {
+            Package::Root => {
+                for dependency in self.requirements {
+                    let terms = vec![
+                        Term::positive(Package::Root, VersionSet::any()),
+                        Term::negative(Package::Protocol(dependency.name.clone()), VersionSet::from(dependency.version_req)),
+                    ];
+                    self.add(Incompatibility::new(terms, Cause::Dependency));
+                }
+                self.solution.decide(Term::positive(Package::Root, VersionSet::any()));
+            }
+            Package::Protocol(name) => {
+                let newest = self.candidates_of(name)
+                    .filter(|c| allowed.versions.fits(c.identifier.version))
+                    .max_by_key(|c| c.identifier.version);
+                match newest {
+                    Some(candidate) => {
+                        for dependency in &candidate.dependencies {
+                            let terms = vec![
+                                Term::positive(package.clone(), self.sharing(candidate, dependency)),
+                                Term::negative(Package::Protocol(dependency.name.clone()), VersionSet::from(dependency.version_req)),
+                            ];
+                            self.add(Incompatibility::new(terms, Cause::Dependency));
+                        }
+                        self.solution.decide(Term::positive(package.clone(), exactly(candidate.identifier.version)));
+                    }
+                    None => {
+                        let term = Term::positive(package.clone(), allowed.versions);
+                        self.add(Incompatibility::new(vec![term], Cause::NoVersions));
+                    }
+                }
+            }
+        }
+        package
+    }
+
+    /// The versions `candidate`'s `dependency` is known to hold for: the range around it without
+    /// any implementation that lacks the dependency. Saying so for the whole range rather than
+    /// only the candidate lets a conflict rule out all of those implementations at once.
+    fn sharing(&self, candidate: &ProtocolCandidate, dependency: &ProtocolDependency) -> VersionSet {
+        let version = candidate.identifier.version;
+        let lacking = |c: &&ProtocolCandidate| !c.dependencies.contains(dependency);
+        let older = self.candidates_of(&candidate.identifier.name)
+            .filter(lacking)
+            .map(|c| c.identifier.version)
+            .filter(|v| *v < version)
+            .max();
+        let newer = self.candidates_of(&candidate.identifier.name)
+            .filter(lacking)
+            .map(|c| c.identifier.version)
+            .filter(|v| *v > version)
+            .min();
+        // The range starts at the oldest implementation newer than the one lacking the dependency.
+        let lower = older.and_then(|older| {
+            self.candidates_of(&candidate.identifier.name)
+                .map(|c| c.identifier.version)
+                .filter(|v| *v > older)
+                .min()
+        });
+        range(lower, newer)
+    }
+
+    fn selection(&self) -> Vec<&'a ProtocolCandidate> {
+        self.solution.assignments
+            .iter()
+            .filter(|a| a.cause.is_none())
+            .filter_map(|a| match &a.term.package {
+                Package::Root => None,
+                Package::Protocol(name) => {
+                    let version = single_version(&a.term.versions)?;
+                    self.candidates_of(name).find(|c| c.identifier.version == version)
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the derivation of the terminal incompatibility at `index`, one line per learned
+    /// incompatibility. Lines that are referred to more than once get a number.
+    fn explain(&self, index: usize) -> String {
+        let mut references = HashMap::new();
+        self.count_references(index, &mut references);
+
+        let mut report = Report { references, numbers: HashMap::new(), lines: Vec::new() };
+        self.explain_into(index, &mut report, false);
+        report.lines.join("\n")
+    }
+
+    fn count_references(&self, index: usize, references: &mut HashMap<usize, usize>) {
+        let count = references.entry(index).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            return;
+        }
+        if let Cause::Derived(a, b) = self.incompatibilities[index].cause {
+            self.count_references(a, references);
+            self.count_references(b, references);
+        }
+    }
+
+    fn is_derived(&self, index: usize) -> bool {
+        matches!(self.incompatibilities[index].cause, Cause::Derived(..))
+    }
+
+    /// The incompatibility, followed by its line number if it has one.
+    fn reference(&self, index: usize, report: &Report) -> String {
+        match report.numbers.get(&index) {
+            Some(number) => format!("{} ({})", self.incompatibilities[index], number),
+            None => self.incompatibilities[index].to_string(),
+        }
+    }
+
+    fn explain_into(&self, index: usize, report: &mut Report, numbered: bool) {
+        let incompatibility = &self.incompatibilities[index];
+        let (a, b) = match incompatibility.cause {
+            Cause::Derived(a, b) => (a, b),
+            _ => {
+                report.lines.push(format!("{}.", incompatibility));
+                return;
+            }
+        };
+
+        let line = match (self.is_derived(a), self.is_derived(b)) {
+            (true, true) => {
+                for cause in [a, b] {
+                    if !report.numbers.contains_key(&cause) {
+                        self.explain_into(cause, report, true);
+                    }
+                }
+                format!("Because {} and {}, {}.", self.reference(a, report), self.reference(b, report), incompatibility)
+            }
+            (true, false) | (false, true) => {
+                let (derived, external) = if self.is_derived(a) { (a, b) } else { (b, a) };
+                if report.numbers.contains_key(&derived) {
+                    format!("Because {} and {}, {}.", self.incompatibilities[external], self.reference(derived, report), incompatibility)
+                } else {
+                    self.explain_into(derived, report, false);
+                    format!("And because {}, {}.", self.incompatibilities[external], incompatibility)
+                }
+            }
+            (false, false) => format!("Because {} and {}, {}.", self.incompatibilities[a], self.incompatibilities[b], incompatibility),
+        };
+
+        if numbered || report.references.get(&index).is_some_and(|count| *count > 1) {
+            let number = report.numbers.len() + 1;
+            report.numbers.insert(index, number);
+            report.lines.push(format!("{} ({})", line, number));
+        } else {
+            report.lines.push(line);
+        }
+    }
+}
+
+struct Report {
+    references: HashMap<usize, usize>,
+    numbers: HashMap<usize, usize>,
+    lines: Vec<String>,
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use fast_version::{Version, VersionReq};
+
+    use super::{solve, ProtocolCandidate, ProtocolDependency};
+    use crate::protocol::identifier::ProtocolIdentifier;
+    use crate::protocol::name::ProtocolName;
+
+    fn name(input: &str) -> ProtocolName {
+        ProtocolName::new(input.to_string()).unwrap()
+    }
+
+    fn dependency(protocol: &str, req: &str) -> ProtocolDependency {
+        ProtocolDependency::new(name(protocol), VersionReq::parse(req).unwrap())
+    }
+
+    fn candidate(protocol: &str, version: &str, dependencies: &[(&str, &str)]) -> ProtocolCandidate {
+        let version: Version<u64> = version.parse().unwrap();
+        let identifier = ProtocolIdentifier::new(name(protocol), version, VersionReq::default());
+        ProtocolCandidate::new(identifier, dependencies.iter().map(|(p, r)| dependency(p, r)).collect())
+    }
+
+    fn selected(requirements: &[ProtocolDependency], candidates: &[ProtocolCandidate]) -> Vec<String> {
+        let mut ret: Vec<String> = solve(requirements, candidates)
+            .unwrap()
+            .into_iter()
+            .map(|c| format!("{} {}", c.identifier.name, c.identifier.version))
+            .collect();
+        ret.sort();
+        ret
+    }
+
+    #[test]
+    fn newest_test() {
+        let candidates = [
+            candidate("Ping", "1.0.0", &[]),
+            candidate("Ping", "1.1.0", &[]),
+            candidate("Ping", "1.2.0", &[]),
+            candidate("Rpc", "1.0.0", &[("Ping", ">=1.1")]),
+        ];
+        assert_eq!(selected(&[dependency("Rpc", "*")], &candidates), ["Ping 1.2.0", "Rpc 1.0.0"]);
+        assert_eq!(selected(&[dependency("Rpc", "*"), dependency("Ping", "~1.1")], &candidates), ["Ping 1.1.0", "Rpc 1.0.0"]);
+        assert_eq!(selected(&[], &candidates), Vec::<String>::new());
+    }
+
+    #[test]
+    fn backtrack_test() {
+        let candidates = [
+            candidate("Ping", "1.0.0", &[]),
+            candidate("Ping", "1.2.0", &[]),
+            candidate("Rpc", "1.0.0", &[("Ping", ">=1.1")]),
+            candidate("Rpc", "2.0.0", &[("Ping", "^2")]),
+            candidate("Sync", "1.0.0", &[("Core", "^1")]),
+            candidate("Sync", "1.1.0", &[("Core", "^2")]),
+            candidate("Core", "1.4.0", &[("Ping", "<1.1")]),
+            candidate("Core", "2.0.0", &[("Ping", "=1.0.0")]),
+        ];
+        assert_eq!(selected(&[dependency("Rpc", "*")], &candidates), ["Ping 1.2.0", "Rpc 1.0.0"]);
+        assert_eq!(selected(&[dependency("Sync", "*")], &candidates), ["Core 2.0.0", "Ping 1.0.0", "Sync 1.1.0"]);
+
+        // Every Sync needs Ping 1.0.0, so only the Rpc not depending on Ping 1.2 remains, and it
+        // needs Ping 2.
+        let error = solve(&[dependency("Sync", "*"), dependency("Rpc", "*")], &candidates).unwrap_err();
+        assert!(error.explanation().ends_with("version solving failed."), "{}", error);
+    }
+
+    #[test]
+    fn explanation_test() {
+        let candidates = [
+            candidate("Ping", "1.1.0", &[]),
+            candidate("Rpc", "1.0.0", &[("Ping", ">=1.1")]),
+            candidate("Rpc", "2.0.0", &[("Ping", "^2")]),
+        ];
+        let error = solve(&[dependency("Rpc", ">=2")], &candidates).unwrap_err();
+        assert_eq!(
+            error.explanation(),
+            "Because Rpc >=2 requires Ping 2.* and no implementation of Ping matches 2.*, Rpc >=2 is forbidden.\n\
+             And because the node requires Rpc >=2, version solving failed."
+        );
+
+        let error = solve(&[dependency("Chat", "^1")], &candidates).unwrap_err();
+        assert_eq!(error.to_string(), "Because no implementation of Chat matches 1.* and the node requires Chat 1.*, version solving failed.");
+    }
+
+    #[test]
+    fn conflict_test() {
+        let candidates = [
+            candidate("Foo", "1.0.0", &[("Core", "^1")]),
+            candidate("Bar", "1.0.0", &[("Core", "^2")]),
+            candidate("Core", "1.0.0", &[]),
+            candidate("Core", "2.0.0", &[]),
+        ];
+        let error = solve(&[dependency("Foo", "*"), dependency("Bar", "*")], &candidates).unwrap_err();
+        assert_eq!(
+            error.explanation(),
+            "Because Bar requires Core 2.* and Foo requires Core 1.*, Bar is incompatible with Foo.\n\
+             And because the node requires Foo, Bar is forbidden.\n\
+             And because the node requires Bar, version solving failed."
+        );
+    }
+}
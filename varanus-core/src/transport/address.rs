@@ -9,6 +9,7 @@ pub trait TransportIdentifier: PartialOrd + Clone + Send + Sync + Debug + Any {
 }
 
 
+#[allow(dead_code)]
 pub(crate) trait InternalTransportIdentifier: Send + Sync + Debug {
     fn to_self_string(&self) -> String;
     fn inner_typeid(&self) -> TypeId;
@@ -28,8 +29,8 @@ impl<T: TransportIdentifier> InternalTransportIdentifier for T {
 
 impl PartialEq for Box<dyn InternalTransportIdentifier> {
     fn eq(&self, other: &Self) -> bool {
-        let other_type_id = other.type_id();
-        let self_type_id = self.type_id();
+        let other_type_id = other.inner_typeid();
+        let self_type_id = self.inner_typeid();
         self_type_id.eq(&other_type_id)
     }
 }
@@ -40,7 +41,7 @@ impl Eq for Box<dyn InternalTransportIdentifier> {
 
 impl Hash for Box<dyn InternalTransportIdentifier> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let self_type_id = self.type_id();
+        let self_type_id = self.inner_typeid();
         self_type_id.hash(state);
     }
 }
@@ -51,6 +52,7 @@ pub trait GenericAddress: Clone + Send + Sync + Debug + Any {
     fn transport_identifier() -> Self::Associated;
 }
 
+#[allow(dead_code)]
 pub(crate) trait InternalGenericAddress: Any + Send + Sync + Debug {
     fn get_identifier(&self) -> Box<dyn InternalTransportIdentifier>;
 }
@@ -11,6 +11,7 @@ pub trait TransportConnection: AsyncWrite + AsyncRead + Any + Unpin {
 
 }
 
+#[allow(dead_code)]
 pub(crate) trait InternalTransportConnection: AsyncWrite + AsyncRead + Unpin {
     fn local_address(&mut self) -> Option<Box<dyn InternalGenericAddress>>;
     fn remote_address(&mut self) -> Option<Box<dyn InternalGenericAddress>>;
@@ -0,0 +1,84 @@
+use std::any::Any;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::address::InternalGenericAddress;
+use super::connection::{InternalTransportConnection, TransportConnection};
+
+/// An inbound connection together with the address of the remote side.
+pub type Accepted<C> = (C, <C as TransportConnection>::TransportAddress);
+
+/// An item of a listener, `None` once it is closed.
+pub type AcceptResult<L> = Option<Result<Accepted<<L as TransportListener>::Connection>, <L as TransportListener>::TransportError>>;
+
+/// Inbound connections of a [`TransportProtocol::listen`](super::TransportProtocol::listen) call.
+///
+/// Shaped like a stream: `poll_accept` yields connections until it returns `Ready(None)`, after
+/// which the listener is closed. An `Err` item only concerns that one connection attempt, the
+/// listener keeps accepting afterwards.
+pub trait TransportListener: Any + Unpin {
+    type Connection: TransportConnection;
+    type TransportError: Error + Send + Sync + 'static;
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<AcceptResult<Self>>;
+
+    fn local_address(&self) -> Option<<Self::Connection as TransportConnection>::TransportAddress>;
+
+    /// Waits for the next inbound connection.
+    fn accept(&mut self) -> Accept<'_, Self> where Self: Sized {
+        Accept {
+            listener: self
+        }
+    }
+}
+
+pub struct Accept<'a, L: TransportListener> {
+    listener: &'a mut L,
+}
+
+impl<L: TransportListener> Future for Accept<'_, L> {
+    type Output = AcceptResult<L>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().listener.poll_accept(cx)
+    }
+}
+
+pub(crate) type InternalAccepted = (Box<dyn InternalTransportConnection>, Box<dyn InternalGenericAddress>);
+
+pub(crate) type InternalAcceptResult = Option<Result<InternalAccepted, Box<dyn Error + Send + Sync>>>;
+
+#[allow(dead_code)]
+pub(crate) trait InternalTransportListener: Unpin {
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<InternalAcceptResult>;
+
+    fn local_address(&self) -> Option<Box<dyn InternalGenericAddress>>;
+}
+
+impl<T: TransportListener> InternalTransportListener for T {
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<InternalAcceptResult> {
+        T::poll_accept(self, cx).map(|item| {
+            item.map(|result| {
+                result.map(|(connection, address)| {
+                    let connection: Box<dyn InternalTransportConnection> = Box::new(connection);
+                    let address: Box<dyn InternalGenericAddress> = Box::new(address);
+                    (connection, address)
+                }).map_err(|e| {
+                    let boxed: Box<dyn Error + Send + Sync> = Box::new(e);
+                    boxed
+                })
+            })
+        })
+    }
+
+    fn local_address(&self) -> Option<Box<dyn InternalGenericAddress>> {
+        T::local_address(self).map(|d| {
+            let boxed: Box<dyn InternalGenericAddress> = Box::new(d);
+            boxed
+        })
+    }
+}
+
+pub(crate) type InternalListener = Box<dyn InternalTransportListener>;
@@ -0,0 +1,401 @@
+//! An in-process transport, to run protocols and whole clusters without sockets.
+//!
+//! Transports that share a [`MemoryHub`] can reach each other; by default they all use the
+//! process-global hub. Connections are `tokio::io::duplex` pipes, so nothing here needs a runtime
+//! except for driving the reads and writes.
+
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+
+use fast_version::{version, version_req};
+use thiserror::Error;
+use tokio::io::{duplex, AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::protocol::name::ProtocolName;
+use crate::protocol::{DefaultVersionNumber, GenericProtocol, Version, VersionReq};
+use crate::transport::address::{GenericAddress, TransportIdentifier};
+use crate::transport::connection::TransportConnection;
+use crate::transport::listener::{AcceptResult, TransportListener};
+use crate::transport::TransportProtocol;
+
+const MEMORY_VERSION: Version<DefaultVersionNumber> = version!("0.1.0");
+const MEMORY_VERSION_REQ: VersionReq<DefaultVersionNumber> = version_req!("^0.1");
+
+/// Capacity of each direction of a connection in bytes.
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Ports handed out for port `0` and to dialers start here, to stay clear of the small port
+/// numbers tests usually pick.
+const EPHEMERAL_PORT_START: u64 = 1 << 32;
+
+lazy_static::lazy_static! {
+    static ref MEMORY_PROTOCOL_NAME: ProtocolName = {
+        ProtocolName::new("MemoryTransport".to_string()).unwrap()
+    };
+
+    static ref GLOBAL_HUB: MemoryHub = MemoryHub::new();
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct MemoryIdentifier;
+
+impl TransportIdentifier for MemoryIdentifier {
+    fn new() -> Self {
+        MemoryIdentifier
+    }
+
+    fn string() -> String {
+        "memory".to_string()
+    }
+
+    fn from_string(_: &str) -> Self {
+        MemoryIdentifier
+    }
+}
+
+/// A port on a [`MemoryHub`]. Listening on port `0` picks an unused port.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemoryAddress {
+    port: u64,
+}
+
+impl MemoryAddress {
+    pub fn new(port: u64) -> Self {
+        Self {
+            port
+        }
+    }
+
+    pub fn port(&self) -> u64 {
+        self.port
+    }
+}
+
+impl From<u64> for MemoryAddress {
+    fn from(port: u64) -> Self {
+        Self::new(port)
+    }
+}
+
+impl GenericAddress for MemoryAddress {
+    type Associated = MemoryIdentifier;
+
+    fn transport_identifier() -> Self::Associated {
+        MemoryIdentifier
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MemoryError {
+    #[error("nothing is listening on port `{0}`")]
+    ConnectionRefused(u64),
+    #[error("port `{0}` is already in use")]
+    AddressInUse(u64),
+}
+
+struct HubState {
+    listeners: HashMap<u64, UnboundedSender<MemoryConnection>>,
+    next_ephemeral_port: u64,
+}
+
+impl HubState {
+    fn ephemeral_port(&mut self) -> u64 {
+        loop {
+            let port = self.next_ephemeral_port;
+            self.next_ephemeral_port = self.next_ephemeral_port.checked_add(1).unwrap_or(EPHEMERAL_PORT_START);
+            if !self.listeners.contains_key(&port) {
+                return port;
+            }
+        }
+    }
+}
+
+/// The registry of listening ports. Clones share the same ports.
+#[derive(Clone)]
+pub struct MemoryHub {
+    state: Arc<Mutex<HubState>>,
+}
+
+impl MemoryHub {
+    /// A hub of its own, isolated from all others.
+    pub fn new() -> Self {
+        let state = HubState {
+            listeners: HashMap::new(),
+            next_ephemeral_port: EPHEMERAL_PORT_START,
+        };
+        Self {
+            state: Arc::new(Mutex::new(state))
+        }
+    }
+
+    /// The process-global hub.
+    pub fn global() -> Self {
+        GLOBAL_HUB.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HubState> {
+        // The map stays consistent even if a holder panicked.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for MemoryHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct MemoryConnection {
+    stream: DuplexStream,
+    local: MemoryAddress,
+    remote: MemoryAddress,
+}
+
+impl AsyncRead for MemoryConnection {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MemoryConnection {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+impl TransportConnection for MemoryConnection {
+    type TransportAddress = MemoryAddress;
+
+    fn local_address(&mut self) -> Option<Self::TransportAddress> {
+        Some(self.local)
+    }
+
+    fn remote_address(&mut self) -> Option<Self::TransportAddress> {
+        Some(self.remote)
+    }
+}
+
+/// Frees its port when dropped.
+pub struct MemoryListener {
+    hub: MemoryHub,
+    address: MemoryAddress,
+    sender: UnboundedSender<MemoryConnection>,
+    receiver: UnboundedReceiver<MemoryConnection>,
+}
+
+impl TransportListener for MemoryListener {
+    type Connection = MemoryConnection;
+    type TransportError = MemoryError;
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<AcceptResult<Self>> {
+        // The listener holds a sender itself, so the channel never runs dry.
+        self.receiver.poll_recv(cx).map(|connection| {
+            connection.map(|connection| {
+                let remote = connection.remote;
+                Ok((connection, remote))
+            })
+        })
+    }
+
+    fn local_address(&self) -> Option<MemoryAddress> {
+        Some(self.address)
+    }
+}
+
+impl Drop for MemoryListener {
+    fn drop(&mut self) {
+        let mut state = self.hub.lock();
+        if state.listeners.get(&self.address.port).is_some_and(|sender| sender.same_channel(&self.sender)) {
+            state.listeners.remove(&self.address.port);
+        }
+    }
+}
+
+pub struct MemoryTransport {
+    hub: MemoryHub,
+    buffer_size: usize,
+}
+
+impl MemoryTransport {
+    /// A transport on the process-global hub.
+    pub fn new() -> Self {
+        Self::with_hub(MemoryHub::global())
+    }
+
+    pub fn with_hub(hub: MemoryHub) -> Self {
+        Self {
+            hub,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        }
+    }
+
+    /// Sets the capacity of each direction of the connections this transport dials.
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    pub fn hub(&self) -> &MemoryHub {
+        &self.hub
+    }
+}
+
+impl Default for MemoryTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GenericProtocol for MemoryTransport {
+    fn version() -> Version<DefaultVersionNumber> {
+        MEMORY_VERSION
+    }
+
+    fn version_req() -> VersionReq<DefaultVersionNumber> {
+        MEMORY_VERSION_REQ
+    }
+
+    fn name() -> ProtocolName {
+        MEMORY_PROTOCOL_NAME.clone()
+    }
+}
+
+impl TransportProtocol for MemoryTransport {
+    type Connection = MemoryConnection;
+    type TransportIdentifier = MemoryIdentifier;
+    type TransportAddress = MemoryAddress;
+    type TransportError = MemoryError;
+    type TransportFuture = Ready<Result<MemoryConnection, MemoryError>>;
+    type Listener = MemoryListener;
+
+    fn dial(&self, address: &Self::TransportAddress) -> Self::TransportFuture {
+        let mut state = self.hub.lock();
+        let sender = match state.listeners.get(&address.port) {
+            Some(d) => d.clone(),
+            None => return ready(Err(MemoryError::ConnectionRefused(address.port))),
+        };
+        let local = MemoryAddress::new(state.ephemeral_port());
+        drop(state);
+
+        let (client, server) = duplex(self.buffer_size);
+        let server = MemoryConnection { stream: server, local: *address, remote: local };
+        match sender.send(server) {
+            Ok(()) => ready(Ok(MemoryConnection { stream: client, local, remote: *address })),
+            Err(_) => ready(Err(MemoryError::ConnectionRefused(address.port))),
+        }
+    }
+
+    fn listen(&self, address: &Self::TransportAddress) -> Result<Self::Listener, Self::TransportError> {
+        let mut state = self.hub.lock();
+        let port = match address.port {
+            0 => state.ephemeral_port(),
+            port if state.listeners.contains_key(&port) => return Err(MemoryError::AddressInUse(port)),
+            port => port,
+        };
+        let (sender, receiver) = unbounded_channel();
+        state.listeners.insert(port, sender.clone());
+        Ok(MemoryListener {
+            hub: self.hub.clone(),
+            address: MemoryAddress::new(port),
+            sender,
+            receiver,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod memory_tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::{MemoryAddress, MemoryError, MemoryHub, MemoryTransport, EPHEMERAL_PORT_START};
+    use crate::node::NodeState;
+    use crate::transport::connection::TransportConnection;
+    use crate::transport::listener::TransportListener;
+    use crate::transport::testing::ping_pong;
+    use crate::transport::TransportProtocol;
+
+    fn node(hub: &MemoryHub) -> NodeState<MemoryTransport> {
+        NodeState::builder()
+            .add_default_transport(MemoryTransport::with_hub(hub.clone()))
+            .add_generic_transport(MemoryTransport::with_hub(hub.clone()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn cluster_test() {
+        let hub = MemoryHub::new();
+        let nodes = [node(&hub), node(&hub), node(&hub)];
+        let mut listeners: Vec<_> = nodes.iter().enumerate().map(|(i, node)| node.listen_default(&MemoryAddress::new(i as u64 + 1)).unwrap()).collect();
+
+        for (i, node) in nodes.iter().enumerate() {
+            for (j, listener) in listeners.iter_mut().enumerate() {
+                let address = MemoryAddress::new(j as u64 + 1);
+                let mut dialer = match (i + j) % 2 {
+                    0 => node.dial_default(&address).await.unwrap(),
+                    _ => node.dial_generic::<MemoryTransport>(&address).unwrap().await.unwrap(),
+                };
+                let (mut accepted, remote) = listener.accept().await.unwrap().unwrap();
+                assert_eq!(dialer.remote_address(), Some(address));
+                assert_eq!(accepted.local_address(), Some(address));
+                assert_eq!(dialer.local_address(), Some(remote));
+                assert!(remote.port() >= EPHEMERAL_PORT_START);
+
+                dialer.write_all(&[i as u8]).await.unwrap();
+                accepted.write_all(&[j as u8]).await.unwrap();
+                let mut buf = [0_u8; 1];
+                accepted.read_exact(&mut buf).await.unwrap();
+                assert_eq!(buf, [i as u8]);
+                dialer.read_exact(&mut buf).await.unwrap();
+                assert_eq!(buf, [j as u8]);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn port_test() {
+        let hub = MemoryHub::new();
+        let transport = MemoryTransport::with_hub(hub.clone());
+        let node = node(&hub);
+        let listener = node.listen_default(&MemoryAddress::new(7)).unwrap();
+        assert_eq!(node.listen_default(&MemoryAddress::new(7)).err(), Some(MemoryError::AddressInUse(7)));
+        assert_eq!(node.dial_default(&MemoryAddress::new(8)).await.err(), Some(MemoryError::ConnectionRefused(8)));
+
+        let first = node.listen_default(&MemoryAddress::new(0)).unwrap();
+        let second = node.listen_default(&MemoryAddress::new(0)).unwrap();
+        assert_ne!(first.local_address(), second.local_address());
+
+        // Closing a listener frees the port.
+        drop(listener);
+        assert_eq!(node.dial_default(&MemoryAddress::new(7)).await.err(), Some(MemoryError::ConnectionRefused(7)));
+        let _listener = node.listen_default(&MemoryAddress::new(7)).unwrap();
+
+        // Hubs don't see each other's ports.
+        let other = MemoryTransport::with_hub(MemoryHub::new());
+        assert!(other.listen(&MemoryAddress::new(7)).is_ok());
+        assert!(transport.dial(&MemoryAddress::new(7)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn global_hub_test() {
+        let first = NodeState::builder().add_default_transport(MemoryTransport::new()).build();
+        let second = NodeState::builder().add_default_transport(MemoryTransport::default()).build();
+        let mut listener = first.listen_default(&MemoryAddress::new(0)).unwrap();
+        let address = listener.local_address().unwrap();
+        let mut dialer = second.dial_default(&address).await.unwrap();
+        let (mut accepted, _) = listener.accept().await.unwrap().unwrap();
+        ping_pong(&mut dialer, &mut accepted).await;
+    }
+}
@@ -10,11 +10,19 @@ use pin_project::pin_project;
 use crate::protocol::GenericProtocol;
 use crate::transport::address::InternalTransportIdentifier;
 use crate::transport::connection::InternalTransportConnection;
+use crate::transport::listener::InternalListener;
 
-use self::{connection::TransportConnection, address::{TransportIdentifier, GenericAddress}};
+use self::{connection::TransportConnection, address::{TransportIdentifier, GenericAddress}, listener::TransportListener};
 
 pub mod address;
 pub mod connection;
+pub mod listener;
+pub mod memory;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+/// A [`TransportProtocol::TransportFuture`] for transports that dial in an `async` block.
+pub type BoxedDial<C, E> = Pin<Box<dyn Future<Output = Result<C, E>> + Send>>;
 
 pub trait TransportProtocol: GenericProtocol + Any {
     type Connection: TransportConnection<TransportAddress = Self::TransportAddress>;
@@ -22,8 +30,16 @@ pub trait TransportProtocol: GenericProtocol + Any {
     type TransportAddress: GenericAddress;
     type TransportError: Error + Send + Sync + 'static;
     type TransportFuture: Future<Output = Result<Self::Connection, Self::TransportError>> + 'static;
+    type Listener: TransportListener<Connection = Self::Connection, TransportError = Self::TransportError>;
 
+    /// Connects to `address`.
+    ///
+    /// Transports on tokio sockets register them with the runtime of the caller, so this and
+    /// [`listen`](Self::listen) have to be called within a tokio runtime.
     fn dial(&self, address: &Self::TransportAddress) -> Self::TransportFuture;
+
+    /// Starts accepting inbound connections on `address`.
+    fn listen(&self, address: &Self::TransportAddress) -> Result<Self::Listener, Self::TransportError>;
 }
 
 #[pin_project]
@@ -62,10 +78,15 @@ impl<C: TransportConnection, E: Error + Send + Sync, F: Future<Output = Result<C
     }
 }
 
+pub(crate) type InternalDialFuture = Box<dyn Future<Output = Result<Box<dyn InternalTransportConnection>, Box<dyn Error + Send + Sync>>>>;
+
+#[allow(dead_code)]
 pub(crate) trait InternalTransportProtocol: 'static + Any + Send + Sync {
     fn transport_identifier(&self) -> Box<dyn InternalTransportIdentifier>;
 
-    fn dial(&self, address: Box<dyn Any>) -> Box<dyn Future<Output = Result<Box<dyn InternalTransportConnection>, Box<dyn Error + Send + Sync>>>>;
+    fn dial(&self, address: Box<dyn Any>) -> InternalDialFuture;
+
+    fn listen(&self, address: Box<dyn Any>) -> Result<InternalListener, Box<dyn Error + Send + Sync>>;
 
     fn as_dyn_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync + 'static>;
 }
@@ -78,16 +99,32 @@ impl<T: TransportProtocol> InternalTransportProtocol for T {
         boxed
     }
 
-    fn dial(&self, address: Box<dyn Any>) -> Box<dyn Future<Output=Result<Box<dyn InternalTransportConnection>, Box<dyn Error + Send + Sync>>>> {
+    fn dial(&self, address: Box<dyn Any>) -> InternalDialFuture {
         let any_address: Box<dyn Any> = address;
         let transport_address = any_address.downcast::<T::TransportAddress>().expect("unexpected error in generic address downcast");
 
         let m = T::dial(self, &transport_address);
         let future = InternalDial::new(m);
-        let boxed: Box<dyn Future<Output=Result<Box<dyn InternalTransportConnection>, Box<dyn Error + Send + Sync>>>> = Box::new(future);
+        let boxed: InternalDialFuture = Box::new(future);
         boxed
     }
 
+    fn listen(&self, address: Box<dyn Any>) -> Result<InternalListener, Box<dyn Error + Send + Sync>> {
+        let any_address: Box<dyn Any> = address;
+        let transport_address = any_address.downcast::<T::TransportAddress>().expect("unexpected error in generic address downcast");
+
+        match T::listen(self, &transport_address) {
+            Ok(listener) => {
+                let boxed: InternalListener = Box::new(listener);
+                Ok(boxed)
+            }
+            Err(e) => {
+                let boxed: Box<dyn Error + Send + Sync> = Box::new(e);
+                Err(boxed)
+            }
+        }
+    }
+
     fn as_dyn_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync + 'static> {
         self
     }
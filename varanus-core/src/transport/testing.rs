@@ -0,0 +1,28 @@
+//! Checks shared by the tests of the transport crates, enabled with the `testing` feature.
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::node::NodeState;
+use crate::transport::listener::TransportListener;
+use crate::transport::TransportProtocol;
+
+/// Listens on `address` with the default transport of `state`, then dials the address the
+/// listener ended up on and accepts that connection.
+pub async fn connect<Dt: TransportProtocol>(state: &NodeState<Dt>, address: &Dt::TransportAddress) -> (Dt::Listener, Dt::Connection, Dt::Connection) {
+    let mut listener = state.listen_default(address).unwrap();
+    let address = listener.local_address().unwrap();
+    let dialer = state.dial_default(&address).await.unwrap();
+    let (accepted, _) = listener.accept().await.unwrap().unwrap();
+    (listener, dialer, accepted)
+}
+
+/// Sends `ping` from `dialer` and answers with `pong` from `accepted`.
+pub async fn ping_pong<A: AsyncRead + AsyncWrite + Unpin, B: AsyncRead + AsyncWrite + Unpin>(dialer: &mut A, accepted: &mut B) {
+    dialer.write_all(b"ping").await.unwrap();
+    let mut buf = [0_u8; 4];
+    accepted.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+    accepted.write_all(b"pong").await.unwrap();
+    dialer.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"pong");
+}